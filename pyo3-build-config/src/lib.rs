@@ -138,6 +138,11 @@ fn resolve_cross_compile_config_path() -> Option<PathBuf> {
 pub fn print_feature_cfgs() {
     let rustc_minor_version = rustc_minor_version().unwrap_or(0);
 
+    // std::backtrace was stabilized in Rust 1.65
+    if rustc_minor_version >= 65 {
+        println!("cargo:rustc-cfg=rustc_has_backtrace");
+    }
+
     if rustc_minor_version >= 70 {
         println!("cargo:rustc-cfg=rustc_has_once_lock");
     }
@@ -187,6 +192,7 @@ pub fn print_expected_cfgs() {
     println!("cargo:rustc-check-cfg=cfg(pyo3_leak_on_drop_without_reference_pool)");
     println!("cargo:rustc-check-cfg=cfg(diagnostic_namespace)");
     println!("cargo:rustc-check-cfg=cfg(c_str_lit)");
+    println!("cargo:rustc-check-cfg=cfg(rustc_has_backtrace)");
     println!("cargo:rustc-check-cfg=cfg(rustc_has_once_lock)");
     println!("cargo:rustc-check-cfg=cfg(io_error_more)");
     println!("cargo:rustc-check-cfg=cfg(fn_ptr_eq)");
@@ -7,14 +7,16 @@ use syn::{
     punctuated::Punctuated,
     spanned::Spanned,
     token::Comma,
-    Attribute, Expr, ExprPath, Ident, Index, LitBool, LitStr, Member, Path, Result, Token,
+    Attribute, Expr, ExprPath, Ident, Index, LitBool, LitStr, Member, Path, Result, Token, Type,
 };
 
 pub mod kw {
+    syn::custom_keyword!(abstractmethod);
     syn::custom_keyword!(annotation);
     syn::custom_keyword!(attribute);
     syn::custom_keyword!(cancel_handle);
     syn::custom_keyword!(constructor);
+    syn::custom_keyword!(dataclass);
     syn::custom_keyword!(dict);
     syn::custom_keyword!(eq);
     syn::custom_keyword!(eq_int);
@@ -23,24 +25,33 @@ pub mod kw {
     syn::custom_keyword!(from_py_with);
     syn::custom_keyword!(frozen);
     syn::custom_keyword!(get);
+    syn::custom_keyword!(generic);
     syn::custom_keyword!(get_all);
     syn::custom_keyword!(hash);
     syn::custom_keyword!(item);
     syn::custom_keyword!(from_item_all);
+    syn::custom_keyword!(local);
+    syn::custom_keyword!(metaclass);
     syn::custom_keyword!(mapping);
     syn::custom_keyword!(module);
+    syn::custom_keyword!(multiple_interpreters);
     syn::custom_keyword!(name);
     syn::custom_keyword!(ord);
+    syn::custom_keyword!(panic_policy);
     syn::custom_keyword!(pass_module);
+    syn::custom_keyword!(pickle);
+    syn::custom_keyword!(register);
     syn::custom_keyword!(rename_all);
     syn::custom_keyword!(sequence);
     syn::custom_keyword!(set);
     syn::custom_keyword!(set_all);
     syn::custom_keyword!(signature);
+    syn::custom_keyword!(state);
     syn::custom_keyword!(str);
     syn::custom_keyword!(subclass);
     syn::custom_keyword!(submodule);
     syn::custom_keyword!(text_signature);
+    syn::custom_keyword!(traceback);
     syn::custom_keyword!(transparent);
     syn::custom_keyword!(unsendable);
     syn::custom_keyword!(weakref);
@@ -301,15 +312,112 @@ impl ToTokens for TextSignatureAttributeValue {
     }
 }
 
-pub type ExtendsAttribute = KeywordAttribute<kw::extends, Path>;
+/// A Python type, given either as a Rust type implementing `PyTypeInfo` or as the import path
+/// of a class defined in Python, such as `"enum.EnumType"`.
+#[derive(Clone, Debug)]
+pub enum TypeOrImportPath {
+    Type(Path),
+    Import(LitStr),
+}
+
+impl Parse for TypeOrImportPath {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        if input.peek(LitStr) {
+            let path: LitStr = input.parse()?;
+            ensure_spanned!(
+                path.value().contains('.'),
+                path.span() => "expected the import path of a class, such as `\"module.Class\"`"
+            );
+            Ok(TypeOrImportPath::Import(path))
+        } else {
+            input.parse().map(TypeOrImportPath::Type)
+        }
+    }
+}
+
+impl ToTokens for TypeOrImportPath {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            TypeOrImportPath::Type(path) => path.to_tokens(tokens),
+            TypeOrImportPath::Import(path) => path.to_tokens(tokens),
+        }
+    }
+}
+
+pub type ExtendsAttribute = KeywordAttribute<kw::extends, TypeOrImportPath>;
 pub type FreelistAttribute = KeywordAttribute<kw::freelist, Box<Expr>>;
+pub type MetaclassAttribute = KeywordAttribute<kw::metaclass, TypeOrImportPath>;
 pub type ModuleAttribute = KeywordAttribute<kw::module, LitStr>;
 pub type NameAttribute = KeywordAttribute<kw::name, NameLitStr>;
+pub type RegisterAttribute = KeywordAttribute<kw::register, LitStr>;
 pub type RenameAllAttribute = KeywordAttribute<kw::rename_all, RenamingRuleLitStr>;
 pub type StrFormatterAttribute = OptionalKeywordAttribute<kw::str, StringFormatter>;
 pub type TextSignatureAttribute = KeywordAttribute<kw::text_signature, TextSignatureAttributeValue>;
 pub type SubmoduleAttribute = kw::submodule;
 pub type GILUsedAttribute = KeywordAttribute<kw::gil_used, LitBool>;
+pub type StateAttribute = KeywordAttribute<kw::state, Type>;
+pub type MultipleInterpretersAttribute = KeywordAttribute<kw::multiple_interpreters, LitBool>;
+pub type PanicPolicyAttribute = KeywordAttribute<kw::panic_policy, Expr>;
+
+/// The `generic(Name = Type as Key, ...)` option of `#[pyclass]`, listing the instantiations of a
+/// generic class exposed to Python.
+#[derive(Clone, Debug)]
+pub struct GenericAttribute {
+    pub kw: kw::generic,
+    pub instances: Punctuated<GenericInstance, Comma>,
+}
+
+/// A concrete instantiation of a generic `#[pyclass]`, e.g. `IntList = List<i64> as PyInt`.
+#[derive(Clone, Debug)]
+pub struct GenericInstance {
+    /// Name of the type alias and of the Python class.
+    pub name: Ident,
+    pub ty: Type,
+    /// Python type(s) mapped to this instantiation by `__class_getitem__`.
+    pub key: Option<Type>,
+}
+
+impl Parse for GenericAttribute {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let kw = input.parse()?;
+        let content;
+        syn::parenthesized!(content in input);
+        let instances = content.parse_terminated(GenericInstance::parse, Token![,])?;
+        Ok(GenericAttribute { kw, instances })
+    }
+}
+
+impl ToTokens for GenericAttribute {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let kw = &self.kw;
+        let instances = &self.instances;
+        tokens.extend(quote!(#kw(#instances)));
+    }
+}
+
+impl Parse for GenericInstance {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let name = input.parse()?;
+        let _: Token![=] = input.parse()?;
+        let ty = input.parse()?;
+        let key = if input.parse::<Option<Token![as]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(GenericInstance { name, ty, key })
+    }
+}
+
+impl ToTokens for GenericInstance {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let GenericInstance { name, ty, key } = self;
+        tokens.extend(quote!(#name = #ty));
+        if let Some(key) = key {
+            tokens.extend(quote!(as #key));
+        }
+    }
+}
 
 impl<K: Parse + std::fmt::Debug, V: Parse> Parse for KeywordAttribute<K, V> {
     fn parse(input: ParseStream<'_>) -> Result<Self> {
@@ -351,6 +459,9 @@ impl<K: ToTokens, V: ToTokens> ToTokens for OptionalKeywordAttribute<K, V> {
 
 pub type FromPyWithAttribute = KeywordAttribute<kw::from_py_with, LitStrValue<ExprPath>>;
 
+/// The `default` or `default = <expr>` option of a `#[pyclass(dataclass)]` field.
+pub type DefaultAttribute = OptionalKeywordAttribute<Token![default], Expr>;
+
 /// For specifying the path to the pyo3 crate.
 pub type CrateAttribute = KeywordAttribute<Token![crate], LitStrValue<Path>>;
 
@@ -0,0 +1,288 @@
+//! Generation of the metadata used by `pyo3::inspect` to describe functions and classes,
+//! only emitted with the `experimental-inspect` feature.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+
+use crate::method::{FnArg, FnSpec, FnType, RegularArg};
+use crate::utils::{self, Ctx};
+
+/// Whether the introspection metadata should be generated.
+pub fn enabled() -> bool {
+    cfg!(feature = "experimental-inspect")
+}
+
+/// Returns an expression building a `pyo3::impl_::introspection::FunctionIntrospection` for `spec`.
+///
+/// `cls` is the class the function is a method of, if any.
+pub fn function_introspection(
+    spec: &FnSpec<'_>,
+    cls: Option<&syn::Type>,
+    ctx: &Ctx,
+) -> TokenStream {
+    let Ctx { pyo3_path, .. } = ctx;
+    let python_name = spec.python_name.to_string();
+    let kind = match &spec.tp {
+        FnType::Fn(_)
+        | FnType::FnInit(_)
+        | FnType::Getter(_)
+        | FnType::Setter(_)
+        | FnType::ClassAttribute => {
+            quote!(Method)
+        }
+        FnType::FnClass(_) => quote!(ClassMethod),
+        FnType::FnStatic if cls.is_some() => quote!(StaticMethod),
+        FnType::FnStatic | FnType::FnModule(_) => quote!(Function),
+        FnType::FnNew | FnType::FnNewClass(_) => quote!(Constructor),
+    };
+
+    let py_sig = &spec.signature.python_signature;
+    let mut parameters = Vec::new();
+    let mut parameter = |name: &str, kind: TokenStream, required: bool| {
+        let default = if required {
+            quote!(::std::option::Option::None)
+        } else {
+            let default = spec.signature.default_value_for_parameter(name);
+            quote!(::std::option::Option::Some(::std::borrow::Cow::Borrowed(#default)))
+        };
+        let annotation = match spec
+            .signature
+            .arguments
+            .iter()
+            .find(|arg| arg.name().unraw() == name)
+        {
+            Some(FnArg::Regular(RegularArg {
+                ty,
+                from_py_with: None,
+                ..
+            })) => type_hint(ty, Direction::Input, cls, ctx),
+            _ => quote!(#pyo3_path::inspect::types::TypeInfo::Any),
+        };
+        parameters.push(quote! {
+            ParameterInfo {
+                name: ::std::borrow::Cow::Borrowed(#name),
+                kind: ParameterKind::#kind,
+                default: #default,
+                annotation: #annotation,
+            }
+        });
+    };
+    for (i, name) in py_sig.positional_parameters.iter().enumerate() {
+        let kind = if i < py_sig.positional_only_parameters {
+            quote!(PositionalOnly)
+        } else {
+            quote!(PositionalOrKeyword)
+        };
+        parameter(name, kind, i < py_sig.required_positional_parameters);
+    }
+    if let Some(name) = &py_sig.varargs {
+        parameter(name, quote!(VarPositional), true);
+    }
+    for (name, required) in &py_sig.keyword_only_parameters {
+        parameter(name, quote!(KeywordOnly), *required);
+    }
+    if let Some(name) = &py_sig.kwargs {
+        parameter(name, quote!(VarKeyword), true);
+    }
+
+    let return_type = match (&spec.tp, cls) {
+        (FnType::FnNew | FnType::FnNewClass(_), Some(cls)) => {
+            type_hint(cls, Direction::Output, Some(cls), ctx)
+        }
+        _ => return_type_hint(&spec.output, cls, ctx),
+    };
+    let return_type = if spec.asyncness.is_some() {
+        quote!(#pyo3_path::impl_::introspection::coroutine_of(#return_type))
+    } else {
+        return_type
+    };
+
+    quote! {{
+        #[allow(unused_variables)]
+        fn introspection(
+            py: #pyo3_path::Python<'_>,
+        ) -> #pyo3_path::inspect::interface::FunctionInfo {
+            use #pyo3_path::inspect::interface::{FunctionInfo, FunctionKind, ParameterInfo, ParameterKind};
+            FunctionInfo {
+                name: ::std::borrow::Cow::Borrowed(#python_name),
+                doc: ::std::option::Option::None,
+                kind: FunctionKind::#kind,
+                parameters: ::std::vec![#(#parameters),*],
+                return_type: #return_type,
+            }
+        }
+        introspection
+    }}
+}
+
+/// Returns an expression building a `pyo3::impl_::introspection::TypeIntrospection` for the
+/// values returned by a getter.
+pub fn getter_introspection(
+    output: &syn::ReturnType,
+    cls: Option<&syn::Type>,
+    ctx: &Ctx,
+) -> TokenStream {
+    let Ctx { pyo3_path, .. } = ctx;
+    let hint = return_type_hint(output, cls, ctx);
+    quote! {{
+        #[allow(unused_variables)]
+        fn introspection(py: #pyo3_path::Python<'_>) -> #pyo3_path::inspect::types::TypeInfo {
+            #hint
+        }
+        introspection
+    }}
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Input,
+    Output,
+}
+
+/// Type hint of the value returned by a function, looking through `PyResult`.
+fn return_type_hint(output: &syn::ReturnType, cls: Option<&syn::Type>, ctx: &Ctx) -> TokenStream {
+    let Ctx { pyo3_path, .. } = ctx;
+    let ty = match output {
+        syn::ReturnType::Default => return quote!(#pyo3_path::inspect::types::TypeInfo::None),
+        syn::ReturnType::Type(_, ty) => utils::unwrap_ty_group(ty),
+    };
+    let ty = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path
+            .segments
+            .last()
+            .filter(|segment| segment.ident == "PyResult" || segment.ident == "Result")
+            .and_then(|segment| match &segment.arguments {
+                syn::PathArguments::AngleBracketed(args) => args.args.first(),
+                _ => None,
+            })
+            .and_then(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .unwrap_or(ty),
+        _ => ty,
+    };
+    type_hint(ty, Direction::Output, cls, ctx)
+}
+
+/// Returns an expression computing the `TypeInfo` of `ty`, with a `py` variable in scope.
+fn type_hint(
+    ty: &syn::Type,
+    direction: Direction,
+    cls: Option<&syn::Type>,
+    ctx: &Ctx,
+) -> TokenStream {
+    let Ctx { pyo3_path, .. } = ctx;
+    let ty = utils::unwrap_ty_group(ty);
+    if matches!(ty, syn::Type::Tuple(tuple) if tuple.elems.is_empty()) {
+        return quote!(#pyo3_path::inspect::types::TypeInfo::None);
+    }
+    if let Some(inner) = utils::option_type_argument(ty) {
+        let inner = type_hint(inner, direction, cls, ctx);
+        return quote!(#pyo3_path::inspect::types::TypeInfo::optional_of(#inner));
+    }
+    if let (Direction::Input, syn::Type::Reference(reference)) = (direction, ty) {
+        // `&T` and `&mut T` arguments are extracted from a `T` pyclass, but `&str` and slices
+        // have their own `FromPyObject` implementations.
+        if matches!(&*reference.elem, syn::Type::Path(path) if !path.path.is_ident("str")) {
+            return type_hint(&reference.elem, direction, cls, ctx);
+        }
+    }
+    let ty = match clean_type(ty, cls) {
+        Some(ty) => ty,
+        None => return quote!(#pyo3_path::inspect::types::TypeInfo::Any),
+    };
+    let method = match direction {
+        Direction::Input => quote!(input_hint),
+        Direction::Output => quote!(output_hint),
+    };
+    quote! {{
+        use #pyo3_path::impl_::introspection::{
+            AnyTypeHint as _, InputTypeHint as _, OutputTypeHint as _, PyTypeHint as _,
+        };
+        (&&#pyo3_path::impl_::introspection::TypeHint::<#ty>::new()).#method(py)
+    }}
+}
+
+/// Rewrites `ty` so that it can be named inside of a free function: `Self` is replaced by `cls`
+/// and lifetimes are elided.
+///
+/// Returns `None` for types which can't be named this way, such as `impl Trait`.
+fn clean_type(ty: &syn::Type, cls: Option<&syn::Type>) -> Option<syn::Type> {
+    let mut ty = ty.clone();
+    clean_type_in_place(&mut ty, cls).then_some(ty)
+}
+
+fn clean_type_in_place(ty: &mut syn::Type, cls: Option<&syn::Type>) -> bool {
+    match ty {
+        syn::Type::Path(type_path) => {
+            if type_path.qself.is_none() && type_path.path.is_ident("Self") {
+                return match cls {
+                    Some(cls) => {
+                        *ty = cls.clone();
+                        true
+                    }
+                    None => false,
+                };
+            }
+            if let Some(qself) = &mut type_path.qself {
+                if !clean_type_in_place(&mut qself.ty, cls) {
+                    return false;
+                }
+            }
+            if type_path
+                .path
+                .segments
+                .first()
+                .map_or(false, |s| s.ident == "Self")
+            {
+                return false;
+            }
+            type_path
+                .path
+                .segments
+                .iter_mut()
+                .all(|segment| match &mut segment.arguments {
+                    syn::PathArguments::None => true,
+                    syn::PathArguments::AngleBracketed(args) => {
+                        args.args.iter_mut().all(|arg| match arg {
+                            syn::GenericArgument::Lifetime(lifetime) => {
+                                elide_lifetime(lifetime);
+                                true
+                            }
+                            syn::GenericArgument::Type(ty) => clean_type_in_place(ty, cls),
+                            syn::GenericArgument::AssocType(assoc) => {
+                                clean_type_in_place(&mut assoc.ty, cls)
+                            }
+                            syn::GenericArgument::Const(_) => true,
+                            _ => false,
+                        })
+                    }
+                    syn::PathArguments::Parenthesized(_) => false,
+                })
+        }
+        syn::Type::Reference(reference) => {
+            if let Some(lifetime) = &mut reference.lifetime {
+                elide_lifetime(lifetime);
+            }
+            clean_type_in_place(&mut reference.elem, cls)
+        }
+        syn::Type::Tuple(tuple) => tuple
+            .elems
+            .iter_mut()
+            .all(|elem| clean_type_in_place(elem, cls)),
+        syn::Type::Array(array) => clean_type_in_place(&mut array.elem, cls),
+        syn::Type::Slice(slice) => clean_type_in_place(&mut slice.elem, cls),
+        syn::Type::Paren(paren) => clean_type_in_place(&mut paren.elem, cls),
+        syn::Type::Group(group) => clean_type_in_place(&mut group.elem, cls),
+        _ => false,
+    }
+}
+
+fn elide_lifetime(lifetime: &mut syn::Lifetime) {
+    if lifetime.ident != "static" {
+        *lifetime = syn::Lifetime::new("'_", lifetime.span());
+    }
+}
@@ -11,6 +11,7 @@ mod utils;
 mod attributes;
 mod frompyobject;
 mod intopyobject;
+mod introspection;
 mod konst;
 mod method;
 mod module;
@@ -27,5 +28,5 @@ pub use intopyobject::build_derive_into_pyobject;
 pub use module::{pymodule_function_impl, pymodule_module_impl, PyModuleOptions};
 pub use pyclass::{build_py_class, build_py_enum, PyClassArgs};
 pub use pyfunction::{build_py_function, PyFunctionOptions};
-pub use pyimpl::{build_py_methods, PyClassMethodsType};
+pub use pyimpl::{build_generic_py_methods, build_py_methods, PyClassMethodsType};
 pub use utils::get_doc;
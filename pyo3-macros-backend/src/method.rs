@@ -9,7 +9,7 @@ use syn::{ext::IdentExt, spanned::Spanned, Ident, Result};
 use crate::pyversions::is_abi3_before;
 use crate::utils::{Ctx, LitCStr};
 use crate::{
-    attributes::{FromPyWithAttribute, TextSignatureAttribute, TextSignatureAttributeValue},
+    attributes::{kw, FromPyWithAttribute, TextSignatureAttribute, TextSignatureAttributeValue},
     params::{impl_arg_params, Holders},
     pyfunction::{
         FunctionSignature, PyFunctionArgPyO3Attributes, PyFunctionOptions, SignatureAttribute,
@@ -204,6 +204,8 @@ pub enum FnType {
     FnNew,
     /// Represents a pymethod annotated with both `#[new]` and `#[classmethod]` (in either order)
     FnNewClass(Span),
+    /// Represents a pymethod annotated with `#[init]`, i.e. the `__init__` dunder.
+    FnInit(SelfType),
     /// Represents a pymethod annotated with `#[classmethod]`, like a `@classmethod`
     FnClass(Span),
     /// Represents a pyfunction or a pymethod annotated with `#[staticmethod]`, like a `@staticmethod`
@@ -222,6 +224,7 @@ impl FnType {
             | FnType::Fn(_)
             | FnType::FnClass(_)
             | FnType::FnNewClass(_)
+            | FnType::FnInit(_)
             | FnType::FnModule(_) => true,
             FnType::FnNew | FnType::FnStatic | FnType::ClassAttribute => false,
         }
@@ -234,6 +237,7 @@ impl FnType {
             | FnType::FnStatic
             | FnType::FnClass(_)
             | FnType::FnNewClass(_)
+            | FnType::FnInit(_)
             | FnType::FnModule(_) => true,
             // Setter, Getter and ClassAttribute all have fixed signatures (either take 0 or 1
             // arguments) so cannot have a `signature = (...)` attribute.
@@ -250,7 +254,7 @@ impl FnType {
     ) -> Option<TokenStream> {
         let Ctx { pyo3_path, .. } = ctx;
         match self {
-            FnType::Getter(st) | FnType::Setter(st) | FnType::Fn(st) => {
+            FnType::Getter(st) | FnType::Setter(st) | FnType::Fn(st) | FnType::FnInit(st) => {
                 let mut receiver = st.receiver(
                     cls.expect("no class given for Fn with a \"self\" receiver"),
                     error_mode,
@@ -374,10 +378,11 @@ impl SelfType {
 /// Determines which CPython calling convention a given FnSpec uses.
 #[derive(Clone, Debug)]
 pub enum CallingConvention {
-    Noargs,   // METH_NOARGS
-    Varargs,  // METH_VARARGS | METH_KEYWORDS
+    Noargs,          // METH_NOARGS
+    Varargs,         // METH_VARARGS | METH_KEYWORDS
     Fastcall, // METH_FASTCALL | METH_KEYWORDS (not compatible with `abi3` feature before 3.10)
     TpNew,    // special convention for tp_new
+    TpNewVectorcall, // special convention for tp_vectorcall of the type, alongside tp_new
 }
 
 impl CallingConvention {
@@ -410,8 +415,54 @@ pub struct FnSpec<'a> {
     pub signature: FunctionSignature<'a>,
     pub convention: CallingConvention,
     pub text_signature: Option<TextSignatureAttribute>,
+    pub traceback: Option<kw::traceback>,
+    pub local: Option<kw::local>,
     pub asyncness: Option<syn::Token![async]>,
     pub unsafety: Option<syn::Token![unsafe]>,
+    pub output: syn::ReturnType,
+}
+
+/// Return type of a function returning a Rust stream, converted into a Python asynchronous
+/// iterator.
+enum StreamReturn {
+    /// `impl Stream<Item = Result<T, E>>`
+    Stream,
+    /// `PyResult<impl Stream<Item = Result<T, E>>>` or `Result<impl Stream<...>, E>`
+    Result,
+}
+
+fn stream_return(output: &syn::ReturnType) -> Option<StreamReturn> {
+    fn is_impl_stream(ty: &syn::Type) -> bool {
+        match utils::unwrap_ty_group(ty) {
+            syn::Type::ImplTrait(impl_trait) => impl_trait.bounds.iter().any(|bound| {
+                matches!(bound, syn::TypeParamBound::Trait(bound)
+                    if bound.path.segments.last().map_or(false, |seg| seg.ident == "Stream"))
+            }),
+            _ => false,
+        }
+    }
+
+    let ty = match output {
+        syn::ReturnType::Default => return None,
+        syn::ReturnType::Type(_, ty) => utils::unwrap_ty_group(ty),
+    };
+    if is_impl_stream(ty) {
+        return Some(StreamReturn::Stream);
+    }
+    if let syn::Type::Path(syn::TypePath { qself: None, path }) = ty {
+        let seg = path
+            .segments
+            .last()
+            .filter(|seg| seg.ident == "PyResult" || seg.ident == "Result")?;
+        if let syn::PathArguments::AngleBracketed(args) = &seg.arguments {
+            if let Some(syn::GenericArgument::Type(ty)) = args.args.first() {
+                if is_impl_stream(ty) {
+                    return Some(StreamReturn::Result);
+                }
+            }
+        }
+    }
+    None
 }
 
 pub fn parse_method_receiver(arg: &syn::FnArg) -> Result<SelfType> {
@@ -448,6 +499,8 @@ impl<'a> FnSpec<'a> {
             text_signature,
             name,
             signature,
+            traceback,
+            local,
             ..
         } = options;
 
@@ -489,8 +542,11 @@ impl<'a> FnSpec<'a> {
             python_name,
             signature,
             text_signature,
+            traceback,
+            local,
             asyncness: sig.asyncness,
             unsafety: sig.unsafety,
+            output: sig.output.clone(),
         })
     }
 
@@ -547,6 +603,13 @@ impl<'a> FnSpec<'a> {
                 set_name_to_new()?;
                 FnType::FnNewClass(*span)
             }
+            [MethodTypeAttribute::Init(_)] => {
+                if let Some(name) = &python_name {
+                    bail_spanned!(name.span() => "`name` not allowed with `#[init]`");
+                }
+                *python_name = Some(syn::Ident::new("__init__", Span::call_site()));
+                FnType::FnInit(parse_receiver("expected receiver for `#[init]`")?)
+            }
             [MethodTypeAttribute::ClassMethod(_)] => {
                 // Add a helpful hint if the classmethod doesn't look like a classmethod
                 let span = match sig.inputs.first() {
@@ -618,6 +681,31 @@ impl<'a> FnSpec<'a> {
         ident: &proc_macro2::Ident,
         cls: Option<&syn::Type>,
         ctx: &Ctx,
+    ) -> Result<TokenStream> {
+        self.get_wrapper_function_with_convention(ident, cls, &self.convention, ctx)
+    }
+
+    /// Like [FnSpec::get_wrapper_function], but parsing the arguments with the vectorcall
+    /// convention, for slots which are also implemented with vectorcall (`__new__` and `__call__`).
+    pub fn get_vectorcall_wrapper_function(
+        &self,
+        ident: &proc_macro2::Ident,
+        cls: Option<&syn::Type>,
+        ctx: &Ctx,
+    ) -> Result<TokenStream> {
+        let convention = match self.convention {
+            CallingConvention::TpNew => CallingConvention::TpNewVectorcall,
+            _ => CallingConvention::Fastcall,
+        };
+        self.get_wrapper_function_with_convention(ident, cls, &convention, ctx)
+    }
+
+    fn get_wrapper_function_with_convention(
+        &self,
+        ident: &proc_macro2::Ident,
+        cls: Option<&syn::Type>,
+        convention: &CallingConvention,
+        ctx: &Ctx,
     ) -> Result<TokenStream> {
         let Ctx {
             pyo3_path,
@@ -638,11 +726,28 @@ impl<'a> FnSpec<'a> {
             }
         }
 
+        if let Some(local) = &self.local {
+            ensure_spanned!(self.asyncness.is_some(), local.span() => "`local` can only be used with `async fn`");
+        }
         if self.asyncness.is_some() {
             ensure_spanned!(
                 cfg!(feature = "experimental-async"),
                 self.asyncness.span() => "async functions are only supported with the `experimental-async` feature"
             );
+            if let Some(traceback) = &self.traceback {
+                bail_spanned!(traceback.span() => "`traceback` cannot be used with `async fn`");
+            }
+        }
+
+        let stream_return = stream_return(&self.output);
+        if let (Some(_), syn::ReturnType::Type(_, ty)) = (&stream_return, &self.output) {
+            ensure_spanned!(
+                cfg!(feature = "experimental-async"),
+                ty.span() => "returning `impl Stream` is only supported with the `experimental-async` feature"
+            );
+            if let Some(asyncness) = &self.asyncness {
+                bail_spanned!(asyncness.span() => "`async fn` cannot return `impl Stream`, return it from a function without `async`");
+            }
         }
 
         let rust_call = |args: Vec<TokenStream>, holders: &mut Holders| {
@@ -662,23 +767,40 @@ impl<'a> FnSpec<'a> {
                 let arg_names = (0..args.len())
                     .map(|i| format_ident!("arg_{}", i))
                     .collect::<Vec<_>>();
+                // local futures are paired with the release of the borrow of their receiver,
+                // used if they are leaked
+                let with_borrow = |borrow: TokenStream, future: TokenStream| {
+                    if self.local.is_some() {
+                        quote! { (#borrow, #future) }
+                    } else {
+                        future
+                    }
+                };
                 let future = match self.tp {
                     FnType::Fn(SelfType::Receiver { mutable: false, .. }) => {
+                        let future = with_borrow(
+                            quote!(::std::option::Option::Some(__guard.borrow_release(py))),
+                            quote! { async move { function(&__guard, #(#arg_names),*).await } },
+                        );
                         quote! {{
                             #(let #arg_names = #args;)*
                             let __guard = unsafe { #pyo3_path::impl_::coroutine::RefGuard::<#cls>::new(&#pyo3_path::impl_::pymethods::BoundRef::ref_from_ptr(py, &_slf))? };
-                            async move { function(&__guard, #(#arg_names),*).await }
+                            #future
                         }}
                     }
                     FnType::Fn(SelfType::Receiver { mutable: true, .. }) => {
+                        let future = with_borrow(
+                            quote!(::std::option::Option::Some(__guard.borrow_release(py))),
+                            quote! { async move { function(&mut __guard, #(#arg_names),*).await } },
+                        );
                         quote! {{
                             #(let #arg_names = #args;)*
                             let mut __guard = unsafe { #pyo3_path::impl_::coroutine::RefMutGuard::<#cls>::new(&#pyo3_path::impl_::pymethods::BoundRef::ref_from_ptr(py, &_slf))? };
-                            async move { function(&mut __guard, #(#arg_names),*).await }
+                            #future
                         }}
                     }
                     _ => {
-                        if let Some(self_arg) = self_arg() {
+                        let future = if let Some(self_arg) = self_arg() {
                             quote! {
                                 function(
                                     // NB #self_arg includes a comma, so none inserted here
@@ -688,15 +810,31 @@ impl<'a> FnSpec<'a> {
                             }
                         } else {
                             quote! { function(#(#args),*) }
-                        }
+                        };
+                        with_borrow(quote!(::std::option::Option::None), future)
                     }
                 };
+                let (future, new_coroutine, borrow) = if self.local.is_some() {
+                    (
+                        quote! { let (borrow, future) = #future; },
+                        quote!(new_local_coroutine),
+                        quote!(borrow,),
+                    )
+                } else {
+                    (
+                        quote! { let future = #future; },
+                        quote!(new_coroutine),
+                        quote!(),
+                    )
+                };
                 let mut call = quote! {{
-                    let future = #future;
-                    #pyo3_path::impl_::coroutine::new_coroutine(
+                    #future
+                    #pyo3_path::impl_::coroutine::#new_coroutine(
                         #pyo3_path::intern!(py, stringify!(#python_name)),
                         #qualname_prefix,
                         #throw_callback,
+                        unsafe { #pyo3_path::impl_::panic::panic_policy(py, _slf.cast()) },
+                        #borrow
                         async move {
                             let fut = future.await;
                             #pyo3_path::impl_::wrap::converter(&fut).wrap(fut)
@@ -711,16 +849,27 @@ impl<'a> FnSpec<'a> {
                     }};
                 }
                 call
-            } else if let Some(self_arg) = self_arg() {
-                quote! {
-                    function(
-                        // NB #self_arg includes a comma, so none inserted here
-                        #self_arg
-                        #(#args),*
-                    )
-                }
             } else {
-                quote! { function(#(#args),*) }
+                let call = if let Some(self_arg) = self_arg() {
+                    quote! {
+                        function(
+                            // NB #self_arg includes a comma, so none inserted here
+                            #self_arg
+                            #(#args),*
+                        )
+                    }
+                } else {
+                    quote! { function(#(#args),*) }
+                };
+                match stream_return {
+                    Some(StreamReturn::Stream) => {
+                        quote! { #pyo3_path::coroutine::AsyncIterator::new(#call) }
+                    }
+                    Some(StreamReturn::Result) => {
+                        quote! { #call.map(#pyo3_path::coroutine::AsyncIterator::new) }
+                    }
+                    None => call,
+                }
             };
 
             // We must assign the output_span to the return value of the call,
@@ -729,12 +878,16 @@ impl<'a> FnSpec<'a> {
             let ret_expr = quote! { let #ret_ident = #call; };
             let return_conversion =
                 quotes::map_result_into_ptr(quotes::ok_wrap(ret_ident.to_token_stream(), ctx), ctx);
-            quote! {
-                {
-                    #ret_expr
-                    #return_conversion
-                }
-            }
+            self.traceable(
+                quote! {
+                    {
+                        #ret_expr
+                        #return_conversion
+                    }
+                },
+                cls,
+                ctx,
+            )
         };
 
         let func_name = &self.name;
@@ -744,7 +897,7 @@ impl<'a> FnSpec<'a> {
             quote!(#func_name)
         };
 
-        Ok(match self.convention {
+        Ok(match convention {
             CallingConvention::Noargs => {
                 let mut holders = Holders::new();
                 let args = self
@@ -814,27 +967,47 @@ impl<'a> FnSpec<'a> {
                     }
                 }
             }
-            CallingConvention::TpNew => {
+            CallingConvention::TpNew | CallingConvention::TpNewVectorcall => {
+                let fastcall = matches!(convention, CallingConvention::TpNewVectorcall);
                 let mut holders = Holders::new();
-                let (arg_convert, args) = impl_arg_params(self, cls, false, &mut holders, ctx);
+                let (arg_convert, args) = impl_arg_params(self, cls, fastcall, &mut holders, ctx);
                 let self_arg = self
                     .tp
                     .self_arg(cls, ExtractErrorMode::Raise, &mut holders, ctx);
                 let call = quote_spanned! {*output_span=> #rust_name(#self_arg #(#args),*) };
                 let init_holders = holders.init_holders(ctx);
+                let initializer = self.traceable(
+                    quote! {{
+                        let result = #call;
+                        result.convert(py)
+                    }},
+                    cls,
+                    ctx,
+                );
+                let args = if fastcall {
+                    quote! {
+                        _args: *const *mut #pyo3_path::ffi::PyObject,
+                        _nargs: #pyo3_path::ffi::Py_ssize_t,
+                        _kwnames: *mut #pyo3_path::ffi::PyObject
+                    }
+                } else {
+                    quote! {
+                        _args: *mut #pyo3_path::ffi::PyObject,
+                        _kwargs: *mut #pyo3_path::ffi::PyObject
+                    }
+                };
                 quote! {
                     unsafe fn #ident(
                         py: #pyo3_path::Python<'_>,
                         _slf: *mut #pyo3_path::ffi::PyTypeObject,
-                        _args: *mut #pyo3_path::ffi::PyObject,
-                        _kwargs: *mut #pyo3_path::ffi::PyObject
+                        #args
                     ) -> #pyo3_path::PyResult<*mut #pyo3_path::ffi::PyObject> {
                         use #pyo3_path::impl_::callback::IntoPyCallbackOutput;
                         let function = #rust_name; // Shadow the function name to avoid #3017
+                        #pyo3_path::impl_::pymethods::ensure_not_abstract(py, _slf)?;
                         #arg_convert
                         #init_holders
-                        let result = #call;
-                        let initializer: #pyo3_path::PyClassInitializer::<#cls> = result.convert(py)?;
+                        let initializer: #pyo3_path::PyClassInitializer::<#cls> = #initializer?;
                         #pyo3_path::impl_::pymethods::tp_new_impl(py, initializer, _slf)
                     }
                 }
@@ -842,6 +1015,37 @@ impl<'a> FnSpec<'a> {
         })
     }
 
+    /// Wraps `body`, an expression evaluating to a `PyResult`, so that a frame for this function
+    /// is added to the traceback of its errors when `#[pyo3(traceback)]` is used.
+    fn traceable(&self, body: TokenStream, cls: Option<&syn::Type>, ctx: &Ctx) -> TokenStream {
+        let Ctx { pyo3_path, .. } = ctx;
+        if self.traceback.is_none() {
+            return body;
+        }
+        let name = self.name.unraw();
+        let function = match cls {
+            Some(cls) => format!(
+                "{}::{}",
+                cls.to_token_stream().to_string().replace(' ', ""),
+                name
+            ),
+            None => name.to_string(),
+        };
+        // `file!` and `line!` take the location of the function name
+        let location = quote_spanned! { self.name.span()=> ::std::file!(), ::std::line!() };
+        quote! {
+            unsafe {
+                #pyo3_path::impl_::trampoline::traceable(
+                    py,
+                    _slf.cast(),
+                    #function,
+                    #location,
+                    || #body,
+                )
+            }
+        }
+    }
+
     /// Return a `PyMethodDef` constructor for this function, matching the selected
     /// calling convention.
     pub fn get_methoddef(&self, wrapper: impl ToTokens, doc: &PythonDoc, ctx: &Ctx) -> TokenStream {
@@ -916,7 +1120,9 @@ impl<'a> FnSpec<'a> {
                     #doc,
                 )
             },
-            CallingConvention::TpNew => unreachable!("tp_new cannot get a methoddef"),
+            CallingConvention::TpNew | CallingConvention::TpNewVectorcall => {
+                unreachable!("tp_new cannot get a methoddef")
+            }
         }
     }
 
@@ -934,7 +1140,7 @@ impl<'a> FnSpec<'a> {
         let self_argument = match &self.tp {
             // Getters / Setters / ClassAttribute are not callables on the Python side
             FnType::Getter(_) | FnType::Setter(_) | FnType::ClassAttribute => return None,
-            FnType::Fn(_) => Some("self"),
+            FnType::Fn(_) | FnType::FnInit(_) => Some("self"),
             FnType::FnModule(_) => Some("module"),
             FnType::FnClass(_) | FnType::FnNewClass(_) => Some("cls"),
             FnType::FnStatic | FnType::FnNew => None,
@@ -950,6 +1156,7 @@ impl<'a> FnSpec<'a> {
 
 enum MethodTypeAttribute {
     New(Span),
+    Init(Span),
     ClassMethod(Span),
     StaticMethod(Span),
     Getter(Span, Option<Ident>),
@@ -961,6 +1168,7 @@ impl MethodTypeAttribute {
     fn span(&self) -> Span {
         match self {
             MethodTypeAttribute::New(span)
+            | MethodTypeAttribute::Init(span)
             | MethodTypeAttribute::ClassMethod(span)
             | MethodTypeAttribute::StaticMethod(span)
             | MethodTypeAttribute::Getter(span, _)
@@ -1018,6 +1226,9 @@ impl MethodTypeAttribute {
         if path.is_ident("new") {
             ensure_no_arguments(meta, "new")?;
             Ok(Some(MethodTypeAttribute::New(path.span())))
+        } else if path.is_ident("init") {
+            ensure_no_arguments(meta, "init")?;
+            Ok(Some(MethodTypeAttribute::Init(path.span())))
         } else if path.is_ident("classmethod") {
             ensure_no_arguments(meta, "classmethod")?;
             Ok(Some(MethodTypeAttribute::ClassMethod(path.span())))
@@ -1043,6 +1254,7 @@ impl Display for MethodTypeAttribute {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             MethodTypeAttribute::New(_) => "#[new]".fmt(f),
+            MethodTypeAttribute::Init(_) => "#[init]".fmt(f),
             MethodTypeAttribute::ClassMethod(_) => "#[classmethod]".fmt(f),
             MethodTypeAttribute::StaticMethod(_) => "#[staticmethod]".fmt(f),
             MethodTypeAttribute::Getter(_, _) => "#[getter]".fmt(f),
@@ -1106,6 +1318,9 @@ fn ensure_signatures_on_valid_method(
             FnType::ClassAttribute => {
                 bail_spanned!(text_signature.kw.span() => "`text_signature` not allowed with `classattr`")
             }
+            FnType::FnInit(_) => {
+                bail_spanned!(text_signature.kw.span() => "`text_signature` not allowed with `init`")
+            }
             _ => {}
         }
     }
@@ -3,7 +3,8 @@
 use crate::{
     attributes::{
         self, kw, take_attributes, take_pyo3_options, CrateAttribute, GILUsedAttribute,
-        ModuleAttribute, NameAttribute, SubmoduleAttribute,
+        ModuleAttribute, MultipleInterpretersAttribute, NameAttribute, PanicPolicyAttribute,
+        StateAttribute, SubmoduleAttribute,
     },
     get_doc,
     pyclass::PyClassPyO3Option,
@@ -11,7 +12,7 @@ use crate::{
     utils::{has_attribute, has_attribute_with_namespace, Ctx, IdentOrStr, LitCStr},
 };
 use proc_macro2::{Span, TokenStream};
-use quote::quote;
+use quote::{quote, quote_spanned};
 use std::ffi::CString;
 use syn::{
     ext::IdentExt,
@@ -30,6 +31,9 @@ pub struct PyModuleOptions {
     module: Option<ModuleAttribute>,
     submodule: Option<kw::submodule>,
     gil_used: Option<GILUsedAttribute>,
+    state: Option<StateAttribute>,
+    multiple_interpreters: Option<MultipleInterpretersAttribute>,
+    panic_policy: Option<PanicPolicyAttribute>,
 }
 
 impl Parse for PyModuleOptions {
@@ -76,6 +80,11 @@ impl PyModuleOptions {
                 PyModulePyO3Option::GILUsed(gil_used) => {
                     set_option!(gil_used)
                 }
+                PyModulePyO3Option::State(state) => set_option!(state),
+                PyModulePyO3Option::MultipleInterpreters(multiple_interpreters) => {
+                    set_option!(multiple_interpreters)
+                }
+                PyModulePyO3Option::PanicPolicy(panic_policy) => set_option!(panic_policy),
             }
         }
         Ok(())
@@ -106,7 +115,8 @@ pub fn pymodule_module_impl(
     let doc = get_doc(attrs, None, ctx);
     let name = options
         .name
-        .map_or_else(|| ident.unraw(), |name| name.value.0);
+        .as_ref()
+        .map_or_else(|| ident.unraw(), |name| name.value.0.clone());
     let full_name = if let Some(module) = &options.module {
         format!("{}.{}", module.value.value(), name)
     } else {
@@ -337,6 +347,7 @@ pub fn pymodule_module_impl(
         }
     }
 
+    let def_options = module_def_options(&options);
     let module_def = quote! {{
         use #pyo3_path::impl_::pymodule as impl_;
         const INITIALIZER: impl_::ModuleInitializer = impl_::ModuleInitializer(__pyo3_pymodule);
@@ -345,7 +356,7 @@ pub fn pymodule_module_impl(
                 __PYO3_NAME,
                 #doc,
                 INITIALIZER
-            )
+            )#def_options
         }
     }};
     let initialization = module_initialization(
@@ -389,9 +400,11 @@ pub fn pymodule_function_impl(
     let ident = &function.sig.ident;
     let name = options
         .name
-        .map_or_else(|| ident.unraw(), |name| name.value.0);
+        .as_ref()
+        .map_or_else(|| ident.unraw(), |name| name.value.0.clone());
     let vis = &function.vis;
     let doc = get_doc(&function.attrs, None, ctx);
+    let def_options = module_def_options(&options);
 
     let initialization = module_initialization(
         &name,
@@ -432,13 +445,35 @@ pub fn pymodule_function_impl(
                         #ident::__PYO3_NAME,
                         #doc,
                         INITIALIZER
-                    )
+                    )#def_options
                 }
             }
         }
     })
 }
 
+/// Builder calls applying the `state`, `multiple_interpreters` and `panic_policy` options to the
+/// `ModuleDef`.
+fn module_def_options(options: &PyModuleOptions) -> TokenStream {
+    let mut def_options = TokenStream::new();
+    if let Some(state) = &options.state {
+        let ty = &state.value;
+        def_options.extend(quote_spanned! { ty.span() => .with_state::<#ty>() });
+    }
+    if options
+        .multiple_interpreters
+        .as_ref()
+        .map_or(false, |op| op.value.value)
+    {
+        def_options.extend(quote! { .with_multiple_interpreters() });
+    }
+    if let Some(panic_policy) = &options.panic_policy {
+        let policy = &panic_policy.value;
+        def_options.extend(quote_spanned! { policy.span() => .with_panic_policy(#policy) });
+    }
+    def_options
+}
+
 fn module_initialization(
     name: &syn::Ident,
     ctx: &Ctx,
@@ -469,7 +504,7 @@ fn module_initialization(
             #[doc(hidden)]
             #[export_name = #pyinit_symbol]
             pub unsafe extern "C" fn __pyo3_init() -> *mut #pyo3_path::ffi::PyObject {
-                unsafe { #pyo3_path::impl_::trampoline::module_init(|py| _PYO3_DEF.make_module(py, #gil_used)) }
+                unsafe { _PYO3_DEF.module_init(#gil_used) }
             }
         });
     }
@@ -617,6 +652,9 @@ enum PyModulePyO3Option {
     Name(NameAttribute),
     Module(ModuleAttribute),
     GILUsed(GILUsedAttribute),
+    State(StateAttribute),
+    MultipleInterpreters(MultipleInterpretersAttribute),
+    PanicPolicy(PanicPolicyAttribute),
 }
 
 impl Parse for PyModulePyO3Option {
@@ -632,6 +670,12 @@ impl Parse for PyModulePyO3Option {
             input.parse().map(PyModulePyO3Option::Submodule)
         } else if lookahead.peek(attributes::kw::gil_used) {
             input.parse().map(PyModulePyO3Option::GILUsed)
+        } else if lookahead.peek(attributes::kw::state) {
+            input.parse().map(PyModulePyO3Option::State)
+        } else if lookahead.peek(attributes::kw::multiple_interpreters) {
+            input.parse().map(PyModulePyO3Option::MultipleInterpreters)
+        } else if lookahead.peek(attributes::kw::panic_policy) {
+            input.parse().map(PyModulePyO3Option::PanicPolicy)
         } else {
             Err(lookahead.error())
         }
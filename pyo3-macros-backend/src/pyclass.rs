@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::ffi::CString;
 use std::fmt::Debug;
 
 use proc_macro2::{Ident, Span, TokenStream};
@@ -10,19 +11,20 @@ use syn::{parse_quote, parse_quote_spanned, spanned::Spanned, ImplItemFn, Result
 
 use crate::attributes::kw::frozen;
 use crate::attributes::{
-    self, kw, take_pyo3_options, CrateAttribute, ErrorCombiner, ExtendsAttribute,
-    FreelistAttribute, ModuleAttribute, NameAttribute, NameLitStr, RenameAllAttribute,
-    StrFormatterAttribute,
+    self, kw, take_pyo3_options, CrateAttribute, DefaultAttribute, ErrorCombiner, ExtendsAttribute,
+    FreelistAttribute, GenericAttribute, MetaclassAttribute, ModuleAttribute, NameAttribute,
+    NameLitStr, RegisterAttribute, RenameAllAttribute, StrFormatterAttribute, TypeOrImportPath,
 };
+use crate::introspection;
 use crate::konst::{ConstAttributes, ConstSpec};
 use crate::method::{FnArg, FnSpec, PyArg, RegularArg};
-use crate::pyfunction::ConstructorAttribute;
+use crate::pyfunction::{ConstructorAttribute, PythonSignature};
 use crate::pyimpl::{gen_py_const, get_cfg_attributes, PyClassMethodsType};
 use crate::pymethod::{
     impl_py_getter_def, impl_py_setter_def, MethodAndMethodDef, MethodAndSlotDef, PropertyType,
     SlotDef, __GETITEM__, __HASH__, __INT__, __LEN__, __REPR__, __RICHCMP__, __STR__,
 };
-use crate::pyversions::is_abi3_before;
+use crate::pyversions::{has_pyclass_vectorcall, is_abi3_before, is_python_before};
 use crate::utils::{self, apply_renaming_rule, Ctx, LitCStr, PythonDoc};
 use crate::PyFunctionOptions;
 
@@ -60,6 +62,8 @@ impl PyClassArgs {
 #[derive(Clone, Default)]
 pub struct PyClassPyO3Options {
     pub krate: Option<CrateAttribute>,
+    pub abstract_: Option<Token![abstract]>,
+    pub dataclass: Option<kw::dataclass>,
     pub dict: Option<kw::dict>,
     pub eq: Option<kw::eq>,
     pub eq_int: Option<kw::eq_int>,
@@ -67,11 +71,15 @@ pub struct PyClassPyO3Options {
     pub get_all: Option<kw::get_all>,
     pub freelist: Option<FreelistAttribute>,
     pub frozen: Option<kw::frozen>,
+    pub generic: Option<GenericAttribute>,
     pub hash: Option<kw::hash>,
     pub mapping: Option<kw::mapping>,
+    pub metaclass: Option<MetaclassAttribute>,
     pub module: Option<ModuleAttribute>,
     pub name: Option<NameAttribute>,
     pub ord: Option<kw::ord>,
+    pub pickle: Option<kw::pickle>,
+    pub register: Option<RegisterAttribute>,
     pub rename_all: Option<RenameAllAttribute>,
     pub sequence: Option<kw::sequence>,
     pub set_all: Option<kw::set_all>,
@@ -82,19 +90,25 @@ pub struct PyClassPyO3Options {
 }
 
 pub enum PyClassPyO3Option {
+    Abstract(Token![abstract]),
     Crate(CrateAttribute),
+    Dataclass(kw::dataclass),
     Dict(kw::dict),
     Eq(kw::eq),
     EqInt(kw::eq_int),
     Extends(ExtendsAttribute),
     Freelist(FreelistAttribute),
     Frozen(kw::frozen),
+    Generic(GenericAttribute),
     GetAll(kw::get_all),
     Hash(kw::hash),
     Mapping(kw::mapping),
+    Metaclass(MetaclassAttribute),
     Module(ModuleAttribute),
     Name(NameAttribute),
     Ord(kw::ord),
+    Pickle(kw::pickle),
+    Register(RegisterAttribute),
     RenameAll(RenameAllAttribute),
     Sequence(kw::sequence),
     SetAll(kw::set_all),
@@ -107,8 +121,12 @@ pub enum PyClassPyO3Option {
 impl Parse for PyClassPyO3Option {
     fn parse(input: ParseStream<'_>) -> Result<Self> {
         let lookahead = input.lookahead1();
-        if lookahead.peek(Token![crate]) {
+        if lookahead.peek(Token![abstract]) {
+            input.parse().map(PyClassPyO3Option::Abstract)
+        } else if lookahead.peek(Token![crate]) {
             input.parse().map(PyClassPyO3Option::Crate)
+        } else if lookahead.peek(kw::dataclass) {
+            input.parse().map(PyClassPyO3Option::Dataclass)
         } else if lookahead.peek(kw::dict) {
             input.parse().map(PyClassPyO3Option::Dict)
         } else if lookahead.peek(kw::eq) {
@@ -121,18 +139,26 @@ impl Parse for PyClassPyO3Option {
             input.parse().map(PyClassPyO3Option::Freelist)
         } else if lookahead.peek(attributes::kw::frozen) {
             input.parse().map(PyClassPyO3Option::Frozen)
+        } else if lookahead.peek(attributes::kw::generic) {
+            input.parse().map(PyClassPyO3Option::Generic)
         } else if lookahead.peek(attributes::kw::get_all) {
             input.parse().map(PyClassPyO3Option::GetAll)
         } else if lookahead.peek(attributes::kw::hash) {
             input.parse().map(PyClassPyO3Option::Hash)
         } else if lookahead.peek(attributes::kw::mapping) {
             input.parse().map(PyClassPyO3Option::Mapping)
+        } else if lookahead.peek(attributes::kw::metaclass) {
+            input.parse().map(PyClassPyO3Option::Metaclass)
         } else if lookahead.peek(attributes::kw::module) {
             input.parse().map(PyClassPyO3Option::Module)
         } else if lookahead.peek(kw::name) {
             input.parse().map(PyClassPyO3Option::Name)
         } else if lookahead.peek(attributes::kw::ord) {
             input.parse().map(PyClassPyO3Option::Ord)
+        } else if lookahead.peek(attributes::kw::pickle) {
+            input.parse().map(PyClassPyO3Option::Pickle)
+        } else if lookahead.peek(attributes::kw::register) {
+            input.parse().map(PyClassPyO3Option::Register)
         } else if lookahead.peek(kw::rename_all) {
             input.parse().map(PyClassPyO3Option::RenameAll)
         } else if lookahead.peek(attributes::kw::sequence) {
@@ -186,7 +212,15 @@ impl PyClassPyO3Options {
         }
 
         match option {
+            PyClassPyO3Option::Abstract(abstract_) => {
+                ensure_spanned!(
+                    self.abstract_.is_none(),
+                    abstract_.span() => "`abstract` may only be specified once"
+                );
+                self.abstract_ = Some(abstract_);
+            }
             PyClassPyO3Option::Crate(krate) => set_option!(krate),
+            PyClassPyO3Option::Dataclass(dataclass) => set_option!(dataclass),
             PyClassPyO3Option::Dict(dict) => {
                 ensure_spanned!(
                     !is_abi3_before(3, 9),
@@ -199,12 +233,28 @@ impl PyClassPyO3Options {
             PyClassPyO3Option::Extends(extends) => set_option!(extends),
             PyClassPyO3Option::Freelist(freelist) => set_option!(freelist),
             PyClassPyO3Option::Frozen(frozen) => set_option!(frozen),
+            PyClassPyO3Option::Generic(generic) => set_option!(generic),
             PyClassPyO3Option::GetAll(get_all) => set_option!(get_all),
             PyClassPyO3Option::Hash(hash) => set_option!(hash),
             PyClassPyO3Option::Mapping(mapping) => set_option!(mapping),
+            PyClassPyO3Option::Metaclass(metaclass) => {
+                ensure_spanned!(
+                    !is_python_before(3, 12),
+                    metaclass.span() => "`metaclass` requires Python >= 3.12"
+                );
+                set_option!(metaclass);
+            }
             PyClassPyO3Option::Module(module) => set_option!(module),
             PyClassPyO3Option::Name(name) => set_option!(name),
             PyClassPyO3Option::Ord(ord) => set_option!(ord),
+            PyClassPyO3Option::Pickle(pickle) => set_option!(pickle),
+            PyClassPyO3Option::Register(register) => {
+                ensure_spanned!(
+                    register.value.value().contains('.'),
+                    register.value.span() => "expected the path of an abstract base class, such as `\"collections.abc.Sequence\"`"
+                );
+                set_option!(register);
+            }
             PyClassPyO3Option::RenameAll(rename_all) => set_option!(rename_all),
             PyClassPyO3Option::Sequence(sequence) => set_option!(sequence),
             PyClassPyO3Option::SetAll(set_all) => set_option!(set_all),
@@ -243,13 +293,38 @@ pub fn build_py_class(
     }
 
     ensure_spanned!(
-        class.generics.params.is_empty(),
+        class.generics.params.is_empty() || args.options.generic.is_some(),
         class.generics.span() => concat!(
-            "#[pyclass] cannot have generic parameters. For an explanation, see \
+            "#[pyclass] cannot have generic parameters, unless its instantiations are listed with \
+            `generic(...)`. For an explanation, see \
             https://pyo3.rs/v", env!("CARGO_PKG_VERSION"), "/class.html#no-generic-parameters"
         )
     );
 
+    if let Some(abstract_) = args.options.abstract_ {
+        // Abstract classes are meant to be subclassed
+        args.options
+            .subclass
+            .get_or_insert(kw::subclass(abstract_.span));
+    }
+
+    if let Some(TypeOrImportPath::Import(_)) = args.options.extends.as_ref().map(|e| &e.value) {
+        // Python classes manage their own `__dict__` and `__weakref__`
+        if let Some(dict) = &args.options.dict {
+            bail_spanned!(dict.span() => "`dict` cannot be used when extending a Python class");
+        }
+        if let Some(weakref) = &args.options.weakref {
+            bail_spanned!(weakref.span() => "`weakref` cannot be used when extending a Python class");
+        }
+    }
+
+    if let Some(dataclass) = &args.options.dataclass {
+        ensure_spanned!(
+            matches!(class.fields, syn::Fields::Named(_)),
+            dataclass.span() => "`dataclass` requires a struct with named fields"
+        );
+    }
+
     let mut all_errors = ErrorCombiner(None);
 
     let mut field_options: Vec<(&syn::Field, FieldPyO3Options)> = match &mut class.fields {
@@ -309,9 +384,46 @@ pub fn build_py_class(
         }
     }
 
-    impl_class(&class.ident, &args, doc, field_options, methods_type, ctx)
+    if let Some(dataclass) = args.options.dataclass {
+        // Like Python dataclasses, all fields are readable attributes compared by `__eq__`
+        for (_, FieldPyO3Options { get, .. }) in &mut field_options {
+            get.get_or_insert(Annotated::Struct(kw::get_all(dataclass.span)));
+        }
+        args.options.eq.get_or_insert(kw::eq(dataclass.span));
+    } else if let Some(default) = field_options
+        .iter()
+        .find_map(|(_, options)| options.default.as_ref())
+    {
+        bail_spanned!(default.kw.span() => "`default` requires the `dataclass` option");
+    }
+
+    if let Some(generic) = &args.options.generic {
+        let class = (&class.ident, &class.vis, &class.generics);
+        return impl_generic_class(class, generic, &args, doc, field_options, methods_type, ctx);
+    }
+
+    // The generated constructor cannot initialize a base `#[pyclass]`, only native types
+    let dataclass_base_assertion = match (&args.options.dataclass, &args.options.extends) {
+        (Some(_), Some(extends)) => match &extends.value {
+            TypeOrImportPath::Type(base) => {
+                let pyo3_path = &ctx.pyo3_path;
+                let assert = quote_spanned! { base.span() =>
+                    #pyo3_path::impl_::pyclass::assert_dataclass_base::<#base>();
+                };
+                quote! { const _: () = { #assert }; }
+            }
+            TypeOrImportPath::Import(_) => TokenStream::new(),
+        },
+        _ => TokenStream::new(),
+    };
+    let class_impl = impl_class(&class.ident, &args, doc, field_options, methods_type, ctx)?;
+    Ok(quote! {
+        #dataclass_base_assertion
+        #class_impl
+    })
 }
 
+#[derive(Clone)]
 enum Annotated<X, Y> {
     Field(X),
     Struct(Y),
@@ -327,16 +439,19 @@ impl<X: Spanned, Y: Spanned> Annotated<X, Y> {
 }
 
 /// `#[pyo3()]` options for pyclass fields
+#[derive(Clone)]
 struct FieldPyO3Options {
     get: Option<Annotated<kw::get, kw::get_all>>,
     set: Option<Annotated<kw::set, kw::set_all>>,
     name: Option<NameAttribute>,
+    default: Option<DefaultAttribute>,
 }
 
 enum FieldPyO3Option {
     Get(attributes::kw::get),
     Set(attributes::kw::set),
     Name(NameAttribute),
+    Default(DefaultAttribute),
 }
 
 impl Parse for FieldPyO3Option {
@@ -348,6 +463,8 @@ impl Parse for FieldPyO3Option {
             input.parse().map(FieldPyO3Option::Set)
         } else if lookahead.peek(attributes::kw::name) {
             input.parse().map(FieldPyO3Option::Name)
+        } else if lookahead.peek(Token![default]) {
+            input.parse().map(FieldPyO3Option::Default)
         } else {
             Err(lookahead.error())
         }
@@ -360,6 +477,7 @@ impl FieldPyO3Options {
             get: None,
             set: None,
             name: None,
+            default: None,
         };
 
         for option in take_pyo3_options(attrs)? {
@@ -379,6 +497,11 @@ impl FieldPyO3Options {
                         return Err(syn::Error::new(options.name.span(), UNIQUE_NAME));
                     }
                 }
+                FieldPyO3Option::Default(default) => {
+                    if options.default.replace(default).is_some() {
+                        return Err(syn::Error::new(options.default.span(), UNIQUE_DEFAULT));
+                    }
+                }
             }
         }
 
@@ -424,26 +547,35 @@ fn impl_class(
     let (default_hash, default_hash_slot) =
         pyclass_hash(&args.options, &syn::parse_quote!(#cls), ctx)?;
 
+    let (dataclass_impl, dataclass_methods, dataclass_slots) = match args.options.dataclass {
+        Some(_) => impl_dataclass(cls, args, &field_options, ctx)?,
+        None => Default::default(),
+    };
+
+    let (pickle_impl, pickle_methods) = match &args.options.pickle {
+        Some(_) => impl_pickle(cls, &field_options, ctx)?,
+        None => Default::default(),
+    };
+
     let mut slots = Vec::new();
     slots.extend(default_richcmp_slot);
     slots.extend(default_hash_slot);
     slots.extend(default_str_slot);
+    slots.extend(dataclass_slots);
 
-    let py_class_impl = PyClassImplsBuilder::new(
+    let mut default_methods = descriptors_to_items(
         cls,
-        args,
-        methods_type,
-        descriptors_to_items(
-            cls,
-            args.options.rename_all.as_ref(),
-            args.options.frozen,
-            field_options,
-            ctx,
-        )?,
-        slots,
-    )
-    .doc(doc)
-    .impl_all(ctx)?;
+        args.options.rename_all.as_ref(),
+        args.options.frozen,
+        field_options,
+        ctx,
+    )?;
+    default_methods.extend(dataclass_methods);
+    default_methods.extend(pickle_methods);
+
+    let py_class_impl = PyClassImplsBuilder::new(cls, args, methods_type, default_methods, slots)
+        .doc(doc)
+        .impl_all(ctx)?;
 
     Ok(quote! {
         impl #pyo3_path::types::DerefToPyAny for #cls {}
@@ -458,10 +590,557 @@ fn impl_class(
             #default_richcmp
             #default_hash
             #default_str
+            #dataclass_impl
         }
+
+        #pickle_impl
     })
 }
 
+/// Implements the methods generated by `#[pyclass(dataclass)]`: a constructor taking the fields
+/// as arguments, `__repr__`, `__match_args__` and `__replace__` (used by `copy.replace`).
+///
+/// `__eq__` and the field getters are enabled by `build_py_class` through the `eq` and `get`
+/// options.
+fn impl_dataclass(
+    cls: &syn::Ident,
+    args: &PyClassArgs,
+    field_options: &[(&syn::Field, FieldPyO3Options)],
+    ctx: &Ctx,
+) -> Result<(TokenStream, Vec<MethodAndMethodDef>, Vec<MethodAndSlotDef>)> {
+    let Ctx { pyo3_path, .. } = ctx;
+    let cls_type: syn::Type = parse_quote!(#cls);
+    let rename_all = args.options.rename_all.as_ref();
+
+    let mut arguments = Vec::new();
+    let mut python_signature = PythonSignature::default();
+    let mut field_idents = Vec::new();
+    let mut field_values = Vec::new();
+    for (field, options) in field_options {
+        // `build_py_class` checked that the fields are named
+        let ident = field.ident.as_ref().unwrap();
+        let python_name = match &options.name {
+            Some(name) => name.value.0.to_string(),
+            None => {
+                let name = ident.unraw().to_string();
+                match rename_all {
+                    Some(rename_all) => apply_renaming_rule(rename_all.value.rule, &name),
+                    None => name,
+                }
+            }
+        };
+
+        let default_value = options
+            .default
+            .as_ref()
+            .map(|default| match &default.value {
+                Some(value) => value.clone(),
+                None => {
+                    parse_quote_spanned!(default.kw.span() => ::std::default::Default::default())
+                }
+            });
+        if default_value.is_none() {
+            ensure_spanned!(
+                python_signature.required_positional_parameters
+                    == python_signature.positional_parameters.len(),
+                ident.span() => format!("field `{}` without a default cannot follow fields with defaults", ident)
+            );
+            python_signature.required_positional_parameters += 1;
+        }
+        // Name the argument after the Python attribute, so that it appears in error messages and
+        // in the text signature with its default value
+        let name = match syn::parse::Parser::parse_str(Ident::parse_any, &python_name) {
+            Ok(mut name) => {
+                name.set_span(ident.span());
+                Cow::Owned(name)
+            }
+            Err(_) => Cow::Borrowed(ident),
+        };
+        python_signature.positional_parameters.push(python_name);
+        arguments.push(FnArg::Regular(RegularArg {
+            name,
+            ty: &field.ty,
+            from_py_with: None,
+            default_value,
+            option_wrapped_type: None,
+        }));
+
+        let ty = &field.ty;
+        field_idents.push(ident);
+        field_values.push(quote! {
+            #pyo3_path::impl_::pyclass::ConvertField::<
+                { #pyo3_path::impl_::pyclass::IsIntoPyObjectRef::<#ty>::VALUE },
+                { #pyo3_path::impl_::pyclass::IsIntoPyObject::<#ty>::VALUE },
+            >::convert_field::<#ty>(&this.#ident, py)?
+        });
+    }
+    let field_types = field_options.iter().map(|(field, _)| &field.ty);
+    let python_names = python_signature.positional_parameters.clone();
+
+    let spec = FnSpec {
+        tp: crate::method::FnType::FnNew,
+        name: &format_ident!("__pymethod_constructor__"),
+        python_name: format_ident!("__new__"),
+        signature: crate::pyfunction::FunctionSignature {
+            arguments,
+            python_signature,
+            attribute: None,
+        },
+        convention: crate::method::CallingConvention::TpNew,
+        text_signature: None,
+        traceback: None,
+        local: None,
+        asyncness: None,
+        unsafety: None,
+        output: syn::ReturnType::Default,
+    };
+    let constructor = crate::pymethod::impl_py_method_def_new(&cls_type, &spec, ctx)?;
+
+    let repr_items =
+        python_names
+            .iter()
+            .zip(&field_values)
+            .enumerate()
+            .map(|(i, (name, value))| {
+                let prefix = if i == 0 {
+                    format!("{}=", name)
+                } else {
+                    format!(", {}=", name)
+                };
+                quote! {
+                    repr.push_str(#prefix);
+                    repr.push_str(&#pyo3_path::types::PyStringMethods::to_cow(
+                        &#pyo3_path::types::PyAnyMethods::repr(#value.bind(py))?
+                    )?);
+                }
+            });
+    let mut repr_impl: syn::ImplItemFn = parse_quote! {
+        fn __pyo3__generated____repr__(
+            slf: &#pyo3_path::Bound<'_, Self>,
+        ) -> #pyo3_path::PyResult<::std::string::String> {
+            #[allow(unused_imports)]
+            use #pyo3_path::impl_::pyclass::Probe;
+            let py = slf.py();
+            let this = slf.try_borrow()?;
+            let mut repr = #pyo3_path::types::PyTypeMethods::qualname(
+                &#pyo3_path::types::PyAnyMethods::get_type(slf.as_any())
+            )?
+            .to_string();
+            repr.push('(');
+            #(#repr_items)*
+            repr.push(')');
+            ::std::result::Result::Ok(repr)
+        }
+    };
+    let repr_slot = generate_default_protocol_slot(&cls_type, &mut repr_impl, &__REPR__, ctx)?;
+
+    let (match_args, match_args_const_impl) = impl_match_args(ctx, &cls_type, &python_names);
+
+    let mut replace_impl: syn::ImplItemFn = parse_quote! {
+        #[pyo3(signature = (**changes))]
+        fn __replace__<'py>(
+            slf: &#pyo3_path::Bound<'py, Self>,
+            changes: ::std::option::Option<&#pyo3_path::Bound<'py, #pyo3_path::types::PyDict>>,
+        ) -> #pyo3_path::PyResult<#pyo3_path::Bound<'py, #pyo3_path::PyAny>> {
+            #[allow(unused_imports)]
+            use #pyo3_path::impl_::pyclass::Probe;
+            let py = slf.py();
+            let kwargs = #pyo3_path::types::PyDict::new(py);
+            {
+                let this = slf.try_borrow()?;
+                #(#pyo3_path::types::PyDictMethods::set_item(&kwargs, #python_names, #field_values)?;)*
+            }
+            if let ::std::option::Option::Some(changes) = changes {
+                #pyo3_path::types::PyDictMethods::update(
+                    &kwargs,
+                    #pyo3_path::types::PyDictMethods::as_mapping(changes),
+                )?;
+            }
+            #pyo3_path::types::PyAnyMethods::call(
+                #pyo3_path::types::PyAnyMethods::get_type(slf.as_any()).as_any(),
+                (),
+                ::std::option::Option::Some(&kwargs),
+            )
+        }
+    };
+    let replace_options = PyFunctionOptions::from_attrs(&mut replace_impl.attrs)?;
+    let replace = match crate::pymethod::gen_py_method(
+        &cls_type,
+        &mut replace_impl.sig,
+        &mut replace_impl.attrs,
+        replace_options,
+        ctx,
+    )? {
+        crate::pymethod::GeneratedPyMethod::Method(method) => method,
+        _ => unreachable!("`__replace__` is a regular method"),
+    };
+
+    let dataclass_impl = quote! {
+        fn __pymethod_constructor__(#(#field_idents: #field_types),*) -> Self {
+            Self { #(#field_idents),* }
+        }
+
+        #repr_impl
+
+        #match_args_const_impl
+
+        #replace_impl
+    };
+
+    Ok((
+        dataclass_impl,
+        vec![match_args, replace],
+        vec![constructor, repr_slot],
+    ))
+}
+
+/// Implements `#[pyclass(pickle)]`: `PyClassPickle`, converting the fields to and from their
+/// pickled state, and the `__reduce__`, `__getstate__` and `__setstate__` methods.
+///
+/// `__reduce__` returns the reconstructor defined by `PyClassImpl::pickle_reconstructor`, so that
+/// unpickling never goes through `tp_new`.
+fn impl_pickle(
+    cls: &syn::Ident,
+    field_options: &[(&syn::Field, FieldPyO3Options)],
+    ctx: &Ctx,
+) -> Result<(TokenStream, Vec<MethodAndMethodDef>)> {
+    let Ctx { pyo3_path, .. } = ctx;
+    let cls_type: syn::Type = parse_quote!(#cls);
+
+    let mut members = Vec::new();
+    let mut keys = Vec::new();
+    let mut values = Vec::new();
+    for (index, (field, _)) in field_options.iter().enumerate() {
+        let (member, key): (syn::Member, String) = match &field.ident {
+            Some(ident) => (ident.clone().into(), ident.unraw().to_string()),
+            None => (syn::Index::from(index).into(), index.to_string()),
+        };
+        let ty = &field.ty;
+        values.push(quote! {
+            #pyo3_path::impl_::pyclass::ConvertField::<
+                { #pyo3_path::impl_::pyclass::IsIntoPyObjectRef::<#ty>::VALUE },
+                { #pyo3_path::impl_::pyclass::IsIntoPyObject::<#ty>::VALUE },
+            >::convert_field::<#ty>(&this.#member, py)?
+        });
+        members.push(member);
+        keys.push(key);
+    }
+    let pickle_trait_impl = quote! {
+        impl #pyo3_path::impl_::pyclass::PyClassPickle for #cls {
+            #[allow(unused_variables)]
+            fn fields_state<'py>(
+                slf: &#pyo3_path::Bound<'py, Self>,
+            ) -> #pyo3_path::PyResult<#pyo3_path::Bound<'py, #pyo3_path::PyAny>> {
+                #[allow(unused_imports)]
+                use #pyo3_path::impl_::pyclass::Probe;
+                let py = slf.py();
+                let state = #pyo3_path::types::PyDict::new(py);
+                {
+                    let this = slf.try_borrow()?;
+                    #(#pyo3_path::types::PyDictMethods::set_item(&state, #keys, #values)?;)*
+                }
+                ::std::result::Result::Ok(state.into_any())
+            }
+
+            #[allow(unused_variables)]
+            fn from_fields_state(
+                state: &#pyo3_path::Bound<'_, #pyo3_path::PyAny>,
+            ) -> #pyo3_path::PyResult<Self> {
+                let state = #pyo3_path::types::PyAnyMethods::downcast::<#pyo3_path::types::PyDict>(state)?;
+                ::std::result::Result::Ok(Self {
+                    #(#members: #pyo3_path::impl_::pyclass::pickle_extract_field(state, #keys)?,)*
+                })
+            }
+        }
+    };
+
+    let mut method_impls: Vec<syn::ImplItemFn> = vec![
+        parse_quote! {
+            #[allow(clippy::type_complexity)]
+            fn __reduce__<'py>(
+                slf: &#pyo3_path::Bound<'py, Self>,
+            ) -> #pyo3_path::PyResult<(
+                #pyo3_path::Bound<'py, #pyo3_path::PyAny>,
+                (
+                    #pyo3_path::Bound<'py, #pyo3_path::types::PyType>,
+                    #pyo3_path::Bound<'py, #pyo3_path::PyAny>,
+                ),
+                #pyo3_path::Bound<'py, #pyo3_path::PyAny>,
+            )> {
+                #pyo3_path::impl_::pyclass::pickle_reduce(slf)
+            }
+        },
+        parse_quote! {
+            fn __getstate__<'py>(
+                slf: &#pyo3_path::Bound<'py, Self>,
+            ) -> #pyo3_path::PyResult<#pyo3_path::Bound<'py, #pyo3_path::PyAny>> {
+                #pyo3_path::impl_::pyclass::pickle_getstate(slf.as_any())
+            }
+        },
+        parse_quote! {
+            fn __setstate__(
+                slf: &#pyo3_path::Bound<'_, Self>,
+                state: &#pyo3_path::Bound<'_, #pyo3_path::PyAny>,
+            ) -> #pyo3_path::PyResult<()> {
+                #pyo3_path::impl_::pyclass::pickle_setstate(slf.as_any(), state)
+            }
+        },
+    ];
+    let mut methods = Vec::new();
+    for method_impl in &mut method_impls {
+        let options = PyFunctionOptions::from_attrs(&mut method_impl.attrs)?;
+        match crate::pymethod::gen_py_method(
+            &cls_type,
+            &mut method_impl.sig,
+            &mut method_impl.attrs,
+            options,
+            ctx,
+        )? {
+            crate::pymethod::GeneratedPyMethod::Method(method) => methods.push(method),
+            _ => unreachable!("pickle methods are regular methods"),
+        }
+    }
+
+    let pickle_impl = quote! {
+        #pickle_trait_impl
+
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        impl #cls {
+            #(#method_impls)*
+        }
+    };
+
+    Ok((pickle_impl, methods))
+}
+
+/// Implements a generic class once per instantiation listed in `generic(...)`.
+///
+/// Each instantiation is exposed as its own Python class, named after its type alias, and its
+/// impls are generated in a `const` block defining the generic parameters as aliases of the
+/// concrete arguments. A `macro_rules!` replays generic `#[pymethods]` blocks in the same way, and a
+/// `{Class}Generic` class maps `Class[key]` to the instantiations with `__class_getitem__`.
+///
+/// The `macro_rules!` is imported under the name of the class, so that it can be invoked with the
+/// path of the class wherever the class is in scope. It takes the names of the generic parameters
+/// of the `#[pymethods]` block, which may differ from those of the class.
+///
+/// The instantiations extend `{Class}Generic`, so that they are instances of `Class` in Python. As
+/// it holds no data, instantiations are converted to their initializer with a default base.
+fn impl_generic_class(
+    (cls, vis, generics): (&syn::Ident, &syn::Visibility, &syn::Generics),
+    generic: &GenericAttribute,
+    args: &PyClassArgs,
+    doc: PythonDoc,
+    field_options: Vec<(&syn::Field, FieldPyO3Options)>,
+    methods_type: PyClassMethodsType,
+    ctx: &Ctx,
+) -> syn::Result<TokenStream> {
+    let Ctx { pyo3_path, .. } = ctx;
+    ensure_spanned!(
+        !generics.params.is_empty(),
+        generic.span() => "`generic` requires a class with generic parameters"
+    );
+    ensure_spanned!(
+        !generic.instances.is_empty(),
+        generic.span() => "`generic` requires at least one instantiation"
+    );
+    if let Some(extends) = &args.options.extends {
+        bail_spanned!(
+            extends.span() => "`generic` cannot be combined with `extends`: instantiations extend the generic class"
+        );
+    }
+
+    let generic_cls = format_ident!("{}Generic", cls);
+    let mut instance_args = args.clone();
+    instance_args.options.generic = None;
+    instance_args.options.name = None;
+    instance_args.options.extends = Some(parse_quote!(extends = #generic_cls));
+
+    let class_params: Vec<_> = generics
+        .params
+        .iter()
+        .map(|param| match param {
+            syn::GenericParam::Type(param) => param.ident.to_token_stream(),
+            syn::GenericParam::Const(param) => param.ident.to_token_stream(),
+            syn::GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+        })
+        .collect();
+    let macro_params: Vec<_> = (0..generics.params.len())
+        .map(|i| {
+            let param = format_ident!("param{}", i);
+            quote!($#param)
+        })
+        .collect();
+
+    let mut instances = Vec::new();
+    let mut param_aliases = Vec::new();
+    let mut class_getitem_arms = Vec::new();
+    for instance in &generic.instances {
+        let alias = &instance.name;
+        let ty = &instance.ty;
+        let aliases = generic_param_aliases(cls, generics, ty, &class_params)?;
+        let class_impl = impl_class(
+            alias,
+            &instance_args,
+            doc.clone(),
+            field_options.clone(),
+            methods_type,
+            ctx,
+        )?;
+        let into_py = impl_into_py(alias, ctx);
+        let alias_doc = format!(
+            "Instantiation of [`{}`] exposed to Python as `{}`.",
+            cls, alias
+        );
+        instances.push(quote! {
+            #[doc = #alias_doc]
+            #vis type #alias = #ty;
+
+            const _: () = {
+                #aliases
+                #class_impl
+                #into_py
+
+                impl ::std::convert::From<#alias> for #pyo3_path::PyClassInitializer<#alias> {
+                    fn from(value: #alias) -> Self {
+                        #pyo3_path::PyClassInitializer::from(#generic_cls).add_subclass(value)
+                    }
+                }
+            };
+        });
+        if let Some(key) = &instance.key {
+            let key = match key {
+                syn::Type::Tuple(tuple) => {
+                    let elems = tuple.elems.iter();
+                    quote!((#(<#elems as #pyo3_path::PyTypeInfo>::type_object(py),)*))
+                }
+                key => quote!(<#key as #pyo3_path::PyTypeInfo>::type_object(py)),
+            };
+            class_getitem_arms.push(quote! {
+                if #pyo3_path::types::PyAnyMethods::eq(key, #key)? {
+                    return ::std::result::Result::Ok(
+                        <#alias as #pyo3_path::PyTypeInfo>::type_object(py)
+                    );
+                }
+            });
+        }
+        param_aliases.push(generic_param_aliases(cls, generics, ty, &macro_params)?);
+    }
+
+    let generic_doc = format!(
+        "Generic Python class of [`{}`], base of its instantiations, whose `__class_getitem__` \
+        returns them.",
+        cls
+    );
+    let python_name = get_class_python_name(cls, args).to_string();
+    let krate = &args.options.krate.iter().collect::<Vec<_>>();
+    let module = args.options.module.iter();
+    let macro_ident = generic_pymethods_macro_ident(cls);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        #(#instances)*
+
+        #[doc = #generic_doc]
+        #[#pyo3_path::pyclass(#(#krate,)* #(#module,)* name = #python_name, frozen, subclass)]
+        #vis struct #generic_cls;
+
+        #[#pyo3_path::pymethods(#(#krate)*)]
+        impl #generic_cls {
+            #[classmethod]
+            fn __class_getitem__<'py>(
+                cls: &#pyo3_path::Bound<'py, #pyo3_path::types::PyType>,
+                key: &#pyo3_path::Bound<'py, #pyo3_path::PyAny>,
+            ) -> #pyo3_path::PyResult<#pyo3_path::Bound<'py, #pyo3_path::types::PyType>> {
+                let py = cls.py();
+                #(#class_getitem_arms)*
+                ::std::result::Result::Err(#pyo3_path::exceptions::PyTypeError::new_err(
+                    ::std::format!(
+                        "{}[{}] is not an instantiation exposed to Python",
+                        #python_name,
+                        #pyo3_path::types::PyAnyMethods::repr(key)?,
+                    )
+                ))
+            }
+        }
+
+        impl #impl_generics #pyo3_path::impl_::pyclass::GenericPyClass for #cls #ty_generics #where_clause {}
+
+        #[doc(hidden)]
+        #[allow(unused_macros)]
+        macro_rules! #macro_ident {
+            ([#(#macro_params:ident),*] $($item:tt)*) => {
+                #(const _: () = {
+                    #param_aliases
+                    $($item)*
+                };)*
+            };
+        }
+
+        #[doc(hidden)]
+        #[allow(unused_imports)]
+        pub(crate) use #macro_ident as #cls;
+    })
+}
+
+/// Name of the `macro_rules!` expanding generic `#[pymethods]` blocks of a generic class.
+fn generic_pymethods_macro_ident(cls: &syn::Ident) -> syn::Ident {
+    format_ident!("__pyo3_generic_{}", cls.unraw(), span = cls.span())
+}
+
+/// Defines `names`, one per generic parameter of `cls`, as aliases of the arguments of the
+/// instantiation `ty`.
+fn generic_param_aliases(
+    cls: &syn::Ident,
+    generics: &syn::Generics,
+    ty: &syn::Type,
+    names: &[TokenStream],
+) -> syn::Result<TokenStream> {
+    let segment = match ty {
+        syn::Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+        _ => None,
+    };
+    let arguments = match segment {
+        Some(segment) if segment.ident == *cls => match &segment.arguments {
+            syn::PathArguments::AngleBracketed(arguments) => Some(&arguments.args),
+            _ => None,
+        },
+        _ => None,
+    };
+    let arguments = match arguments {
+        Some(arguments) if arguments.len() == generics.params.len() => arguments,
+        _ => bail_spanned!(
+            ty.span() => format!(
+                "expected an instantiation of `{}` with {} generic argument(s)",
+                cls,
+                generics.params.len()
+            )
+        ),
+    };
+    let mut aliases = TokenStream::new();
+    for ((param, argument), ident) in generics.params.iter().zip(arguments).zip(names) {
+        match (param, argument) {
+            (syn::GenericParam::Type(_), syn::GenericArgument::Type(argument)) => {
+                aliases.extend(quote!(#[allow(dead_code)] type #ident = #argument;));
+            }
+            (syn::GenericParam::Const(param), syn::GenericArgument::Const(argument)) => {
+                let ty = &param.ty;
+                aliases.extend(quote!(#[allow(dead_code)] const #ident: #ty = #argument;));
+            }
+            // a path such as `N` in `Array<N>` is parsed as a type
+            (syn::GenericParam::Const(param), syn::GenericArgument::Type(argument)) => {
+                let ty = &param.ty;
+                aliases.extend(quote!(#[allow(dead_code)] const #ident: #ty = #argument;));
+            }
+            (_, argument) => bail_spanned!(
+                argument.span() => "generic argument does not match the generic parameter of the class"
+            ),
+        }
+    }
+    Ok(aliases)
+}
+
 enum PyClassEnum<'a> {
     Simple(PyClassSimpleEnum<'a>),
     Complex(PyClassComplexEnum<'a>),
@@ -496,6 +1175,14 @@ pub fn build_py_enum(
         bail_spanned!(extends.span() => "enums can't extend from other classes");
     } else if let Some(subclass) = &args.options.subclass {
         bail_spanned!(subclass.span() => "enums can't be inherited by other classes");
+    } else if let Some(generic) = &args.options.generic {
+        bail_spanned!(generic.span() => "`generic` is only supported for structs");
+    } else if let Some(dataclass) = &args.options.dataclass {
+        bail_spanned!(dataclass.span() => "`dataclass` is only supported for structs");
+    } else if let Some(pickle) = &args.options.pickle {
+        bail_spanned!(pickle.span() => "`pickle` is only supported for structs");
+    } else if let Some(abstract_) = &args.options.abstract_ {
+        bail_spanned!(abstract_.span() => "enums can't be abstract");
     } else if enum_.variants.is_empty() {
         bail_spanned!(enum_.brace_token.span.join() => "#[pyclass] can't be used on enums without any variants");
     }
@@ -1184,10 +1871,10 @@ fn impl_complex_enum_variant_cls(
     }
 }
 
-fn impl_complex_enum_variant_match_args(
+fn impl_match_args(
     ctx: &Ctx,
-    variant_cls_type: &syn::Type,
-    field_names: &mut Vec<Ident>,
+    cls_type: &syn::Type,
+    field_names: &[String],
 ) -> (MethodAndMethodDef, syn::ImplItemConst) {
     let ident = format_ident!("__match_args__");
     let match_args_const_impl: syn::ImplItemConst = {
@@ -1197,7 +1884,7 @@ fn impl_complex_enum_variant_match_args(
         parse_quote! {
             #[allow(non_upper_case_globals)]
             const #ident: ( #(#args_tp,)* ) = (
-                #(stringify!(#field_names),)*
+                #(#field_names,)*
             );
         }
     };
@@ -1210,9 +1897,9 @@ fn impl_complex_enum_variant_match_args(
         },
     };
 
-    let variant_match_args = gen_py_const(variant_cls_type, &spec, ctx);
+    let match_args = gen_py_const(cls_type, &spec, ctx);
 
-    (variant_match_args, match_args_const_impl)
+    (match_args, match_args_const_impl)
 }
 
 fn impl_complex_enum_struct_variant_cls(
@@ -1234,8 +1921,13 @@ fn impl_complex_enum_struct_variant_cls(
         let field_type = field.ty;
         let field_with_type = quote! { #field_name: #field_type };
 
-        let field_getter =
-            complex_enum_variant_field_getter(&variant_cls_type, field_name, field.span, ctx)?;
+        let field_getter = complex_enum_variant_field_getter(
+            &variant_cls_type,
+            field_name,
+            field_type,
+            field.span,
+            ctx,
+        )?;
 
         let field_getter_impl = quote! {
             fn #field_name(slf: #pyo3_path::PyRef<Self>) -> #pyo3_path::PyResult<#pyo3_path::PyObject> {
@@ -1259,8 +1951,14 @@ fn impl_complex_enum_struct_variant_cls(
         field_getter_impls.push(field_getter_impl);
     }
 
-    let (variant_match_args, match_args_const_impl) =
-        impl_complex_enum_variant_match_args(ctx, &variant_cls_type, &mut field_names);
+    let (variant_match_args, match_args_const_impl) = impl_match_args(
+        ctx,
+        &variant_cls_type,
+        &field_names
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+    );
 
     field_getters.push(variant_match_args);
 
@@ -1300,8 +1998,13 @@ fn impl_complex_enum_tuple_variant_field_getters(
         let field_name = format_ident!("_{}", index);
         let field_type = field.ty;
 
-        let field_getter =
-            complex_enum_variant_field_getter(variant_cls_type, &field_name, field.span, ctx)?;
+        let field_getter = complex_enum_variant_field_getter(
+            variant_cls_type,
+            &field_name,
+            field_type,
+            field.span,
+            ctx,
+        )?;
 
         // Generate the match arms needed to destructure the tuple and access the specific field
         let field_access_tokens: Vec<_> = (0..variant.fields.len())
@@ -1433,8 +2136,14 @@ fn impl_complex_enum_tuple_variant_cls(
 
     slots.push(variant_getitem);
 
-    let (variant_match_args, match_args_method_impl) =
-        impl_complex_enum_variant_match_args(ctx, &variant_cls_type, &mut field_names);
+    let (variant_match_args, match_args_method_impl) = impl_match_args(
+        ctx,
+        &variant_cls_type,
+        &field_names
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+    );
 
     field_getters.push(variant_match_args);
 
@@ -1670,8 +2379,11 @@ fn complex_enum_struct_variant_new<'a>(
         signature,
         convention: crate::method::CallingConvention::TpNew,
         text_signature: None,
+        traceback: None,
+        local: None,
         asyncness: None,
         unsafety: None,
+        output: syn::ReturnType::Default,
     };
 
     crate::pymethod::impl_py_method_def_new(&variant_cls_type, &spec, ctx)
@@ -1724,8 +2436,11 @@ fn complex_enum_tuple_variant_new<'a>(
         signature,
         convention: crate::method::CallingConvention::TpNew,
         text_signature: None,
+        traceback: None,
+        local: None,
         asyncness: None,
         unsafety: None,
+        output: syn::ReturnType::Default,
     };
 
     crate::pymethod::impl_py_method_def_new(&variant_cls_type, &spec, ctx)
@@ -1734,6 +2449,7 @@ fn complex_enum_tuple_variant_new<'a>(
 fn complex_enum_variant_field_getter<'a>(
     variant_cls_type: &'a syn::Type,
     field_name: &'a syn::Ident,
+    field_type: &'a syn::Type,
     field_span: Span,
     ctx: &Ctx,
 ) -> Result<MethodAndMethodDef> {
@@ -1748,8 +2464,11 @@ fn complex_enum_variant_field_getter<'a>(
         signature,
         convention: crate::method::CallingConvention::Noargs,
         text_signature: None,
+        traceback: None,
+        local: None,
         asyncness: None,
         unsafety: None,
+        output: parse_quote!(-> #field_type),
     };
 
     let property_type = crate::pymethod::PropertyType::Function {
@@ -1776,6 +2495,7 @@ fn descriptors_to_items(
             name: Some(name),
             get: None,
             set: None,
+            ..
         } = options
         {
             return Err(syn::Error::new_spanned(name, USELESS_NAME));
@@ -1813,6 +2533,32 @@ fn descriptors_to_items(
     Ok(items)
 }
 
+/// Implements the conversions of `cls` to Python objects, through `Py::new`.
+fn impl_into_py(cls: &syn::Ident, ctx: &Ctx) -> TokenStream {
+    let Ctx { pyo3_path, .. } = ctx;
+    quote! {
+        #[allow(deprecated)]
+        impl #pyo3_path::IntoPy<#pyo3_path::PyObject> for #cls {
+            fn into_py(self, py: #pyo3_path::Python<'_>) -> #pyo3_path::PyObject {
+                #pyo3_path::IntoPy::into_py(#pyo3_path::Py::new(py, self).unwrap(), py)
+            }
+        }
+
+        impl<'py> #pyo3_path::conversion::IntoPyObject<'py> for #cls {
+            type Target = Self;
+            type Output = #pyo3_path::Bound<'py, <Self as #pyo3_path::conversion::IntoPyObject<'py>>::Target>;
+            type Error = #pyo3_path::PyErr;
+
+            fn into_pyobject(self, py: #pyo3_path::Python<'py>) -> ::std::result::Result<
+                <Self as #pyo3_path::conversion::IntoPyObject>::Output,
+                <Self as #pyo3_path::conversion::IntoPyObject>::Error,
+            > {
+                #pyo3_path::Bound::new(py, self)
+            }
+        }
+    }
+}
+
 fn impl_pytypeinfo(cls: &syn::Ident, attr: &PyClassArgs, ctx: &Ctx) -> TokenStream {
     let Ctx { pyo3_path, .. } = ctx;
     let cls_name = get_class_python_name(cls, attr).to_string();
@@ -2134,32 +2880,9 @@ impl<'a> PyClassImplsBuilder<'a> {
     }
 
     fn impl_into_py(&self, ctx: &Ctx) -> TokenStream {
-        let Ctx { pyo3_path, .. } = ctx;
-        let cls = self.cls;
-        let attr = self.attr;
         // If #cls is not extended type, we allow Self->PyObject conversion
-        if attr.options.extends.is_none() {
-            quote! {
-                #[allow(deprecated)]
-                impl #pyo3_path::IntoPy<#pyo3_path::PyObject> for #cls {
-                    fn into_py(self, py: #pyo3_path::Python<'_>) -> #pyo3_path::PyObject {
-                        #pyo3_path::IntoPy::into_py(#pyo3_path::Py::new(py, self).unwrap(), py)
-                    }
-                }
-
-                impl<'py> #pyo3_path::conversion::IntoPyObject<'py> for #cls {
-                    type Target = Self;
-                    type Output = #pyo3_path::Bound<'py, <Self as #pyo3_path::conversion::IntoPyObject<'py>>::Target>;
-                    type Error = #pyo3_path::PyErr;
-
-                    fn into_pyobject(self, py: #pyo3_path::Python<'py>) -> ::std::result::Result<
-                        <Self as #pyo3_path::conversion::IntoPyObject>::Output,
-                        <Self as #pyo3_path::conversion::IntoPyObject>::Error,
-                    > {
-                        #pyo3_path::Bound::new(py, self)
-                    }
-                }
-            }
+        if self.attr.options.extends.is_none() {
+            impl_into_py(self.cls, ctx)
         } else {
             quote! {}
         }
@@ -2172,9 +2895,33 @@ impl<'a> PyClassImplsBuilder<'a> {
             PythonDoc::to_token_stream,
         );
         let is_basetype = self.attr.options.subclass.is_some();
-        let base = match &self.attr.options.extends {
-            Some(extends_attr) => extends_attr.value.clone(),
-            None => parse_quote! { #pyo3_path::PyAny },
+        let is_abstract = self.attr.options.abstract_.is_some();
+        let register_abc = match &self.attr.options.register {
+            Some(register) => {
+                let path = &register.value;
+                quote!(::std::option::Option::Some(#path))
+            }
+            None => quote!(::std::option::Option::None),
+        };
+        let base = match self
+            .attr
+            .options
+            .extends
+            .as_ref()
+            .map(|extends| &extends.value)
+        {
+            Some(TypeOrImportPath::Type(path)) => path.clone(),
+            _ => parse_quote! { #pyo3_path::PyAny },
+        };
+        let python_base = match self
+            .attr
+            .options
+            .extends
+            .as_ref()
+            .map(|extends| &extends.value)
+        {
+            Some(TypeOrImportPath::Import(path)) => quote!(::std::option::Option::Some(#path)),
+            _ => quote!(::std::option::Option::None),
         };
         let is_subclass = self.attr.options.extends.is_some();
         let is_mapping: bool = self.attr.options.mapping.is_some();
@@ -2206,6 +2953,40 @@ impl<'a> PyClassImplsBuilder<'a> {
             TokenStream::new()
         };
 
+        let metaclass = self.attr.options.metaclass.as_ref().map(|metaclass| {
+            let metaclass = match &metaclass.value {
+                TypeOrImportPath::Type(path) => quote! {
+                    <#path as #pyo3_path::PyTypeInfo>::type_object(py)
+                },
+                TypeOrImportPath::Import(path) => quote! {
+                    #pyo3_path::impl_::pyclass::import_type(py, #path)?
+                },
+            };
+            quote! {
+                fn metaclass(
+                    py: #pyo3_path::Python<'_>,
+                ) -> #pyo3_path::PyResult<::std::option::Option<#pyo3_path::Bound<'_, #pyo3_path::types::PyType>>> {
+                    ::std::result::Result::Ok(::std::option::Option::Some(#metaclass))
+                }
+            }
+        });
+
+        let pickle_reconstructor = self.attr.options.pickle.as_ref().map(|_| {
+            // added next to the class in its module, where pickle looks it up by name
+            let name = format!(
+                "_pyo3_reconstruct_{}",
+                get_class_python_name(cls, self.attr).unraw()
+            );
+            let name = LitCStr::new(CString::new(name).unwrap(), Span::call_site(), ctx);
+            quote! {
+                fn pickle_reconstructor() -> ::std::option::Option<&'static #pyo3_path::impl_::pymethods::PyMethodDef> {
+                    const DEF: #pyo3_path::impl_::pymethods::PyMethodDef =
+                        #pyo3_path::impl_::pyclass::pickle_reconstructor_def::<#cls>(#name);
+                    ::std::option::Option::Some(&DEF)
+                }
+            }
+        });
+
         let thread_checker = if self.attr.options.unsendable.is_some() {
             quote! { #pyo3_path::impl_::pyclass::ThreadCheckerImpl }
         } else {
@@ -2287,10 +3068,45 @@ impl<'a> PyClassImplsBuilder<'a> {
                     type BaseNativeType = <Self as #pyo3_path::impl_::pyclass::PyClassImpl>::BaseNativeType;
                     type Initializer = #pyo3_path::pyclass_init::PyClassInitializer<Self>;
                     type PyClassMutability = <Self as #pyo3_path::impl_::pyclass::PyClassImpl>::PyClassMutability;
+                    const DYNAMIC_LAYOUT: bool = <Self as #pyo3_path::impl_::pyclass::PyClassImpl>::DYNAMIC_LAYOUT;
+                    const EXTENDS_PYTHON_CLASS: bool = <Self as #pyo3_path::impl_::pyclass::PyClassImpl>::EXTENDS_PYTHON_CLASS;
+                }
+            }
+        });
+
+        let constructor_introspection = introspection::enabled().then(|| {
+            quote! {
+                fn constructor_introspection() -> ::std::option::Option<#pyo3_path::impl_::introspection::FunctionIntrospection> {
+                    use #pyo3_path::impl_::pyclass::*;
+                    let collector = PyClassImplCollector::<Self>::new();
+                    collector.new_introspection()
                 }
             }
         });
 
+        let vectorcall = has_pyclass_vectorcall().then(|| {
+            // The vectorcall pointer of instances is stored after the class layout, where
+            // subclasses store their own fields.
+            let call_vectorcall = attr.options.subclass.is_none().then(|| {
+                quote! {
+                    fn call_vectorcall() -> ::std::option::Option<#pyo3_path::ffi::vectorcallfunc> {
+                        use #pyo3_path::impl_::pyclass::*;
+                        let collector = PyClassImplCollector::<Self>::new();
+                        collector.call_vectorcall()
+                    }
+                }
+            });
+            quote! {
+                fn new_vectorcall() -> ::std::option::Option<#pyo3_path::ffi::vectorcallfunc> {
+                    use #pyo3_path::impl_::pyclass::*;
+                    let collector = PyClassImplCollector::<Self>::new();
+                    collector.new_vectorcall()
+                }
+
+                #call_vectorcall
+            }
+        });
+
         let assertions = if attr.options.unsendable.is_some() {
             TokenStream::new()
         } else {
@@ -2310,6 +3126,9 @@ impl<'a> PyClassImplsBuilder<'a> {
             impl #pyo3_path::impl_::pyclass::PyClassImpl for #cls {
                 const IS_BASETYPE: bool = #is_basetype;
                 const IS_SUBCLASS: bool = #is_subclass;
+                const IS_ABSTRACT: bool = #is_abstract;
+                const REGISTER_ABC: ::std::option::Option<&'static str> = #register_abc;
+                const PYTHON_BASE: ::std::option::Option<&'static str> = #python_base;
                 const IS_MAPPING: bool = #is_mapping;
                 const IS_SEQUENCE: bool = #is_sequence;
 
@@ -2344,11 +3163,19 @@ impl<'a> PyClassImplsBuilder<'a> {
 
                 #weaklist_offset
 
+                #metaclass
+
+                #pickle_reconstructor
+
                 fn lazy_type_object() -> &'static #pyo3_path::impl_::pyclass::LazyTypeObject<Self> {
                     use #pyo3_path::impl_::pyclass::LazyTypeObject;
                     static TYPE_OBJECT: LazyTypeObject<#cls> = LazyTypeObject::new();
                     &TYPE_OBJECT
                 }
+
+                #constructor_introspection
+
+                #vectorcall
             }
 
             #[doc(hidden)]
@@ -2478,6 +3305,7 @@ fn generate_cfg_check(variants: &[PyClassEnumUnitVariant<'_>], cls: &syn::Ident)
 const UNIQUE_GET: &str = "`get` may only be specified once";
 const UNIQUE_SET: &str = "`set` may only be specified once";
 const UNIQUE_NAME: &str = "`name` may only be specified once";
+const UNIQUE_DEFAULT: &str = "`default` may only be specified once";
 
 const DUPE_SET: &str = "useless `set` - the struct is already annotated with `set_all`";
 const DUPE_GET: &str = "useless `get` - the struct is already annotated with `get_all`";
@@ -1,3 +1,4 @@
+use crate::introspection;
 use crate::utils::Ctx;
 use crate::{
     attributes::{
@@ -17,7 +18,9 @@ use syn::{
 
 mod signature;
 
-pub use self::signature::{ConstructorAttribute, FunctionSignature, SignatureAttribute};
+pub use self::signature::{
+    ConstructorAttribute, FunctionSignature, PythonSignature, SignatureAttribute,
+};
 
 #[derive(Clone, Debug)]
 pub struct PyFunctionArgPyO3Attributes {
@@ -89,6 +92,9 @@ pub struct PyFunctionOptions {
     pub name: Option<NameAttribute>,
     pub signature: Option<SignatureAttribute>,
     pub text_signature: Option<TextSignatureAttribute>,
+    pub traceback: Option<attributes::kw::traceback>,
+    pub local: Option<attributes::kw::local>,
+    pub abstractmethod: Option<attributes::kw::abstractmethod>,
     pub krate: Option<CrateAttribute>,
 }
 
@@ -102,6 +108,9 @@ impl Parse for PyFunctionOptions {
                 || lookahead.peek(attributes::kw::pass_module)
                 || lookahead.peek(attributes::kw::signature)
                 || lookahead.peek(attributes::kw::text_signature)
+                || lookahead.peek(attributes::kw::traceback)
+                || lookahead.peek(attributes::kw::local)
+                || lookahead.peek(attributes::kw::abstractmethod)
             {
                 options.add_attributes(std::iter::once(input.parse()?))?;
                 if !input.is_empty() {
@@ -124,6 +133,9 @@ pub enum PyFunctionOption {
     PassModule(attributes::kw::pass_module),
     Signature(SignatureAttribute),
     TextSignature(TextSignatureAttribute),
+    Traceback(attributes::kw::traceback),
+    Local(attributes::kw::local),
+    AbstractMethod(attributes::kw::abstractmethod),
     Crate(CrateAttribute),
 }
 
@@ -138,6 +150,12 @@ impl Parse for PyFunctionOption {
             input.parse().map(PyFunctionOption::Signature)
         } else if lookahead.peek(attributes::kw::text_signature) {
             input.parse().map(PyFunctionOption::TextSignature)
+        } else if lookahead.peek(attributes::kw::traceback) {
+            input.parse().map(PyFunctionOption::Traceback)
+        } else if lookahead.peek(attributes::kw::local) {
+            input.parse().map(PyFunctionOption::Local)
+        } else if lookahead.peek(attributes::kw::abstractmethod) {
+            input.parse().map(PyFunctionOption::AbstractMethod)
         } else if lookahead.peek(syn::Token![crate]) {
             input.parse().map(PyFunctionOption::Crate)
         } else {
@@ -174,6 +192,9 @@ impl PyFunctionOptions {
                 PyFunctionOption::PassModule(pass_module) => set_option!(pass_module),
                 PyFunctionOption::Signature(signature) => set_option!(signature),
                 PyFunctionOption::TextSignature(text_signature) => set_option!(text_signature),
+                PyFunctionOption::Traceback(traceback) => set_option!(traceback),
+                PyFunctionOption::Local(local) => set_option!(local),
+                PyFunctionOption::AbstractMethod(abstractmethod) => set_option!(abstractmethod),
                 PyFunctionOption::Crate(krate) => set_option!(krate),
             }
         }
@@ -201,9 +222,16 @@ pub fn impl_wrap_pyfunction(
         name,
         signature,
         text_signature,
+        traceback,
+        local,
+        abstractmethod,
         krate,
     } = options;
 
+    if let Some(abstractmethod) = abstractmethod {
+        bail_spanned!(abstractmethod.span() => "`abstractmethod` can only be used with methods");
+    }
+
     let ctx = &Ctx::new(&krate, Some(&func.sig));
     let Ctx { pyo3_path, .. } = &ctx;
 
@@ -249,8 +277,11 @@ pub fn impl_wrap_pyfunction(
         python_name,
         signature,
         text_signature,
+        traceback,
+        local,
         asyncness: func.sig.asyncness,
         unsafety: func.sig.unsafety,
+        output: func.sig.output.clone(),
     };
 
     let vis = &func.vis;
@@ -258,7 +289,11 @@ pub fn impl_wrap_pyfunction(
 
     let wrapper_ident = format_ident!("__pyfunction_{}", spec.name);
     let wrapper = spec.get_wrapper_function(&wrapper_ident, None, ctx)?;
-    let methoddef = spec.get_methoddef(wrapper_ident, &spec.get_doc(&func.attrs, ctx), ctx);
+    let mut methoddef = spec.get_methoddef(wrapper_ident, &spec.get_doc(&func.attrs, ctx), ctx);
+    if introspection::enabled() {
+        let introspection = introspection::function_introspection(&spec, None, ctx);
+        methoddef = quote! { #methoddef.with_introspection(#introspection) };
+    }
 
     let wrapped_pyfunction = quote! {
 
@@ -490,7 +490,7 @@ impl<'a> FunctionSignature<'a> {
         }
     }
 
-    fn default_value_for_parameter(&self, parameter: &str) -> String {
+    pub(crate) fn default_value_for_parameter(&self, parameter: &str) -> String {
         let mut default = "...".to_string();
         if let Some(fn_arg) = self.arguments.iter().find(|arg| arg.name() == parameter) {
             if let FnArg::Regular(RegularArg {
@@ -9,7 +9,7 @@ use crate::{
 };
 use proc_macro2::TokenStream;
 use pymethod::GeneratedPyMethod;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, quote_spanned};
 use syn::ImplItemFn;
 use syn::{
     parse::{Parse, ParseStream},
@@ -85,6 +85,131 @@ pub fn build_py_methods(
     }
 }
 
+/// Expands a generic `#[pymethods]` block once per instantiation of its `#[pyclass(generic(...))]`.
+///
+/// The block is passed to the `macro_rules!` generated alongside the class, imported under the
+/// name of the class, which defines the generic parameters of the block as aliases of the concrete
+/// arguments of each instantiation. The bounds of the parameters are checked against each
+/// instantiation by a function with the same generic parameters.
+pub fn build_generic_py_methods(mut ast: syn::ItemImpl) -> syn::Result<TokenStream> {
+    if let Some((_, path, _)) = &ast.trait_ {
+        bail_spanned!(path.span() => "#[pymethods] cannot be used on trait impl blocks");
+    }
+    if let Some(lifetime) = ast.generics.lifetimes().next() {
+        bail_spanned!(
+            lifetime.span() => "#[pymethods] cannot be used with lifetime parameters"
+        );
+    }
+    let options = PyImplOptions::from_attrs(&mut ast.attrs.clone())?;
+    let Ctx { pyo3_path, .. } = &Ctx::new(&options.krate, None);
+    let generics = std::mem::take(&mut ast.generics);
+    let cls = ast.self_ty.clone();
+    let path = match &mut *ast.self_ty {
+        syn::Type::Path(path) if path.qself.is_none() => Some(&mut path.path),
+        _ => None,
+    };
+    let params = match path.as_ref().and_then(|path| path.segments.last()) {
+        Some(syn::PathSegment {
+            arguments: syn::PathArguments::AngleBracketed(arguments),
+            ..
+        }) => generic_arguments_params(&generics, arguments),
+        _ => None,
+    };
+    let (path, params) = match (path, params) {
+        (Some(path), Some(params)) => (path, params),
+        _ => bail_spanned!(
+            ast.self_ty.span() => "generic #[pymethods] must be implemented on a `#[pyclass(generic(...))]`, \
+            with its generic parameters, e.g. `impl<T> Class<T>`"
+        ),
+    };
+    let mut macro_path = path.clone();
+    if let Some(segment) = macro_path.segments.last_mut() {
+        segment.arguments = syn::PathArguments::None;
+    }
+    if let Some(syn::PathArguments::AngleBracketed(arguments)) = path
+        .segments
+        .last_mut()
+        .map(|segment| &mut segment.arguments)
+    {
+        // the generated code also uses the type in expression position
+        arguments.colon2_token.get_or_insert_with(Default::default);
+    }
+
+    let bound_args = generics.params.iter().map(|param| match param {
+        syn::GenericParam::Const(param) => {
+            let ident = &param.ident;
+            quote!({ #ident })
+        }
+        param => {
+            let ident = generic_param_ident(param);
+            quote!(#ident)
+        }
+    });
+    let check_params = &generics.params;
+    let where_clause = &generics.where_clause;
+    let check_bounds = quote_spanned! { generics.span() =>
+        #[allow(dead_code)]
+        fn __pyo3_check_bounds() {
+            fn check<#check_params>() #where_clause {}
+            check::<#(#bound_args),*>();
+        }
+    };
+    let check_generic_class = quote! {
+        const _: () = {
+            #[allow(dead_code)]
+            fn check<#check_params>() #where_clause {
+                #pyo3_path::impl_::pyclass::assert_generic_pyclass::<#cls>();
+            }
+        };
+    };
+    Ok(quote! {
+        #check_generic_class
+
+        #macro_path! {
+            [#(#params),*]
+            #check_bounds
+
+            #[#pyo3_path::pymethods]
+            #ast
+        }
+    })
+}
+
+/// Returns the generic parameters of an `impl` block given as `arguments` of its type, in the
+/// order of the arguments, if each parameter is given exactly once.
+fn generic_arguments_params<'a>(
+    generics: &'a syn::Generics,
+    arguments: &syn::AngleBracketedGenericArguments,
+) -> Option<Vec<&'a syn::Ident>> {
+    let params = arguments
+        .args
+        .iter()
+        .map(|argument| {
+            let ident = match argument {
+                syn::GenericArgument::Type(syn::Type::Path(path)) if path.qself.is_none() => {
+                    path.path.get_ident()?
+                }
+                _ => return None,
+            };
+            generics
+                .params
+                .iter()
+                .map(generic_param_ident)
+                .find(|param| *param == ident)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let unique: HashSet<_> = params.iter().collect();
+    (unique.len() == params.len() && params.len() == generics.params.len()).then_some(params)
+}
+
+fn generic_param_ident(param: &syn::GenericParam) -> &syn::Ident {
+    match param {
+        syn::GenericParam::Type(param) => &param.ident,
+        syn::GenericParam::Const(param) => &param.ident,
+        syn::GenericParam::Lifetime(param) => &param.lifetime.ident,
+    }
+}
+
 fn check_pyfunction(pyo3_path: &PyO3CratePath, meth: &mut ImplItemFn) -> syn::Result<()> {
     let mut error = None;
 
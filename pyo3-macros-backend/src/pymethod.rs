@@ -2,8 +2,10 @@ use std::borrow::Cow;
 use std::ffi::CString;
 
 use crate::attributes::{NameAttribute, RenamingRule};
+use crate::introspection;
 use crate::method::{CallingConvention, ExtractErrorMode, PyArg};
 use crate::params::{impl_regular_arg_param, Holders};
+use crate::pyversions::has_pyclass_vectorcall;
 use crate::utils::PythonDoc;
 use crate::utils::{Ctx, LitCStr};
 use crate::{
@@ -13,7 +15,7 @@ use crate::{
 use crate::{quotes, utils};
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote, quote_spanned, ToTokens};
-use syn::{ext::IdentExt, spanned::Spanned, Result};
+use syn::{ext::IdentExt, parse_quote, spanned::Spanned, Result};
 
 /// Generated code for a single pymethod item.
 pub struct MethodAndMethodDef {
@@ -194,10 +196,28 @@ pub fn gen_py_method(
 ) -> Result<GeneratedPyMethod> {
     check_generic(sig)?;
     ensure_function_options_valid(&options)?;
+    let abstractmethod = options.abstractmethod;
     let method = PyMethod::parse(sig, meth_attrs, options)?;
     let spec = &method.spec;
     let Ctx { pyo3_path, .. } = ctx;
 
+    if let Some(abstractmethod) = &abstractmethod {
+        ensure_spanned!(
+            matches!((&method.kind, &spec.tp), (PyMethodKind::Fn, FnType::Fn(_))),
+            abstractmethod.span() => "`abstractmethod` can only be used with instance methods"
+        );
+    }
+
+    if let Some(traceback) = &spec.traceback {
+        ensure_spanned!(
+            !matches!(spec.tp, FnType::ClassAttribute | FnType::Getter(_) | FnType::Setter(_)),
+            traceback.span() => "`traceback` cannot be used with class attributes, getters or setters"
+        );
+    }
+    if let Some(local) = &spec.local {
+        ensure_spanned!(spec.asyncness.is_some(), local.span() => "`local` can only be used with `async fn`");
+    }
+
     Ok(match (method.kind, &spec.tp) {
         // Class attributes go before protos so that class attributes can be used to set proto
         // method to None.
@@ -232,6 +252,7 @@ pub fn gen_py_method(
             spec,
             &spec.get_doc(meth_attrs, ctx),
             None,
+            abstractmethod.is_some(),
             ctx,
         )?),
         (_, FnType::FnClass(_)) => GeneratedPyMethod::Method(impl_py_method_def(
@@ -239,6 +260,7 @@ pub fn gen_py_method(
             spec,
             &spec.get_doc(meth_attrs, ctx),
             Some(quote!(#pyo3_path::ffi::METH_CLASS)),
+            false,
             ctx,
         )?),
         (_, FnType::FnStatic) => GeneratedPyMethod::Method(impl_py_method_def(
@@ -246,12 +268,14 @@ pub fn gen_py_method(
             spec,
             &spec.get_doc(meth_attrs, ctx),
             Some(quote!(#pyo3_path::ffi::METH_STATIC)),
+            false,
             ctx,
         )?),
         // special prototypes
         (_, FnType::FnNew) | (_, FnType::FnNewClass(_)) => {
             GeneratedPyMethod::Proto(impl_py_method_def_new(cls, spec, ctx)?)
         }
+        (_, FnType::FnInit(_)) => GeneratedPyMethod::Proto(impl_init_slot(cls, method.spec, ctx)?),
 
         (_, FnType::Getter(self_type)) => GeneratedPyMethod::Method(impl_py_getter_def(
             cls,
@@ -310,6 +334,17 @@ fn ensure_no_forbidden_protocol_attributes(
     if let Some(text_signature) = &spec.text_signature {
         bail_spanned!(text_signature.kw.span() => format!("`text_signature` cannot be used with magic method `{}`", method_name));
     }
+    if let Some(traceback) = &spec.traceback {
+        // __call__ uses the same wrapper as ordinary methods, which supports tracebacks.
+        if !matches!(proto_kind, PyMethodProtoKind::Call) {
+            bail_spanned!(traceback.span() => format!("`traceback` cannot be used with magic method `{}`", method_name));
+        }
+    }
+    if let Some(local) = &spec.local {
+        if !matches!(proto_kind, PyMethodProtoKind::Call) {
+            bail_spanned!(local.span() => format!("`local` cannot be used with magic method `{}`", method_name));
+        }
+    }
     Ok(())
 }
 
@@ -319,6 +354,7 @@ pub fn impl_py_method_def(
     spec: &FnSpec<'_>,
     doc: &PythonDoc,
     flags: Option<TokenStream>,
+    is_abstract: bool,
     ctx: &Ctx,
 ) -> Result<MethodAndMethodDef> {
     let Ctx { pyo3_path, .. } = ctx;
@@ -328,14 +364,28 @@ pub fn impl_py_method_def(
     let methoddef_type = match spec.tp {
         FnType::FnStatic => quote!(Static),
         FnType::FnClass(_) => quote!(Class),
+        _ if is_abstract => quote!(AbstractMethod),
         _ => quote!(Method),
     };
     let methoddef = spec.get_methoddef(quote! { #cls::#wrapper_ident }, doc, ctx);
-    let method_def = quote! {
+    let add_introspection = introspection::enabled().then(|| {
+        let introspection = introspection::function_introspection(spec, Some(cls), ctx);
+        quote!(.with_introspection(#introspection))
+    });
+    let assert_abstract = is_abstract.then(|| {
+        quote! {
+            const _: () = ::std::assert!(
+                <#cls as #pyo3_path::impl_::pyclass::PyClassImpl>::IS_ABSTRACT,
+                "`#[pyo3(abstractmethod)]` requires `#[pyclass(abstract)]`"
+            );
+        }
+    });
+    let method_def = quote! {{
+        #assert_abstract
         #pyo3_path::impl_::pyclass::MaybeRuntimePyMethodDef::Static(
-            #pyo3_path::impl_::pymethods::PyMethodDefType::#methoddef_type(#methoddef #add_flags)
+            #pyo3_path::impl_::pymethods::PyMethodDefType::#methoddef_type(#methoddef #add_flags #add_introspection)
         )
-    };
+    }};
     Ok(MethodAndMethodDef {
         associated_method,
         method_def,
@@ -350,7 +400,41 @@ pub fn impl_py_method_def_new(
 ) -> Result<MethodAndSlotDef> {
     let Ctx { pyo3_path, .. } = ctx;
     let wrapper_ident = syn::Ident::new("__pymethod___new____", Span::call_site());
-    let associated_method = spec.get_wrapper_function(&wrapper_ident, Some(cls), ctx)?;
+    let mut associated_method = spec.get_wrapper_function(&wrapper_ident, Some(cls), ctx)?;
+    let new_vectorcall = if has_pyclass_vectorcall() {
+        let vectorcall_ident =
+            syn::Ident::new("__pymethod_vectorcall___new____", Span::call_site());
+        associated_method.extend(spec.get_vectorcall_wrapper_function(
+            &vectorcall_ident,
+            Some(cls),
+            ctx,
+        )?);
+        Some(quote! {
+            #[allow(unknown_lints, non_local_definitions)]
+            impl PyClassNewVectorcall<#cls> for PyClassImplCollector<#cls> {
+                #[inline]
+                fn new_vectorcall(self) -> ::std::option::Option<#pyo3_path::ffi::vectorcallfunc> {
+                    unsafe extern "C" fn vectorcall(
+                        subtype: *mut #pyo3_path::ffi::PyObject,
+                        args: *const *mut #pyo3_path::ffi::PyObject,
+                        nargsf: usize,
+                        kwnames: *mut #pyo3_path::ffi::PyObject,
+                    ) -> *mut #pyo3_path::ffi::PyObject {
+                        #pyo3_path::impl_::trampoline::vectorcall_new(
+                            subtype,
+                            args,
+                            nargsf,
+                            kwnames,
+                            #cls::#vectorcall_ident
+                        )
+                    }
+                    ::std::option::Option::Some(vectorcall)
+                }
+            }
+        })
+    } else {
+        None
+    };
     // Use just the text_signature_call_signature() because the class' Python name
     // isn't known to `#[pymethods]` - that has to be attached at runtime from the PyClassImpl
     // trait implementation created by `#[pyclass]`.
@@ -358,6 +442,18 @@ pub fn impl_py_method_def_new(
         || quote!(::std::option::Option::None),
         |text_signature| quote!(::std::option::Option::Some(#text_signature)),
     );
+    let new_introspection = introspection::enabled().then(|| {
+        let introspection = introspection::function_introspection(spec, Some(cls), ctx);
+        quote! {
+            #[allow(unknown_lints, non_local_definitions)]
+            impl PyClassNewIntrospection<#cls> for PyClassImplCollector<#cls> {
+                #[inline]
+                fn new_introspection(self) -> ::std::option::Option<#pyo3_path::impl_::introspection::FunctionIntrospection> {
+                    ::std::option::Option::Some(#introspection)
+                }
+            }
+        }
+    });
     let slot_def = quote! {
         #pyo3_path::ffi::PyType_Slot {
             slot: #pyo3_path::ffi::Py_tp_new,
@@ -375,6 +471,10 @@ pub fn impl_py_method_def_new(
                             #text_signature_body
                         }
                     }
+                    #[allow(unknown_lints, non_local_definitions)]
+                    impl PyClassHasNew for #cls {}
+                    #new_introspection
+                    #new_vectorcall
 
                     #pyo3_path::impl_::trampoline::newfunc(
                         subtype,
@@ -401,7 +501,42 @@ fn impl_call_slot(cls: &syn::Type, mut spec: FnSpec<'_>, ctx: &Ctx) -> Result<Me
     spec.convention = CallingConvention::Varargs;
 
     let wrapper_ident = syn::Ident::new("__pymethod___call____", Span::call_site());
-    let associated_method = spec.get_wrapper_function(&wrapper_ident, Some(cls), ctx)?;
+    let mut associated_method = spec.get_wrapper_function(&wrapper_ident, Some(cls), ctx)?;
+    let call_vectorcall = if has_pyclass_vectorcall() {
+        let vectorcall_ident =
+            syn::Ident::new("__pymethod_vectorcall___call____", Span::call_site());
+        associated_method.extend(spec.get_vectorcall_wrapper_function(
+            &vectorcall_ident,
+            Some(cls),
+            ctx,
+        )?);
+        Some(quote! {
+            use #pyo3_path::impl_::pyclass::*;
+            #[allow(unknown_lints, non_local_definitions)]
+            impl PyClassCallVectorcall<#cls> for PyClassImplCollector<#cls> {
+                #[inline]
+                fn call_vectorcall(self) -> ::std::option::Option<#pyo3_path::ffi::vectorcallfunc> {
+                    unsafe extern "C" fn vectorcall(
+                        slf: *mut #pyo3_path::ffi::PyObject,
+                        args: *const *mut #pyo3_path::ffi::PyObject,
+                        nargsf: usize,
+                        kwnames: *mut #pyo3_path::ffi::PyObject,
+                    ) -> *mut #pyo3_path::ffi::PyObject {
+                        #pyo3_path::impl_::trampoline::vectorcallfunc(
+                            slf,
+                            args,
+                            nargsf,
+                            kwnames,
+                            #cls::#vectorcall_ident
+                        )
+                    }
+                    ::std::option::Option::Some(vectorcall)
+                }
+            }
+        })
+    } else {
+        None
+    };
     let slot_def = quote! {
         #pyo3_path::ffi::PyType_Slot {
             slot: #pyo3_path::ffi::Py_tp_call,
@@ -412,6 +547,8 @@ fn impl_call_slot(cls: &syn::Type, mut spec: FnSpec<'_>, ctx: &Ctx) -> Result<Me
                     kwargs: *mut #pyo3_path::ffi::PyObject,
                 ) -> *mut #pyo3_path::ffi::PyObject
                 {
+                    #call_vectorcall
+
                     #pyo3_path::impl_::trampoline::ternaryfunc(
                         slf,
                         args,
@@ -429,6 +566,76 @@ fn impl_call_slot(cls: &syn::Type, mut spec: FnSpec<'_>, ctx: &Ctx) -> Result<Me
     })
 }
 
+fn impl_init_slot(cls: &syn::Type, mut spec: FnSpec<'_>, ctx: &Ctx) -> Result<MethodAndSlotDef> {
+    let Ctx { pyo3_path, .. } = ctx;
+    if let Some(asyncness) = &spec.asyncness {
+        bail_spanned!(asyncness.span() => "`#[init]` cannot be an `async fn`");
+    }
+    if let syn::ReturnType::Type(_, ty) = &spec.output {
+        ensure_spanned!(
+            is_unit_result(ty),
+            ty.span() => "`#[init]` must return `()` or `PyResult<()>`"
+        );
+    }
+    // `__init__` is only run after `__new__`
+    let assert_has_new = quote_spanned! { spec.name.span() =>
+        #pyo3_path::impl_::pyclass::assert_pyclass_has_new::<#cls>();
+    };
+
+    // Like `__call__`, `tp_init` receives the arguments as a tuple and a dict.
+    spec.convention = CallingConvention::Varargs;
+
+    let wrapper_ident = syn::Ident::new("__pymethod___init____", Span::call_site());
+    let associated_method = spec.get_wrapper_function(&wrapper_ident, Some(cls), ctx)?;
+    let slot_def = quote! {
+        #pyo3_path::ffi::PyType_Slot {
+            slot: #pyo3_path::ffi::Py_tp_init,
+            pfunc: {
+                unsafe extern "C" fn trampoline(
+                    slf: *mut #pyo3_path::ffi::PyObject,
+                    args: *mut #pyo3_path::ffi::PyObject,
+                    kwargs: *mut #pyo3_path::ffi::PyObject,
+                ) -> ::std::os::raw::c_int
+                {
+                    const _: () = #assert_has_new
+                    #pyo3_path::impl_::trampoline::initproc(
+                        slf,
+                        args,
+                        kwargs,
+                        #cls::#wrapper_ident
+                    )
+                }
+                trampoline
+            } as #pyo3_path::ffi::initproc as _
+        }
+    };
+    Ok(MethodAndSlotDef {
+        associated_method,
+        slot_def,
+    })
+}
+
+/// Returns whether `ty` is `()` or a `Result` of `()`, such as `PyResult<()>`.
+fn is_unit_result(ty: &syn::Type) -> bool {
+    match utils::unwrap_ty_group(ty) {
+        syn::Type::Tuple(tuple) => tuple.elems.is_empty(),
+        syn::Type::Path(path) => match path.path.segments.last() {
+            Some(segment) if segment.ident == "Result" || segment.ident == "PyResult" => {
+                match &segment.arguments {
+                    syn::PathArguments::AngleBracketed(arguments) => matches!(
+                        arguments.args.first(),
+                        Some(syn::GenericArgument::Type(syn::Type::Tuple(tuple)))
+                            if tuple.elems.is_empty()
+                    ),
+                    _ => false,
+                }
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
 fn impl_traverse_slot(
     cls: &syn::Type,
     spec: &FnSpec<'_>,
@@ -813,9 +1020,17 @@ pub fn impl_py_getter_def(
 
             // TODO: on MSRV 1.77+, we can use `::std::mem::offset_of!` here, and it should
             // make it possible for the `MaybeRuntimePyMethodDef` to be a `Static` variant.
+            let mut generate = quote!(GENERATOR.generate(#python_name, #doc));
+            if introspection::enabled() {
+                let introspection =
+                    introspection::getter_introspection(&parse_quote!(-> #ty), Some(cls), ctx);
+                generate = quote! {
+                    #pyo3_path::impl_::introspection::with_getter_introspection(#generate, #introspection)
+                };
+            }
             let generator = quote_spanned! { ty.span() =>
                 #pyo3_path::impl_::pyclass::MaybeRuntimePyMethodDef::Runtime(
-                    || GENERATOR.generate(#python_name, #doc)
+                    || #generate
                 )
             };
             // This is separate so that the unsafe below does not inherit the span and thus does not
@@ -876,6 +1091,11 @@ pub fn impl_py_getter_def(
                 }
             };
 
+            let add_introspection = introspection::enabled().then(|| {
+                let introspection =
+                    introspection::getter_introspection(&spec.output, Some(cls), ctx);
+                quote!(.with_introspection(#introspection))
+            });
             let method_def = quote! {
                 #cfg_attrs
                 #pyo3_path::impl_::pyclass::MaybeRuntimePyMethodDef::Static(
@@ -884,7 +1104,7 @@ pub fn impl_py_getter_def(
                             #python_name,
                             #cls::#wrapper_ident,
                             #doc
-                        )
+                        ) #add_introspection
                     )
                 )
             };
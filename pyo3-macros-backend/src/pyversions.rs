@@ -1,6 +1,19 @@
-use pyo3_build_config::PythonVersion;
+use pyo3_build_config::{PythonImplementation, PythonVersion};
 
 pub fn is_abi3_before(major: u8, minor: u8) -> bool {
     let config = pyo3_build_config::get();
     config.abi3 && config.version < PythonVersion { major, minor }
 }
+
+pub fn is_python_before(major: u8, minor: u8) -> bool {
+    pyo3_build_config::get().version < PythonVersion { major, minor }
+}
+
+/// Whether `#[pyclass]` types and instances can be called through vectorcall, which requires
+/// patching the type object outside of the limited API.
+pub fn has_pyclass_vectorcall() -> bool {
+    let config = pyo3_build_config::get();
+    !config.abi3
+        && config.implementation == PythonImplementation::CPython
+        && config.version >= PythonVersion { major: 3, minor: 9 }
+}
@@ -24,9 +24,29 @@ def test_multiple_imports_same_interpreter_ok():
     spec = importlib.util.find_spec("pyo3_pytests.pyo3_pytests")
 
     module = importlib.util.module_from_spec(spec)
+    spec.loader.exec_module(module)
     assert dir(module) == dir(pyo3_pytests.pyo3_pytests)
 
 
+@pytest.mark.xfail(
+    platform.python_implementation() == "CPython" and sys.version_info < (3, 9),
+    reason="Cannot identify subinterpreters on Python older than 3.9",
+)
+@pytest.mark.skipif(
+    platform.python_implementation() in ("PyPy", "GraalVM"),
+    reason="PyPy and GraalPy only support single-phase initialization",
+)
+def test_reimport_creates_new_module():
+    original = sys.modules.pop("pyo3_pytests.pyo3_pytests")
+    try:
+        module = importlib.import_module("pyo3_pytests.pyo3_pytests")
+        assert module is not original
+        assert dir(module) == dir(original)
+    finally:
+        sys.modules["pyo3_pytests.pyo3_pytests"] = original
+        pyo3_pytests.pyo3_pytests = original
+
+
 @pytest.mark.xfail(
     platform.python_implementation() == "CPython" and sys.version_info < (3, 9),
     reason="Cannot identify subinterpreters on Python older than 3.9",
@@ -38,7 +58,7 @@ def test_multiple_imports_same_interpreter_ok():
 def test_import_in_subinterpreter_forbidden():
     sub_interpreter = subinterpreters.create()
     if sys.version_info < (3, 12):
-        expected_error = "PyO3 modules do not yet support subinterpreters, see https://github.com/PyO3/pyo3/issues/576"
+        expected_error = "this PyO3 module does not support loading in subinterpreters"
     else:
         expected_error = "module pyo3_pytests.pyo3_pytests does not support loading in subinterpreters"
 
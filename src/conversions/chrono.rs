@@ -44,7 +44,7 @@
 use crate::conversion::IntoPyObject;
 use crate::exceptions::{PyTypeError, PyUserWarning, PyValueError};
 #[cfg(Py_LIMITED_API)]
-use crate::sync::GILOnceCell;
+use crate::sync::InterpreterOnceCell;
 use crate::types::any::PyAnyMethods;
 #[cfg(not(Py_LIMITED_API))]
 use crate::types::datetime::timezone_from_offset;
@@ -837,7 +837,7 @@ impl DatetimeTypes {
     }
 
     fn try_get(py: Python<'_>) -> PyResult<&Self> {
-        static TYPES: GILOnceCell<DatetimeTypes> = GILOnceCell::new();
+        static TYPES: InterpreterOnceCell<DatetimeTypes> = InterpreterOnceCell::new();
         TYPES.get_or_try_init(py, || {
             let datetime = py.import("datetime")?;
             let timezone = datetime.getattr("timezone")?;
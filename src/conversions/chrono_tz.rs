@@ -37,7 +37,7 @@
 use crate::conversion::IntoPyObject;
 use crate::exceptions::PyValueError;
 use crate::pybacked::PyBackedStr;
-use crate::sync::GILOnceCell;
+use crate::sync::InterpreterOnceCell;
 use crate::types::{any::PyAnyMethods, PyType};
 use crate::{intern, Bound, FromPyObject, Py, PyAny, PyErr, PyObject, PyResult, Python};
 #[allow(deprecated)]
@@ -67,7 +67,7 @@ impl<'py> IntoPyObject<'py> for Tz {
     type Error = PyErr;
 
     fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
-        static ZONE_INFO: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+        static ZONE_INFO: InterpreterOnceCell<Py<PyType>> = InterpreterOnceCell::new();
         ZONE_INFO
             .import(py, "zoneinfo", "ZoneInfo")
             .and_then(|obj| obj.call1((self.name(),)))
@@ -45,7 +45,7 @@
 
 use crate::conversion::IntoPyObject;
 use crate::ffi;
-use crate::sync::GILOnceCell;
+use crate::sync::InterpreterOnceCell;
 use crate::types::any::PyAnyMethods;
 use crate::types::PyType;
 use crate::{Bound, FromPyObject, Py, PyAny, PyErr, PyObject, PyResult, Python};
@@ -56,7 +56,7 @@ use crate::{IntoPy, ToPyObject};
 use num_bigint::BigInt;
 use num_rational::Ratio;
 
-static FRACTION_CLS: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+static FRACTION_CLS: InterpreterOnceCell<Py<PyType>> = InterpreterOnceCell::new();
 
 fn get_fraction_cls(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
     FRACTION_CLS.import(py, "fractions", "Fraction")
@@ -1,3 +1,5 @@
+#[cfg(feature = "experimental-inspect")]
+use crate::inspect::types::TypeInfo;
 use crate::{
     conversion::IntoPyObject, ffi, types::any::PyAnyMethods, AsPyPointer, Bound, BoundObject,
     FromPyObject, PyAny, PyObject, PyResult, Python,
@@ -44,6 +46,11 @@ where
             },
         )
     }
+
+    #[cfg(feature = "experimental-inspect")]
+    fn type_output() -> TypeInfo {
+        TypeInfo::optional_of(T::type_output())
+    }
 }
 
 impl<'a, 'py, T> IntoPyObject<'py> for &'a Option<T>
@@ -71,6 +78,11 @@ where
             obj.extract().map(Some)
         }
     }
+
+    #[cfg(feature = "experimental-inspect")]
+    fn type_input() -> TypeInfo {
+        TypeInfo::optional_of(T::type_input())
+    }
 }
 
 /// Convert `None` into a null pointer.
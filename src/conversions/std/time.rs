@@ -1,6 +1,6 @@
 use crate::conversion::IntoPyObject;
 use crate::exceptions::{PyOverflowError, PyValueError};
-use crate::sync::GILOnceCell;
+use crate::sync::InterpreterOnceCell;
 use crate::types::any::PyAnyMethods;
 #[cfg(Py_LIMITED_API)]
 use crate::types::PyType;
@@ -93,7 +93,7 @@ impl<'py> IntoPyObject<'py> for Duration {
         }
         #[cfg(Py_LIMITED_API)]
         {
-            static TIMEDELTA: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+            static TIMEDELTA: InterpreterOnceCell<Py<PyType>> = InterpreterOnceCell::new();
             TIMEDELTA
                 .import(py, "datetime", "timedelta")?
                 .call1((days, seconds, microseconds))
@@ -176,7 +176,7 @@ impl<'py> IntoPyObject<'py> for &SystemTime {
 }
 
 fn unix_epoch_py(py: Python<'_>) -> PyResult<&PyObject> {
-    static UNIX_EPOCH: GILOnceCell<PyObject> = GILOnceCell::new();
+    static UNIX_EPOCH: InterpreterOnceCell<PyObject> = InterpreterOnceCell::new();
     UNIX_EPOCH.get_or_try_init(py, || {
         #[cfg(not(Py_LIMITED_API))]
         {
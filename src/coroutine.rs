@@ -11,17 +11,33 @@ use std::{
 use pyo3_macros::{pyclass, pymethods};
 
 use crate::{
-    coroutine::{cancel::ThrowCallback, waker::AsyncioWaker},
+    coroutine::{
+        cancel::ThrowCallback,
+        delegate::{with_poll_context, Delegation, PollContext},
+        waker::{detect_async_library, WakerAdapter},
+    },
     exceptions::{PyAttributeError, PyRuntimeError, PyStopIteration},
-    panic::PanicException,
-    types::{string::PyStringMethods, PyIterator, PyString},
+    panic::{PanicException, PanicPolicy},
+    types::{string::PyStringMethods, PyString},
     Bound, IntoPyObject, IntoPyObjectExt, Py, PyAny, PyErr, PyObject, PyResult, Python,
 };
 
 pub(crate) mod cancel;
+mod context;
+mod delegate;
+mod future;
+mod runtime;
+mod stream;
+mod trio;
 mod waker;
 
 pub use cancel::CancelHandle;
+pub use context::AsyncWithContext;
+pub use future::PyFuture;
+pub use runtime::{future_into_py, set_runtime, Runtime};
+pub use stream::AsyncIterator;
+pub use trio::{Trio, TrioWaker};
+pub use waker::{register_async_library, AsyncLibrary, Asyncio, AsyncioWaker, CoroutineWaker};
 
 const COROUTINE_REUSED_ERROR: &str = "cannot reuse already awaited coroutine";
 
@@ -31,8 +47,15 @@ pub struct Coroutine {
     name: Option<Py<PyString>>,
     qualname_prefix: Option<&'static str>,
     throw_callback: Option<ThrowCallback>,
+    panic_policy: PanicPolicy,
     future: Option<Pin<Box<dyn Future<Output = PyResult<PyObject>> + Send>>>,
-    waker: Option<Arc<AsyncioWaker>>,
+    library: Option<Arc<dyn AsyncLibrary>>,
+    waker: Option<Arc<WakerAdapter>>,
+    /// Python awaitable the Rust future waits for, awaited by delegation.
+    delegation: Option<Delegation>,
+    /// Whether the Rust future can take the exceptions thrown into the coroutine, because it runs
+    /// the body of an `async_with_context`.
+    catching: bool,
 }
 
 // Safety: `Coroutine` is allowed to be `Sync` even though the future is not,
@@ -42,14 +65,17 @@ unsafe impl Sync for Coroutine {}
 impl Coroutine {
     ///  Wrap a future into a Python coroutine.
     ///
-    /// Coroutine `send` polls the wrapped future, ignoring the value passed
-    /// (should always be `None` anyway).
+    /// Coroutine `send` polls the wrapped future, after passing the value sent to
+    /// [`CoroutineWaker::resume`] (`None` with asyncio, the task outcome with Trio).
     ///
     /// `Coroutine `throw` drop the wrapped future and reraise the exception passed
+    ///
+    /// Panics of the future are handled with `panic_policy`.
     pub(crate) fn new<'py, F, T, E>(
         name: Option<Bound<'py, PyString>>,
         qualname_prefix: Option<&'static str>,
         throw_callback: Option<ThrowCallback>,
+        panic_policy: PanicPolicy,
         future: F,
     ) -> Self
     where
@@ -66,60 +92,114 @@ impl Coroutine {
             name: name.map(Bound::unbind),
             qualname_prefix,
             throw_callback,
+            panic_policy,
             future: Some(Box::pin(wrap)),
+            library: None,
             waker: None,
+            delegation: None,
+            catching: false,
         }
     }
 
-    fn poll(&mut self, py: Python<'_>, throw: Option<PyObject>) -> PyResult<PyObject> {
-        // raise if the coroutine has already been run to completion
-        let future_rs = match self.future {
-            Some(ref mut fut) => fut,
-            None => return Err(PyRuntimeError::new_err(COROUTINE_REUSED_ERROR)),
-        };
-        // reraise thrown exception it
-        match (throw, &self.throw_callback) {
-            (Some(exc), Some(cb)) => cb.throw(exc),
-            (Some(exc), None) => {
-                self.close();
+    fn poll(&mut self, py: Python<'_>, mut throw: Option<PyObject>) -> PyResult<PyObject> {
+        loop {
+            // raise if the coroutine has already been run to completion
+            if self.future.is_none() {
+                return Err(PyRuntimeError::new_err(COROUTINE_REUSED_ERROR));
+            }
+            let mut context = PollContext::default();
+            // reraise thrown exception, unless the body of an `async_with_context` takes it
+            match (throw.take(), &self.throw_callback) {
+                (Some(exc), _) if self.catching => context.thrown = Some(exc),
+                (Some(exc), Some(cb)) => cb.throw(exc),
+                (Some(exc), None) => {
+                    self.close(py);
+                    return Err(PyErr::from_value(exc.into_bound(py)));
+                }
+                (None, _) => {}
+            }
+            // create a new waker, or try to reset it in place
+            if let Some(waker) = self.waker.as_mut().and_then(Arc::get_mut) {
+                waker.0.reset();
+            } else {
+                // the async library is detected once, when the coroutine is first polled
+                let library = self.library.get_or_insert_with(|| detect_async_library(py));
+                self.waker = Some(Arc::new(WakerAdapter(library.waker(py)?)));
+            }
+            let waker = Waker::from(self.waker.clone().unwrap());
+            // poll the Rust future and forward its results if ready
+            // polling is UnwindSafe because the future is dropped in case of panic
+            let future_rs = self.future.as_mut().unwrap();
+            let poll = || future_rs.as_mut().poll(&mut Context::from_waker(&waker));
+            let (poll, context) = with_poll_context(context, || {
+                panic::catch_unwind(panic::AssertUnwindSafe(poll))
+            });
+            self.catching = context.catching;
+            let delegation = context.delegation;
+            if let Some(exc) = context.thrown {
+                // the body of the `async_with_context` is not running anymore
+                close_delegation(py, delegation);
+                self.close(py);
                 return Err(PyErr::from_value(exc.into_bound(py)));
             }
-            (None, _) => {}
-        }
-        // create a new waker, or try to reset it in place
-        if let Some(waker) = self.waker.as_mut().and_then(Arc::get_mut) {
-            waker.reset();
-        } else {
-            self.waker = Some(Arc::new(AsyncioWaker::new()));
-        }
-        let waker = Waker::from(self.waker.clone().unwrap());
-        // poll the Rust future and forward its results if ready
-        // polling is UnwindSafe because the future is dropped in case of panic
-        let poll = || future_rs.as_mut().poll(&mut Context::from_waker(&waker));
-        match panic::catch_unwind(panic::AssertUnwindSafe(poll)) {
-            Ok(Poll::Ready(res)) => {
-                self.close();
-                return Err(PyStopIteration::new_err((res?,)));
+            match poll {
+                Ok(Poll::Ready(res)) => {
+                    close_delegation(py, delegation);
+                    self.close(py);
+                    return Err(PyStopIteration::new_err((res?,)));
+                }
+                Err(err) => {
+                    close_delegation(py, delegation);
+                    self.close(py);
+                    return Err(PanicException::from_panic_payload(
+                        py,
+                        err,
+                        self.panic_policy,
+                    ));
+                }
+                _ => {}
             }
-            Err(err) => {
-                self.close();
-                return Err(PanicException::from_panic_payload(err));
+            // await the Python awaitable the future waits for, if any
+            match delegation {
+                Some(delegation) if !delegation.is_abandoned() => {
+                    match delegation.advance(py, Ok(py.None().bind(py))) {
+                        Some(yielded) => {
+                            self.delegation = Some(delegation);
+                            return Ok(yielded);
+                        }
+                        // the awaitable is already done, so the future can be polled again
+                        None => continue,
+                    }
+                }
+                delegation => close_delegation(py, delegation),
             }
-            _ => {}
+            // otherwise, suspend the coroutine until the waker is woken
+            return self.waker.as_ref().unwrap().0.suspend(py);
         }
-        // otherwise, initialize the waker `asyncio.Future`
-        if let Some(future) = self.waker.as_ref().unwrap().initialize_future(py)? {
-            // `asyncio.Future` must be awaited; fortunately, it implements `__iter__ = __await__`
-            // and will yield itself if its result has not been set in polling above
-            if let Some(future) = PyIterator::from_object(future).unwrap().next() {
-                // future has not been leaked into Python for now, and Rust code can only call
-                // `set_result(None)` in `Wake` implementation, so it's safe to unwrap
-                return Ok(future.unwrap().into());
+    }
+
+    /// Resumes the awaitable awaited by delegation with the value sent to the coroutine, or the
+    /// exception thrown into it, and polls the Rust future again once the awaitable is done.
+    fn resume_delegation(
+        &mut self,
+        py: Python<'_>,
+        value: Result<&Bound<'_, PyAny>, PyObject>,
+    ) -> PyResult<PyObject> {
+        let delegation = self.delegation.take().unwrap();
+        match delegation.advance(py, value) {
+            Some(yielded) => {
+                self.delegation = Some(delegation);
+                Ok(yielded)
             }
+            None => self.poll(py, None),
         }
-        // if waker has been waken during future polling, this is roughly equivalent to
-        // `await asyncio.sleep(0)`, so just yield `None`.
-        Ok(py.None())
+    }
+}
+
+/// Closes the awaitable of a delegation which will not be awaited.
+fn close_delegation(py: Python<'_>, delegation: Option<Delegation>) {
+    if let Some(delegation) = delegation {
+        delegation.close(py);
     }
 }
 
@@ -145,15 +225,30 @@ impl Coroutine {
         }
     }
 
-    fn send(&mut self, py: Python<'_>, _value: &Bound<'_, PyAny>) -> PyResult<PyObject> {
-        self.poll(py, None)
+    fn send(&mut self, py: Python<'_>, value: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        if self.delegation.is_some() {
+            return self.resume_delegation(py, Ok(value));
+        }
+        // the value sent by the event loop may carry an exception, e.g. a Trio cancellation
+        let resumed = match &self.waker {
+            Some(waker) => waker.0.resume(value),
+            None => Ok(()),
+        };
+        match resumed {
+            Ok(()) => self.poll(py, None),
+            Err(err) => self.poll(py, Some(err.into_value(py).into_any())),
+        }
     }
 
     fn throw(&mut self, py: Python<'_>, exc: PyObject) -> PyResult<PyObject> {
+        if self.delegation.is_some() {
+            return self.resume_delegation(py, Err(exc));
+        }
         self.poll(py, Some(exc))
     }
 
-    fn close(&mut self) {
+    fn close(&mut self, py: Python<'_>) {
+        close_delegation(py, self.delegation.take());
         // the Rust future is dropped, and the field set to `None`
         // to indicate the coroutine has been run to completion
         drop(self.future.take());
@@ -164,6 +259,9 @@ impl Coroutine {
     }
 
     fn __next__(&mut self, py: Python<'_>) -> PyResult<PyObject> {
+        if self.delegation.is_some() {
+            return self.resume_delegation(py, Ok(py.None().bind(py)));
+        }
         self.poll(py, None)
     }
 }
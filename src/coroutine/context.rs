@@ -0,0 +1,157 @@
+use crate::coroutine::delegate::{with_current, Delegated};
+use crate::coroutine::runtime::CatchUnwind;
+use crate::panic::PanicException;
+use crate::types::any::{chain_exit_error, exit_args, PyAnyMethods};
+use crate::{intern, PyErr, PyObject, PyResult, Python};
+use std::any::Any;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::{mem, panic};
+
+/// Future returned by [`PyAnyMethods::async_with_context`].
+pub struct AsyncWithContext<F, Fut, R> {
+    manager: PyObject,
+    state: State<F, Fut, R>,
+}
+
+enum State<F, Fut, R> {
+    Start(F),
+    Enter {
+        aexit: PyObject,
+        enter: Delegated,
+        f: F,
+    },
+    Body {
+        aexit: PyObject,
+        body: CatchUnwind<Fut>,
+    },
+    Exit {
+        exit: Delegated,
+        outcome: Outcome<R>,
+    },
+    Done,
+}
+
+enum Outcome<R> {
+    Ok(R),
+    Err(PyErr),
+    Panic(Box<dyn Any + Send + 'static>),
+}
+
+impl<F, Fut, R> AsyncWithContext<F, Fut, R> {
+    pub(crate) fn new(manager: PyObject, f: F) -> Self {
+        Self {
+            manager,
+            state: State::Start(f),
+        }
+    }
+}
+
+// `F` and `R` are never pinned, and the body is boxed by `CatchUnwind`
+impl<F, Fut, R> Unpin for AsyncWithContext<F, Fut, R> {}
+
+impl<F, Fut, R> Future for AsyncWithContext<F, Fut, R>
+where
+    F: FnOnce(PyObject) -> Fut,
+    Fut: Future<Output = PyResult<R>>,
+{
+    type Output = PyResult<Option<R>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        loop {
+            this.state = match mem::replace(&mut this.state, State::Done) {
+                State::Start(f) => {
+                    let aenter = Python::with_gil(|py| {
+                        let (aenter, aexit) = this.manager.bind(py).context_manager_methods(
+                            intern!(py, "__aenter__"),
+                            intern!(py, "__aexit__"),
+                            "asynchronous context manager",
+                        )?;
+                        PyResult::Ok((aexit.unbind(), Delegated::new(aenter.call0())))
+                    });
+                    match aenter {
+                        Ok((aexit, enter)) => State::Enter { aexit, enter, f },
+                        Err(err) => return Poll::Ready(Err(err)),
+                    }
+                }
+                State::Enter {
+                    aexit,
+                    mut enter,
+                    f,
+                } => match Pin::new(&mut enter).poll(cx) {
+                    Poll::Ready(Ok(value)) => State::Body {
+                        aexit,
+                        body: CatchUnwind(Box::pin(f(value))),
+                    },
+                    Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                    Poll::Pending => {
+                        this.state = State::Enter { aexit, enter, f };
+                        return Poll::Pending;
+                    }
+                },
+                State::Body { aexit, mut body } => {
+                    // the body is polled even if an exception has been thrown into the coroutine,
+                    // so that it is taken by the innermost `async_with_context`
+                    with_current(|context| context.catching = true);
+                    let poll = Pin::new(&mut body).poll(cx);
+                    let thrown = with_current(|context| context.thrown.take()).flatten();
+                    let outcome = match (poll, thrown) {
+                        (Poll::Ready(Err(payload)), _) => Outcome::Panic(payload),
+                        // the body is dropped, as if the exception was raised at its `await`
+                        (_, Some(exc)) => Outcome::Err(Python::with_gil(|py| {
+                            PyErr::from_value(exc.into_bound(py))
+                        })),
+                        (Poll::Ready(Ok(Ok(result))), None) => Outcome::Ok(result),
+                        (Poll::Ready(Ok(Err(err))), None) => Outcome::Err(err),
+                        (Poll::Pending, None) => {
+                            this.state = State::Body { aexit, body };
+                            return Poll::Pending;
+                        }
+                    };
+                    drop(body);
+                    let exit = Python::with_gil(|py| {
+                        let aexit = aexit.bind(py);
+                        Delegated::new(match &outcome {
+                            Outcome::Ok(_) => aexit.call1((py.None(), py.None(), py.None())),
+                            Outcome::Err(err) => aexit.call1(exit_args(py, err)),
+                            Outcome::Panic(payload) => {
+                                let err = PanicException::new_from_payload(&**payload);
+                                aexit.call1(exit_args(py, &err))
+                            }
+                        })
+                    });
+                    State::Exit { exit, outcome }
+                }
+                State::Exit { mut exit, outcome } => {
+                    let exited = match Pin::new(&mut exit).poll(cx) {
+                        Poll::Ready(exited) => exited,
+                        Poll::Pending => {
+                            this.state = State::Exit { exit, outcome };
+                            return Poll::Pending;
+                        }
+                    };
+                    let payload = match outcome {
+                        Outcome::Ok(result) => return Poll::Ready(exited.map(|_| Some(result))),
+                        Outcome::Err(err) => {
+                            return Poll::Ready(Python::with_gil(|py| match exited {
+                                Ok(suppress) if suppress.bind(py).is_truthy()? => Ok(None),
+                                Ok(_) => Err(err),
+                                Err(exit_err) => Err(chain_exit_error(py, exit_err, err)),
+                            }))
+                        }
+                        Outcome::Panic(payload) => payload,
+                    };
+                    if let Err(exit_err) = exited {
+                        Python::with_gil(|py| {
+                            exit_err.write_unraisable(py, Some(this.manager.bind(py)))
+                        });
+                    }
+                    panic::resume_unwind(payload)
+                }
+                State::Done => panic!("`AsyncWithContext` polled after completion"),
+            };
+        }
+    }
+}
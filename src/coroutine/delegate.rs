@@ -0,0 +1,171 @@
+use crate::exceptions::PyRuntimeError;
+use crate::types::any::PyAnyMethods;
+use crate::types::iterator::{PyIteratorMethods, PySendResult};
+use crate::types::PyIterator;
+use crate::{intern, Bound, Py, PyAny, PyObject, PyResult, Python};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// State shared by a [`Coroutine`](super::Coroutine) with its Rust future while it is polled.
+#[derive(Default)]
+pub(super) struct PollContext {
+    /// Awaitable the Rust future waits for, which the coroutine awaits by delegation.
+    pub(super) delegation: Option<Delegation>,
+    /// Exception thrown into the coroutine, for the innermost `async_with_context` body.
+    pub(super) thrown: Option<PyObject>,
+    /// Whether an `async_with_context` body has been polled, which can take a thrown exception.
+    pub(super) catching: bool,
+}
+
+thread_local! {
+    static POLL_CONTEXT: RefCell<Option<PollContext>> = const { RefCell::new(None) };
+}
+
+/// Runs `f`, which polls the Rust future of a coroutine, with `context` as the poll context of
+/// the current thread, and returns the context as left by the future.
+///
+/// `f` must not unwind, so that the context of an outer coroutine is restored.
+pub(super) fn with_poll_context<R>(
+    context: PollContext,
+    f: impl FnOnce() -> R,
+) -> (R, PollContext) {
+    let outer = POLL_CONTEXT.with(|cell| cell.replace(Some(context)));
+    let result = f();
+    let context = POLL_CONTEXT.with(|cell| cell.replace(outer));
+    (result, context.unwrap_or_default())
+}
+
+/// Calls `f` with the poll context of the coroutine being polled, if any.
+pub(super) fn with_current<R>(f: impl FnOnce(&mut PollContext) -> R) -> Option<R> {
+    POLL_CONTEXT.with(|cell| cell.borrow_mut().as_mut().map(f))
+}
+
+#[derive(Default)]
+struct Shared {
+    /// Outcome of the awaitable, once it is done.
+    result: Option<PyResult<PyObject>>,
+    /// Waker of the last poll of the [`Delegated`] future.
+    waker: Option<Waker>,
+}
+
+/// Python awaitable awaited by a [`Coroutine`](super::Coroutine) on behalf of its Rust future.
+pub(super) struct Delegation {
+    /// Iterator returned by `__await__`.
+    iter: Py<PyIterator>,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Delegation {
+    /// Returns whether the [`Delegated`] future has been dropped, so that the outcome of the
+    /// awaitable is not needed anymore.
+    pub(super) fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.shared) == 1
+    }
+
+    /// Sends `value` into the awaitable, or throws the exception `Err(exc)` into it, and returns
+    /// the value it yields, or `None` if it is done.
+    pub(super) fn advance(
+        &self,
+        py: Python<'_>,
+        value: Result<&Bound<'_, PyAny>, PyObject>,
+    ) -> Option<PyObject> {
+        let iter = self.iter.bind(py);
+        let result = match value {
+            Ok(value) => iter.send(value),
+            Err(exc) => {
+                let err = crate::PyErr::from_value(exc.into_bound(py));
+                // like `await`, close iterators which cannot handle the exception
+                match iter.hasattr(intern!(py, "throw")) {
+                    Ok(true) => iter.throw(err),
+                    Ok(false) => iter.close().and(Err(err)),
+                    Err(hasattr_err) => Err(hasattr_err),
+                }
+            }
+        };
+        let result = match result {
+            Ok(PySendResult::Next(value)) => return Some(value.unbind()),
+            Ok(PySendResult::Return(value)) => Ok(value.unbind()),
+            Err(err) => Err(err),
+        };
+        let mut shared = self.shared.lock().unwrap();
+        shared.result = Some(result);
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+        None
+    }
+
+    /// Closes the awaitable, when it is not awaited until it is done.
+    pub(super) fn close(&self, py: Python<'_>) {
+        if let Err(err) = self.iter.bind(py).close() {
+            err.write_unraisable(py, Some(self.iter.bind(py)));
+        }
+    }
+}
+
+/// Rust future awaiting a Python awaitable by delegation, as `await` does in Python: the
+/// [`Coroutine`](super::Coroutine) polling the future yields the values yielded by the awaitable
+/// to its event loop, and sends into the awaitable the values and exceptions it receives. This
+/// works with any async library, unlike [`PyFuture`](super::PyFuture), which requires asyncio.
+///
+/// The awaitable is started when the future is first polled, which must be by a `Coroutine`.
+pub(super) struct Delegated {
+    /// Iterator of the awaitable, until it is handed over to the coroutine.
+    iter: Option<PyResult<Py<PyIterator>>>,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Delegated {
+    /// Awaits `awaitable`, or fails with its error if it could not be created.
+    pub(super) fn new(awaitable: PyResult<Bound<'_, PyAny>>) -> Self {
+        let iter = awaitable.and_then(|awaitable| {
+            let py = awaitable.py();
+            let iter = awaitable.call_method0(intern!(py, "__await__"))?;
+            Ok(iter.downcast_into::<PyIterator>()?.unbind())
+        });
+        Self {
+            iter: Some(iter),
+            shared: Arc::default(),
+        }
+    }
+}
+
+impl Future for Delegated {
+    type Output = PyResult<PyObject>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(result) = self.shared.lock().unwrap().result.take() {
+            return Poll::Ready(result);
+        }
+        if let Some(iter) = self.iter.take() {
+            let iter = match iter {
+                Ok(iter) => iter,
+                Err(err) => return Poll::Ready(Err(err)),
+            };
+            let shared = self.shared.clone();
+            let mut iter = Some(iter);
+            let registered = with_current(|context| {
+                // the coroutine awaits one awaitable at a time
+                if context.delegation.is_none() {
+                    context.delegation = iter.take().map(|iter| Delegation { iter, shared });
+                }
+            });
+            if registered.is_none() {
+                return Poll::Ready(Err(PyRuntimeError::new_err(
+                    "Python awaitables can only be awaited by Rust futures run by a PyO3 coroutine",
+                )));
+            }
+            if iter.is_some() {
+                // another awaitable is awaited, try again once it is done
+                self.iter = iter.map(Ok);
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+        }
+        self.shared.lock().unwrap().waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
@@ -0,0 +1,154 @@
+use crate::sync::InterpreterOnceCell;
+use crate::types::any::PyAnyMethods;
+use crate::types::{IntoPyDict, PyCFunction, PyModule};
+use crate::{intern, Bound, Py, PyAny, PyObject, PyResult, Python};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// Returns the `asyncio` module of the current interpreter.
+pub(super) fn asyncio(py: Python<'_>) -> PyResult<&Bound<'_, PyModule>> {
+    static ASYNCIO: InterpreterOnceCell<Py<PyModule>> = InterpreterOnceCell::new();
+    ASYNCIO
+        .get_or_try_init(py, || Ok(py.import("asyncio")?.unbind()))
+        .map(|asyncio| asyncio.bind(py))
+}
+
+/// Rust [`Future`] awaiting a Python awaitable.
+///
+/// The awaitable is scheduled on an asyncio event loop when the `PyFuture` is created, and the
+/// future resolves to its result, or to the exception it raised. Dropping the `PyFuture` before
+/// it is resolved cancels the awaitable.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![allow(dead_code)]
+/// use pyo3::coroutine::PyFuture;
+/// use pyo3::prelude::*;
+///
+/// #[pyfunction]
+/// async fn call_async(callback: PyObject) -> PyResult<PyObject> {
+///     // the awaitable is created with the GIL, but awaited without holding it
+///     let future = Python::with_gil(|py| PyFuture::new(&callback.bind(py).call0()?))?;
+///     future.await
+/// }
+/// ```
+pub struct PyFuture {
+    /// `asyncio.Future` or `concurrent.futures.Future` running the awaitable.
+    future: PyObject,
+    /// Event loop of an `asyncio.Future`, which must be cancelled from the loop thread.
+    event_loop: Option<PyObject>,
+    /// Waker of the last poll, woken by a done callback of `future`.
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+impl PyFuture {
+    /// Schedules `awaitable` on the event loop running in the current thread.
+    ///
+    /// Fails with `RuntimeError` if there is no running event loop, like `asyncio.get_running_loop`.
+    pub fn new(awaitable: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let py = awaitable.py();
+        let event_loop = asyncio(py)?.call_method0(intern!(py, "get_running_loop"))?;
+        Self::with_event_loop(awaitable, &event_loop)
+    }
+
+    /// Schedules `awaitable` on `event_loop`.
+    ///
+    /// This can be called from a thread other than the one running `event_loop`, for example by
+    /// Rust code running on a [`Runtime`](super::Runtime), in which case `awaitable` must be a
+    /// coroutine, which is scheduled with `asyncio.run_coroutine_threadsafe`.
+    pub fn with_event_loop(
+        awaitable: &Bound<'_, PyAny>,
+        event_loop: &Bound<'_, PyAny>,
+    ) -> PyResult<Self> {
+        let py = awaitable.py();
+        let asyncio = asyncio(py)?;
+        let running_loop = asyncio.call_method0(intern!(py, "_get_running_loop"))?;
+        let (future, event_loop) = if running_loop.is(event_loop) {
+            let kwargs = [(intern!(py, "loop"), event_loop)].into_py_dict(py)?;
+            let future = asyncio
+                .getattr(intern!(py, "ensure_future"))?
+                .call((awaitable,), Some(&kwargs))?;
+            (future, Some(event_loop.clone().unbind()))
+        } else {
+            let future = asyncio.call_method1(
+                intern!(py, "run_coroutine_threadsafe"),
+                (awaitable, event_loop),
+            )?;
+            (future, None)
+        };
+
+        let waker = Arc::new(Mutex::new(None::<Waker>));
+        let callback_waker = waker.clone();
+        let wake = PyCFunction::new_closure(py, None, None, move |_, _| {
+            if let Some(waker) = callback_waker.lock().unwrap().take() {
+                waker.wake();
+            }
+        })?;
+        future.call_method1(intern!(py, "add_done_callback"), (wake,))?;
+        Ok(Self {
+            future: future.unbind(),
+            event_loop,
+            waker,
+        })
+    }
+
+    fn poll_result(&self, py: Python<'_>, waker: &Waker) -> PyResult<Option<PyObject>> {
+        // the waker is stored before checking the state of the future, so that it is woken if the
+        // future completes in between
+        *self.waker.lock().unwrap() = Some(waker.clone());
+        let future = self.future.bind(py);
+        if !future
+            .call_method0(intern!(py, "done"))?
+            .extract::<bool>()?
+        {
+            return Ok(None);
+        }
+        future
+            .call_method0(intern!(py, "result"))
+            .map(|result| Some(result.unbind()))
+    }
+}
+
+impl Future for PyFuture {
+    type Output = PyResult<PyObject>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Python::with_gil(|py| match self.poll_result(py, cx.waker()) {
+            Ok(Some(result)) => Poll::Ready(Ok(result)),
+            Ok(None) => Poll::Pending,
+            Err(err) => Poll::Ready(Err(err)),
+        })
+    }
+}
+
+impl Drop for PyFuture {
+    fn drop(&mut self) {
+        Python::with_gil(|py| {
+            let future = self.future.bind(py);
+            let cancel = || -> PyResult<()> {
+                if future
+                    .call_method0(intern!(py, "done"))?
+                    .extract::<bool>()?
+                {
+                    return Ok(());
+                }
+                match &self.event_loop {
+                    // `asyncio.Future` is not thread-safe
+                    Some(event_loop) => event_loop
+                        .bind(py)
+                        .call_method1(
+                            intern!(py, "call_soon_threadsafe"),
+                            (future.getattr(intern!(py, "cancel"))?,),
+                        )
+                        .map(drop),
+                    None => future.call_method0(intern!(py, "cancel")).map(drop),
+                }
+            };
+            // the event loop may already be closed, in which case the future will never complete
+            let _ = cancel();
+        })
+    }
+}
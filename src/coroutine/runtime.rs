@@ -0,0 +1,152 @@
+use crate::coroutine::future::asyncio;
+use crate::exceptions::PyRuntimeError;
+use crate::panic::{PanicException, PanicPolicy};
+use crate::sync::InterpreterOnceCell;
+use crate::types::any::PyAnyMethods;
+use crate::types::PyCFunction;
+use crate::{
+    intern, wrap_pyfunction, Bound, IntoPyObject, IntoPyObjectExt, Py, PyAny, PyObject, PyResult,
+    Python,
+};
+use pyo3_macros::pyfunction;
+use std::any::Any;
+use std::future::Future;
+use std::panic;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+/// Executor running the Rust futures converted to Python awaitables by [`future_into_py`].
+///
+/// This is typically implemented by spawning the future on an async runtime such as Tokio:
+///
+/// ```rust,ignore
+/// struct TokioRuntime(tokio::runtime::Runtime);
+///
+/// impl pyo3::coroutine::Runtime for TokioRuntime {
+///     fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send + 'static>>) {
+///         self.0.spawn(future);
+///     }
+/// }
+/// ```
+pub trait Runtime: Send + Sync + 'static {
+    /// Spawns `future`, which must be polled to completion in the background.
+    fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send + 'static>>);
+}
+
+static RUNTIME: Mutex<Option<Arc<dyn Runtime>>> = Mutex::new(None);
+
+/// Sets the [`Runtime`] used by [`future_into_py`], replacing the previous one.
+pub fn set_runtime(runtime: impl Runtime) {
+    *RUNTIME.lock().unwrap() = Some(Arc::new(runtime));
+}
+
+/// Spawns `future` on the [`Runtime`] and returns an `asyncio.Future` resolved with its output.
+///
+/// Unlike `async fn` wrapped by `#[pyfunction]`, which are polled by the event loop with the GIL
+/// held, `future` runs on the runtime threads and only holds the GIL when it acquires it.
+/// It can await Python awaitables with [`PyFuture::with_event_loop`](super::PyFuture::with_event_loop).
+///
+/// The `asyncio.Future` belongs to the event loop running in the current thread. Cancelling it does
+/// not stop `future`, whose output is then discarded.
+///
+/// `future` does not belong to a module, so the `asyncio.Future` is resolved with a
+/// [`PanicException`] if it panics, whatever the panic policy of the calling module.
+///
+/// Fails with `RuntimeError` if there is no running event loop, or if no runtime has been set
+/// with [`set_runtime`].
+pub fn future_into_py<'py, F, T>(py: Python<'py>, future: F) -> PyResult<Bound<'py, PyAny>>
+where
+    F: Future<Output = PyResult<T>> + Send + 'static,
+    T: for<'a> IntoPyObject<'a> + Send + 'static,
+{
+    let runtime = RUNTIME.lock().unwrap().clone().ok_or_else(|| {
+        PyRuntimeError::new_err("no runtime set, see `pyo3::coroutine::set_runtime`")
+    })?;
+    let event_loop = asyncio(py)?.call_method0(intern!(py, "get_running_loop"))?;
+    let py_future = event_loop.call_method0(intern!(py, "create_future"))?;
+
+    let event_loop_ref = event_loop.unbind();
+    let py_future_ref = py_future.clone().unbind();
+    runtime.spawn(Box::pin(async move {
+        let output = CatchUnwind(Box::pin(future)).await;
+        Python::with_gil(|py| {
+            let (result, is_error) = match output
+                .unwrap_or_else(|payload| {
+                    Err(PanicException::from_panic_payload(
+                        py,
+                        payload,
+                        PanicPolicy::Raise,
+                    ))
+                })
+                .and_then(|value| value.into_py_any(py))
+            {
+                Ok(value) => (value, false),
+                Err(err) => (err.into_value(py).into_any(), true),
+            };
+            if let Err(err) = resolve(py, &event_loop_ref, &py_future_ref, result, is_error) {
+                // the event loop is closed, or the callback could not be scheduled
+                err.write_unraisable(py, Some(py_future_ref.bind(py)));
+            }
+        })
+    }));
+    Ok(py_future)
+}
+
+/// Schedules `set_future_result` in the event loop thread, as `asyncio.Future` is not thread-safe.
+fn resolve(
+    py: Python<'_>,
+    event_loop: &PyObject,
+    future: &PyObject,
+    result: PyObject,
+    is_error: bool,
+) -> PyResult<()> {
+    static SET_FUTURE_RESULT: InterpreterOnceCell<Py<PyCFunction>> = InterpreterOnceCell::new();
+    let set_future_result = SET_FUTURE_RESULT.get_or_try_init(py, || {
+        wrap_pyfunction!(set_future_result, py).map(Bound::unbind)
+    })?;
+    event_loop.call_method1(
+        py,
+        intern!(py, "call_soon_threadsafe"),
+        (set_future_result, future, result, is_error),
+    )?;
+    Ok(())
+}
+
+/// Sets the result or the exception of `future`, unless it has been cancelled.
+#[pyfunction(crate = "crate")]
+fn set_future_result(future: &Bound<'_, PyAny>, result: PyObject, is_error: bool) -> PyResult<()> {
+    let py = future.py();
+    if future
+        .call_method0(intern!(py, "done"))?
+        .extract::<bool>()?
+    {
+        return Ok(());
+    }
+    let method = if is_error {
+        intern!(py, "set_exception")
+    } else {
+        intern!(py, "set_result")
+    };
+    future.call_method1(method, (result,))?;
+    Ok(())
+}
+
+/// Future catching the panics of the wrapped future, as they would otherwise unwind into the
+/// runtime.
+// Because `futures::FutureExt::catch_unwind` is not available without the `futures` crate
+pub(super) struct CatchUnwind<F>(pub(super) Pin<Box<F>>);
+
+impl<F: Future> Future for CatchUnwind<F> {
+    type Output = Result<F::Output, Box<dyn Any + Send + 'static>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // polling is UnwindSafe because the future is not polled again after a panic
+        let future = &mut self.0;
+        match panic::catch_unwind(panic::AssertUnwindSafe(|| future.as_mut().poll(cx))) {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(payload) => Poll::Ready(Err(payload)),
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use crate::coroutine::{CancelHandle, Coroutine};
+use crate::exceptions::{PyRuntimeError, PyStopAsyncIteration};
+use crate::panic::PanicPolicy;
+use crate::{intern, IntoPyObject, IntoPyObjectExt, Py, PyErr, PyObject, PyResult, Python};
+use futures_core::Stream;
+use pyo3_macros::{pyclass, pymethods};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll};
+
+type BoxedStream = Pin<Box<dyn Stream<Item = PyResult<PyObject>> + Send>>;
+
+/// Python asynchronous iterator wrapping a Rust [`Stream`].
+///
+/// Each `__anext__` call returns a [`Coroutine`] resolved with the next item of the stream, and
+/// `StopAsyncIteration` is raised when the stream ends. Like asynchronous generators, the iterator
+/// is closed when a `__anext__` call is cancelled, or when `aclose` is called: the stream is then
+/// dropped and the following `__anext__` calls raise `StopAsyncIteration`. Awaiting a `__anext__`
+/// call while another one is pending raises `RuntimeError`, as for asynchronous generators.
+///
+/// A panic of the stream raises [`PanicException`](crate::panic::PanicException), whatever the
+/// panic policy of the module returning the iterator.
+///
+/// # Examples
+///
+/// ```rust
+/// # #![allow(dead_code)]
+/// use futures::stream;
+/// use pyo3::coroutine::AsyncIterator;
+/// use pyo3::prelude::*;
+///
+/// #[pyfunction]
+/// fn count(n: u32) -> AsyncIterator {
+///     AsyncIterator::new(stream::iter((0..n).map(Ok::<_, PyErr>)))
+/// }
+/// ```
+#[pyclass(crate = "crate")]
+pub struct AsyncIterator {
+    stream: Arc<Mutex<Option<BoxedStream>>>,
+    /// Whether a `__anext__` call is pending.
+    running: Arc<AtomicBool>,
+}
+
+impl AsyncIterator {
+    /// Wraps `stream` into a Python asynchronous iterator.
+    pub fn new<S, T, E>(stream: S) -> Self
+    where
+        S: Stream<Item = Result<T, E>> + Send + 'static,
+        T: for<'py> IntoPyObject<'py>,
+        E: Into<PyErr>,
+    {
+        Self {
+            stream: Arc::new(Mutex::new(Some(Box::pin(IntoPyStream(Box::pin(stream)))))),
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+#[pymethods(crate = "crate")]
+impl AsyncIterator {
+    fn __aiter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __anext__(&self, py: Python<'_>) -> Coroutine {
+        let cancel = CancelHandle::new();
+        let throw_callback = cancel.throw_callback();
+        let next = Next {
+            stream: self.stream.clone(),
+            cancel,
+            running: self.running.clone(),
+            started: false,
+        };
+        Coroutine::new(
+            Some(intern!(py, "__anext__").clone()),
+            Some("AsyncIterator"),
+            Some(throw_callback),
+            PanicPolicy::Raise,
+            next,
+        )
+    }
+
+    fn aclose(&self, py: Python<'_>) -> Coroutine {
+        drop(lock(&self.stream).take());
+        Coroutine::new(
+            Some(intern!(py, "aclose").clone()),
+            Some("AsyncIterator"),
+            None,
+            PanicPolicy::Raise,
+            async { Ok::<_, PyErr>(()) },
+        )
+    }
+}
+
+/// Locks the stream, which is dropped if it panicked while it was polled.
+fn lock(stream: &Mutex<Option<BoxedStream>>) -> MutexGuard<'_, Option<BoxedStream>> {
+    stream.lock().unwrap_or_else(|poisoned| {
+        let mut stream = poisoned.into_inner();
+        *stream = None;
+        stream
+    })
+}
+
+/// Future of `__anext__`.
+struct Next {
+    stream: Arc<Mutex<Option<BoxedStream>>>,
+    cancel: CancelHandle,
+    running: Arc<AtomicBool>,
+    /// Whether this call is the pending one, set when it is first polled.
+    started: bool,
+}
+
+impl Drop for Next {
+    fn drop(&mut self) {
+        if self.started {
+            self.running.store(false, Ordering::Release);
+        }
+    }
+}
+
+impl Future for Next {
+    type Output = PyResult<PyObject>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if !self.started {
+            // the stream keeps the waker of the last poll only, so concurrent calls would hang
+            if self.running.swap(true, Ordering::AcqRel) {
+                return Poll::Ready(Err(PyRuntimeError::new_err(
+                    "anext(): asynchronous generator is already running",
+                )));
+            }
+            self.started = true;
+        }
+        if let Poll::Ready(exc) = self.cancel.poll_cancelled(cx) {
+            drop(lock(&self.stream).take());
+            return Poll::Ready(Err(Python::with_gil(|py| {
+                PyErr::from_value(exc.into_bound(py))
+            })));
+        }
+        let mut stream = lock(&self.stream);
+        let next = match stream.as_mut() {
+            Some(stream) => stream.as_mut().poll_next(cx),
+            None => Poll::Ready(None),
+        };
+        match next {
+            Poll::Ready(Some(item)) => Poll::Ready(item),
+            Poll::Ready(None) => {
+                *stream = None;
+                Poll::Ready(Err(PyStopAsyncIteration::new_err(())))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Stream converting the items of the wrapped stream to Python objects.
+struct IntoPyStream<S>(Pin<Box<S>>);
+
+impl<S, T, E> Stream for IntoPyStream<S>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: for<'py> IntoPyObject<'py>,
+    E: Into<PyErr>,
+{
+    type Item = PyResult<PyObject>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.as_mut().poll_next(cx).map(|item| {
+            item.map(|result| Python::with_gil(|py| result.map_err(Into::into)?.into_py_any(py)))
+        })
+    }
+}
@@ -0,0 +1,163 @@
+use crate::coroutine::waker::{AsyncLibrary, CoroutineWaker};
+use crate::exceptions::{PyRuntimeError, PyStopIteration};
+use crate::sync::InterpreterOnceCell;
+use crate::types::any::PyAnyMethods;
+use crate::types::{PyCFunction, PyModule};
+use crate::{intern, Bound, Py, PyAny, PyObject, PyResult, Python};
+use std::sync::{Arc, Mutex};
+
+/// Returns the `trio.lowlevel` module of the current interpreter.
+fn trio_lowlevel(py: Python<'_>) -> PyResult<&Bound<'_, PyModule>> {
+    static LOWLEVEL: InterpreterOnceCell<Py<PyModule>> = InterpreterOnceCell::new();
+    LOWLEVEL
+        .get_or_try_init(py, || Ok(py.import("trio.lowlevel")?.unbind()))
+        .map(|lowlevel| lowlevel.bind(py))
+}
+
+/// The Trio library, detected when the coroutine runs in a Trio task.
+#[derive(Debug, Default)]
+pub struct Trio;
+
+impl AsyncLibrary for Trio {
+    /// Trio is only looked up if it has been imported.
+    fn is_running(&self, py: Python<'_>) -> bool {
+        let imported = py.import("sys").and_then(|sys| {
+            sys.getattr(intern!(py, "modules"))?
+                .contains(intern!(py, "trio"))
+        });
+        imported.unwrap_or(false)
+            && trio_lowlevel(py)
+                .and_then(|lowlevel| lowlevel.call_method0(intern!(py, "current_task")))
+                .is_ok()
+    }
+
+    fn waker(&self, _py: Python<'_>) -> PyResult<Box<dyn CoroutineWaker>> {
+        Ok(Box::new(TrioWaker::new()))
+    }
+}
+
+#[derive(Default)]
+enum State {
+    /// The Rust future is being polled; `true` if it has been woken.
+    Polling(bool),
+    /// The Trio task is blocked in `wait_task_rescheduled`.
+    Suspended { token: PyObject, task: PyObject },
+    /// The Trio task has been rescheduled, or its wait has been aborted.
+    #[default]
+    Rescheduled,
+}
+
+/// Waker suspending a Trio task with `trio.lowlevel.wait_task_rescheduled`.
+///
+/// The coroutine awaits `wait_task_rescheduled` by delegation, as `await` does: the awaitable is
+/// started with `send(None)`, its first yielded value is yielded to Trio, and the value Trio sends
+/// to the coroutine when rescheduling the task is forwarded to it, which returns or raises the
+/// exception of a cancelled task.
+///
+/// Trio requires exactly one `reschedule` call per suspension, so the state is tracked to
+/// reschedule the task on the first wake only, and not at all if the wait is aborted because
+/// the task is cancelled. The task is always rescheduled with `TrioToken.run_sync_soon`, which
+/// can be called from any thread and runs after the task is suspended.
+pub struct TrioWaker {
+    state: Arc<Mutex<State>>,
+    /// The `wait_task_rescheduled` awaitable the coroutine is suspended in.
+    wait: Mutex<Option<PyObject>>,
+}
+
+impl TrioWaker {
+    /// Creates a waker for a coroutine run by the current Trio task.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State::Polling(false))),
+            wait: Mutex::new(None),
+        }
+    }
+}
+
+impl Default for TrioWaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CoroutineWaker for TrioWaker {
+    fn reset(&mut self) {
+        *self.state.lock().unwrap() = State::Polling(false);
+        *self.wait.get_mut().unwrap() = None;
+    }
+
+    fn suspend(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let lowlevel = trio_lowlevel(py)?;
+        let token = lowlevel.call_method0(intern!(py, "current_trio_token"))?;
+        let task = lowlevel.call_method0(intern!(py, "current_task"))?;
+
+        let state = self.state.clone();
+        let abort = PyCFunction::new_closure(py, None, None, move |args, _| {
+            let py = args.py();
+            let abort = trio_lowlevel(py)?.getattr(intern!(py, "Abort"))?;
+            let mut state = state.lock().unwrap();
+            // if the task has already been rescheduled, the cancellation is delivered later
+            let result = match *state {
+                State::Suspended { .. } => abort.getattr(intern!(py, "SUCCEEDED")),
+                _ => abort.getattr(intern!(py, "FAILED")),
+            };
+            *state = State::Rescheduled;
+            result.map(Bound::unbind)
+        })?;
+        let wait = lowlevel.call_method1(intern!(py, "wait_task_rescheduled"), (abort,))?;
+        let yielded = wait.call_method1(intern!(py, "send"), (py.None(),))?;
+        *self.wait.lock().unwrap() = Some(wait.unbind());
+
+        let mut state = self.state.lock().unwrap();
+        if let State::Polling(true) = *state {
+            *state = State::Rescheduled;
+            drop(state);
+            reschedule(&token, &task)?;
+        } else {
+            *state = State::Suspended {
+                token: token.unbind(),
+                task: task.unbind(),
+            };
+        }
+        Ok(yielded.unbind())
+    }
+
+    fn resume(&self, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        let py = value.py();
+        // the coroutine is started by sending `None`, without any pending wait
+        let wait = match self.wait.lock().unwrap().take() {
+            Some(wait) => wait,
+            None => return Ok(()),
+        };
+        match wait.call_method1(py, intern!(py, "send"), (value,)) {
+            Err(err) if err.is_instance_of::<PyStopIteration>(py) => Ok(()),
+            Err(err) => Err(err),
+            Ok(_) => Err(PyRuntimeError::new_err(
+                "`trio.lowlevel.wait_task_rescheduled` yielded more than once",
+            )),
+        }
+    }
+
+    fn wake(&self) {
+        let mut state = self.state.lock().unwrap();
+        match std::mem::take(&mut *state) {
+            State::Polling(_) => *state = State::Polling(true),
+            State::Suspended { token, task } => {
+                drop(state);
+                Python::with_gil(|py| {
+                    reschedule(token.bind(py), task.bind(py))
+                        .expect("unexpected error in coroutine waker")
+                });
+            }
+            State::Rescheduled => {}
+        }
+    }
+}
+
+/// Reschedules `task` from the Trio event loop, as soon as possible.
+fn reschedule(token: &Bound<'_, PyAny>, task: &Bound<'_, PyAny>) -> PyResult<()> {
+    let py = token.py();
+    let reschedule = trio_lowlevel(py)?.getattr(intern!(py, "reschedule"))?;
+    token.call_method1(intern!(py, "run_sync_soon"), (reschedule, task))?;
+    Ok(())
+}
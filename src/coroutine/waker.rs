@@ -1,46 +1,199 @@
-use crate::sync::GILOnceCell;
+use crate::coroutine::trio::Trio;
+use crate::sync::{GILOnceCell, InterpreterOnceCell};
 use crate::types::any::PyAnyMethods;
-use crate::types::PyCFunction;
+use crate::types::{PyCFunction, PyIterator};
 use crate::{intern, wrap_pyfunction, Bound, Py, PyAny, PyObject, PyResult, Python};
 use pyo3_macros::pyfunction;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::task::Wake;
 
-/// Lazy `asyncio.Future` wrapper, implementing [`Wake`] by calling `Future.set_result`.
+/// Python async library able to run a [`Coroutine`](super::Coroutine).
 ///
-/// asyncio future is let uninitialized until [`initialize_future`][1] is called.
+/// The async library of a coroutine is detected when the coroutine is first awaited: the
+/// libraries registered with [`register_async_library`] are tried first, from the last
+/// registered one, then [`Trio`], and [`Asyncio`] is used otherwise.
+///
+/// # Examples
+///
+/// An async library running coroutines in asyncio event loops of its own, but
+/// needing to be notified when they are woken, could be integrated like this:
+///
+/// ```rust
+/// use pyo3::coroutine::{register_async_library, AsyncLibrary, AsyncioWaker, CoroutineWaker};
+/// use pyo3::prelude::*;
+///
+/// struct MyLibrary;
+///
+/// impl AsyncLibrary for MyLibrary {
+///     fn is_running(&self, py: Python<'_>) -> bool {
+///         py.import("sys")
+///             .and_then(|sys| sys.getattr("modules")?.contains("my_library"))
+///             .unwrap_or(false)
+///     }
+///
+///     fn waker(&self, _py: Python<'_>) -> PyResult<Box<dyn CoroutineWaker>> {
+///         Ok(Box::new(MyWaker(AsyncioWaker::new())))
+///     }
+/// }
+///
+/// struct MyWaker(AsyncioWaker);
+///
+/// impl CoroutineWaker for MyWaker {
+///     fn reset(&mut self) {
+///         self.0.reset()
+///     }
+///
+///     fn suspend(&self, py: Python<'_>) -> PyResult<PyObject> {
+///         self.0.suspend(py)
+///     }
+///
+///     fn wake(&self) {
+///         println!("coroutine woken");
+///         self.0.wake()
+///     }
+/// }
+///
+/// register_async_library(MyLibrary);
+/// ```
+pub trait AsyncLibrary: Send + Sync + 'static {
+    /// Returns whether the current task is run by this library.
+    fn is_running(&self, py: Python<'_>) -> bool;
+
+    /// Creates the waker of a coroutine run by this library.
+    fn waker(&self, py: Python<'_>) -> PyResult<Box<dyn CoroutineWaker>>;
+}
+
+/// Waker of a [`Coroutine`](super::Coroutine), which suspends the coroutine in the event loop of
+/// its async library until the Rust future it wraps is woken.
+///
+/// When the Rust future returns `Poll::Pending`, the coroutine yields the object returned by
+/// [`suspend`](CoroutineWaker::suspend) to the event loop, which must resume the coroutine, by
+/// calling its `send` method, once [`wake`](CoroutineWaker::wake) has been called.
+pub trait CoroutineWaker: Send + Sync + 'static {
+    /// Resets the waker before the Rust future is polled again.
+    ///
+    /// This is only called when the waker is not referenced by the Rust future anymore, so that
+    /// it can be reused; a new waker is created otherwise.
+    fn reset(&mut self);
+
+    /// Returns the object to yield to the event loop after the Rust future has returned
+    /// `Poll::Pending`, so that the coroutine is resumed when the waker is woken.
+    ///
+    /// The waker may have been woken while the future was polled, in which case the coroutine
+    /// should be resumed as soon as possible.
+    fn suspend(&self, py: Python<'_>) -> PyResult<PyObject>;
+
+    /// Handles the value sent to the coroutine when it is resumed, failing with the error to
+    /// throw into the coroutine, if any.
+    ///
+    /// The coroutine is started by sending `None`. The default implementation ignores the value.
+    fn resume(&self, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        let _ = value;
+        Ok(())
+    }
+
+    /// Wakes the coroutine. This may be called from any thread, with or without the GIL.
+    fn wake(&self);
+}
+
+/// The asyncio library, which is used when no other library is detected, including for
+/// anyio, which runs on top of asyncio or Trio.
+#[derive(Debug, Default)]
+pub struct Asyncio;
+
+impl AsyncLibrary for Asyncio {
+    fn is_running(&self, _py: Python<'_>) -> bool {
+        true
+    }
+
+    fn waker(&self, _py: Python<'_>) -> PyResult<Box<dyn CoroutineWaker>> {
+        Ok(Box::new(AsyncioWaker::new()))
+    }
+}
+
+static ASYNC_LIBRARIES: Mutex<Vec<Arc<dyn AsyncLibrary>>> = Mutex::new(Vec::new());
+
+/// Registers an [`AsyncLibrary`], which is tried before the libraries registered previously and
+/// before the built-in ones.
+pub fn register_async_library(library: impl AsyncLibrary) {
+    ASYNC_LIBRARIES.lock().unwrap().push(Arc::new(library));
+}
+
+/// Detects the async library running the current task.
+pub(super) fn detect_async_library(py: Python<'_>) -> Arc<dyn AsyncLibrary> {
+    let registered = ASYNC_LIBRARIES.lock().unwrap().clone();
+    if let Some(library) = registered.into_iter().rev().find(|lib| lib.is_running(py)) {
+        return library;
+    }
+    if Trio.is_running(py) {
+        return Arc::new(Trio);
+    }
+    Arc::new(Asyncio)
+}
+
+/// Adapter of a [`CoroutineWaker`] to [`std::task::Waker`].
+pub(super) struct WakerAdapter(pub(super) Box<dyn CoroutineWaker>);
+
+impl Wake for WakerAdapter {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.wake()
+    }
+}
+
+/// Lazy `asyncio.Future` wrapper, woken by calling `Future.set_result`.
+///
+/// asyncio future is let uninitialized until [`suspend`][1] is called.
 /// If [`wake`][2] is called before future initialization (during Rust future polling),
-/// [`initialize_future`][1] will return `None` (it is roughly equivalent to `asyncio.sleep(0)`)
+/// [`suspend`][1] will yield `None` (it is roughly equivalent to `asyncio.sleep(0)`)
 ///
-/// [1]: AsyncioWaker::initialize_future
-/// [2]: AsyncioWaker::wake
+/// [1]: CoroutineWaker::suspend
+/// [2]: CoroutineWaker::wake
 pub struct AsyncioWaker(GILOnceCell<Option<LoopAndFuture>>);
 
 impl AsyncioWaker {
-    pub(super) fn new() -> Self {
+    /// Creates a waker for a coroutine run by the running asyncio event loop.
+    pub fn new() -> Self {
         Self(GILOnceCell::new())
     }
 
-    pub(super) fn reset(&mut self) {
-        self.0.take();
-    }
-
-    pub(super) fn initialize_future<'py>(
-        &self,
-        py: Python<'py>,
-    ) -> PyResult<Option<&Bound<'py, PyAny>>> {
+    fn initialize_future<'py>(&self, py: Python<'py>) -> PyResult<Option<&Bound<'py, PyAny>>> {
         let init = || LoopAndFuture::new(py).map(Some);
         let loop_and_future = self.0.get_or_try_init(py, init)?.as_ref();
         Ok(loop_and_future.map(|LoopAndFuture { future, .. }| future.bind(py)))
     }
 }
 
-impl Wake for AsyncioWaker {
-    fn wake(self: Arc<Self>) {
-        self.wake_by_ref()
+impl Default for AsyncioWaker {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    fn wake_by_ref(self: &Arc<Self>) {
+impl CoroutineWaker for AsyncioWaker {
+    fn reset(&mut self) {
+        self.0.take();
+    }
+
+    fn suspend(&self, py: Python<'_>) -> PyResult<PyObject> {
+        if let Some(future) = self.initialize_future(py)? {
+            // `asyncio.Future` must be awaited; fortunately, it implements `__iter__ = __await__`
+            // and will yield itself if its result has not been set in polling above
+            if let Some(future) = PyIterator::from_object(future).unwrap().next() {
+                // future has not been leaked into Python for now, and Rust code can only call
+                // `set_result(None)` in `Wake` implementation, so it's safe to unwrap
+                return Ok(future.unwrap().into());
+            }
+        }
+        // if waker has been waken during future polling, this is roughly equivalent to
+        // `await asyncio.sleep(0)`, so just yield `None`.
+        Ok(py.None())
+    }
+
+    fn wake(&self) {
         Python::with_gil(|gil| {
             if let Some(loop_and_future) = self.0.get_or_init(gil, || None) {
                 loop_and_future
@@ -58,7 +211,7 @@ struct LoopAndFuture {
 
 impl LoopAndFuture {
     fn new(py: Python<'_>) -> PyResult<Self> {
-        static GET_RUNNING_LOOP: GILOnceCell<PyObject> = GILOnceCell::new();
+        static GET_RUNNING_LOOP: InterpreterOnceCell<PyObject> = InterpreterOnceCell::new();
         let import = || -> PyResult<_> {
             let module = py.import("asyncio")?;
             Ok(module.getattr("get_running_loop")?.into())
@@ -69,7 +222,7 @@ impl LoopAndFuture {
     }
 
     fn set_result(&self, py: Python<'_>) -> PyResult<()> {
-        static RELEASE_WAITER: GILOnceCell<Py<PyCFunction>> = GILOnceCell::new();
+        static RELEASE_WAITER: InterpreterOnceCell<Py<PyCFunction>> = InterpreterOnceCell::new();
         let release_waiter = RELEASE_WAITER.get_or_try_init(py, || {
             wrap_pyfunction!(release_waiter, py).map(Bound::unbind)
         })?;
@@ -3,6 +3,9 @@ use crate::panic::PanicException;
 use crate::type_object::PyTypeInfo;
 use crate::types::any::PyAnyMethods;
 use crate::types::{string::PyStringMethods, typeobject::PyTypeMethods, PyTraceback, PyType};
+#[cfg(not(Py_3_11))]
+use crate::types::{PyList, PyListMethods};
+use crate::types::{PyTuple, PyTupleMethods};
 use crate::{
     exceptions::{self, PyBaseException},
     ffi,
@@ -492,12 +495,17 @@ impl PyErr {
                     .map_or(std::ptr::null_mut(), |traceback| traceback.as_ptr()),
             )
         }
+
+        #[cfg(not(Py_3_11))]
+        self.display_notes(py);
     }
 
     /// Calls `sys.excepthook` and then prints a standard traceback to `sys.stderr`.
     pub fn print(&self, py: Python<'_>) {
         self.clone_ref(py).restore(py);
         unsafe { ffi::PyErr_PrintEx(0) }
+        #[cfg(not(Py_3_11))]
+        self.display_notes(py);
     }
 
     /// Calls `sys.excepthook` and then prints a standard traceback to `sys.stderr`.
@@ -506,6 +514,33 @@ impl PyErr {
     pub fn print_and_set_sys_last_vars(&self, py: Python<'_>) {
         self.clone_ref(py).restore(py);
         unsafe { ffi::PyErr_PrintEx(1) }
+        #[cfg(not(Py_3_11))]
+        self.display_notes(py);
+    }
+
+    /// Writes the notes of the exception to `sys.stderr`, as Python 3.11 and up display them
+    /// after the traceback.
+    #[cfg(not(Py_3_11))]
+    fn display_notes(&self, py: Python<'_>) {
+        // abi3 builds may run on a version which displays the notes itself
+        if py.version_info() >= (3, 11) {
+            return;
+        }
+        let notes = match self.notes(py) {
+            Ok(notes) => notes,
+            Err(_) => return,
+        };
+        if notes.is_empty() {
+            return;
+        }
+        if let Ok(stderr) = py
+            .import("sys")
+            .and_then(|sys| sys.getattr(crate::intern!(py, "stderr")))
+        {
+            for note in notes {
+                let _ = stderr.call_method1(crate::intern!(py, "write"), (note + "\n",));
+            }
+        }
     }
 
     /// Returns true if the current exception matches the exception in `exc`.
@@ -753,6 +788,246 @@ impl PyErr {
         }
     }
 
+    /// Adds a note to the exception, which is displayed after the exception message in tracebacks.
+    ///
+    /// On Python 3.11 and up this calls `BaseException.add_note`. On older versions the note is
+    /// appended to the `__notes__` attribute of the exception in the same way. Their tracebacks
+    /// do not display notes, so [`PyErr::display`] and [`PyErr::print`] write them after the
+    /// traceback.
+    ///
+    /// See also [`PyResultContext`] to add notes to errors as they are propagated.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pyo3::prelude::*;
+    /// use pyo3::exceptions::PyValueError;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let err = PyValueError::new_err("invalid port");
+    ///     err.add_note(py, "while reading the configuration").unwrap();
+    ///     assert_eq!(err.notes(py).unwrap(), ["while reading the configuration"]);
+    /// });
+    /// ```
+    pub fn add_note(&self, py: Python<'_>, note: &str) -> PyResult<()> {
+        let value = self.value(py);
+        #[cfg(Py_3_11)]
+        {
+            value
+                .call_method1(crate::intern!(py, "add_note"), (note,))
+                .map(drop)
+        }
+        #[cfg(not(Py_3_11))]
+        {
+            let name = crate::intern!(py, "__notes__");
+            let notes = match value.getattr(name) {
+                Ok(notes) => notes,
+                Err(err) if err.is_instance_of::<exceptions::PyAttributeError>(py) => {
+                    let notes = PyList::empty(py);
+                    value.setattr(name, &notes)?;
+                    notes.into_any()
+                }
+                Err(err) => return Err(err),
+            };
+            notes
+                .downcast_into::<PyList>()
+                .map_err(|_| {
+                    exceptions::PyTypeError::new_err("cannot add note: __notes__ is not a list")
+                })?
+                .append(note)
+        }
+    }
+
+    /// Returns the notes added to the exception, in the order they were added.
+    ///
+    /// On Python versions before 3.11, the notes are also part of the message of the exception,
+    /// see [`PyErr::add_note`].
+    pub fn notes(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+        match self.value(py).getattr(crate::intern!(py, "__notes__")) {
+            Ok(notes) => notes.extract(),
+            Err(err) if err.is_instance_of::<exceptions::PyAttributeError>(py) => Ok(Vec::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Adds a frame for a Rust function to the traceback of the exception, so that it is shown
+    /// by Python tracebacks like the frame of a Python function.
+    ///
+    /// `file` and `line` are usually given by [`file!`] and [`line!`]. If `file` is a path
+    /// relative to the working directory, the `traceback` module also displays the source line.
+    ///
+    /// This is used by functions marked with `#[pyo3(traceback)]`, whose frame is given the line
+    /// where the function is defined: the frame marks the entry point of the function, not the
+    /// place where the error was raised. On the limited API, PyPy and GraalPy, the error is
+    /// returned unchanged.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #![allow(dead_code)]
+    /// use pyo3::prelude::*;
+    ///
+    /// fn parse_config(py: Python<'_>, config: &Bound<'_, PyAny>) -> PyResult<u16> {
+    ///     config
+    ///         .get_item("port")?
+    ///         .extract()
+    ///         .map_err(|err: PyErr| err.with_rust_frame(py, "parse_config", file!(), line!()))
+    /// }
+    /// ```
+    pub fn with_rust_frame(self, py: Python<'_>, function: &str, file: &str, line: u32) -> PyErr {
+        #[cfg(not(any(Py_LIMITED_API, PyPy, GraalPy)))]
+        {
+            use crate::ffi_ptr_ext::FfiPtrExt;
+
+            let (function, file) = match (CString::new(function), CString::new(file)) {
+                (Ok(function), Ok(file)) => (function, file),
+                _ => return self,
+            };
+            let line = std::os::raw::c_int::try_from(line).unwrap_or(std::os::raw::c_int::MAX);
+            let frame = unsafe {
+                ffi::PyCode_NewEmpty(file.as_ptr(), function.as_ptr(), line)
+                    .cast::<ffi::PyObject>()
+                    .assume_owned_or_err(py)
+                    .and_then(|code| {
+                        let globals = crate::types::PyDict::new(py);
+                        ffi::PyFrame_New(
+                            ffi::PyThreadState_Get(),
+                            code.as_ptr().cast(),
+                            globals.as_ptr(),
+                            std::ptr::null_mut(),
+                        )
+                        .cast::<ffi::PyObject>()
+                        .assume_owned_or_err(py)
+                    })
+            };
+            let frame = match frame {
+                Ok(frame) => frame,
+                Err(_) => return self,
+            };
+            // `PyTraceBack_Here` adds the frame to the traceback of the raised exception. The
+            // exception is taken back directly, as `PyErr::take` would resume a panic.
+            self.restore(py);
+            unsafe { ffi::PyTraceBack_Here(frame.as_ptr().cast()) };
+            match PyErrStateNormalized::take(py) {
+                Some(state) => PyErr::from_state(PyErrState::normalized(state)),
+                None => {
+                    exceptions::PySystemError::new_err("exception missing after adding a frame")
+                }
+            }
+        }
+        #[cfg(any(Py_LIMITED_API, PyPy, GraalPy))]
+        {
+            let _ = (py, function, file, line);
+            self
+        }
+    }
+
+    /// Creates an exception group holding `errors`, like `BaseExceptionGroup(message, errors)` in
+    /// Python.
+    ///
+    /// When all of `errors` are instances of `Exception`, the group is an `ExceptionGroup`, which
+    /// can be handled by `except Exception`. The group is created lazily, so if `errors` is empty
+    /// a `ValueError` is raised instead when the group is first accessed.
+    ///
+    /// A `Vec<PyErr>` can also be converted into a group with a default message, which allows
+    /// functions returning `Result<T, Vec<PyErr>>` to report several errors at once.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pyo3::prelude::*;
+    /// use pyo3::exceptions::{PyKeyError, PyValueError};
+    ///
+    /// Python::with_gil(|py| {
+    ///     let err = PyErr::new_group(
+    ///         "invalid configuration",
+    ///         vec![
+    ///             PyValueError::new_err("negative timeout"),
+    ///             PyKeyError::new_err("host"),
+    ///         ],
+    ///     );
+    ///     assert!(err.is_group(py));
+    ///     assert_eq!(err.exceptions(py).unwrap().len(), 2);
+    ///
+    ///     let (value_errors, rest) = err.split::<PyValueError>(py).unwrap();
+    ///     assert_eq!(value_errors.unwrap().exceptions(py).unwrap().len(), 1);
+    ///     assert!(rest.unwrap().exceptions(py).unwrap()[0].is_instance_of::<PyKeyError>(py));
+    /// });
+    /// ```
+    #[cfg(Py_3_11)]
+    pub fn new_group(message: impl Into<String>, errors: Vec<PyErr>) -> PyErr {
+        let message = message.into();
+        PyErr::from_state(PyErrState::lazy(Box::new(move |py| {
+            let group = exceptions::PyBaseExceptionGroup::type_object(py)
+                .call1((message, errors))
+                .unwrap_or_else(|err| err.into_value(py).into_bound(py).into_any());
+            // The type is taken from the value, which is an `ExceptionGroup` when possible
+            PyErrStateLazyFnOutput {
+                ptype: group.get_type().into_any().unbind(),
+                pvalue: group.unbind(),
+            }
+        })))
+    }
+
+    /// Returns true if this error is an exception group.
+    #[cfg(Py_3_11)]
+    #[inline]
+    pub fn is_group(&self, py: Python<'_>) -> bool {
+        self.is_instance_of::<exceptions::PyBaseExceptionGroup>(py)
+    }
+
+    /// Returns the errors held by this exception group, or `None` if this is not a group.
+    ///
+    /// Nested groups are returned as they are, and can be inspected in turn.
+    #[cfg(Py_3_11)]
+    pub fn exceptions(&self, py: Python<'_>) -> Option<Vec<PyErr>> {
+        if !self.is_group(py) {
+            return None;
+        }
+        let exceptions = self
+            .value(py)
+            .getattr(crate::intern!(py, "exceptions"))
+            .ok()?
+            .downcast_into::<PyTuple>()
+            .ok()?;
+        Some(exceptions.iter().map(PyErr::from_value).collect())
+    }
+
+    /// Splits this exception group into a group of the errors which are instances of `T`, and a
+    /// group of the other errors, like `BaseExceptionGroup.split` in Python.
+    ///
+    /// Either side is `None` when it would be empty. Nested groups are split recursively, and the
+    /// resulting groups keep the message, traceback, cause, context and notes of the originals.
+    /// An error which is not a group is matched as a whole, as is done by `except*`.
+    #[cfg(Py_3_11)]
+    pub fn split<T>(&self, py: Python<'_>) -> PyResult<(Option<PyErr>, Option<PyErr>)>
+    where
+        T: PyTypeInfo,
+    {
+        if !self.is_group(py) {
+            let err = self.clone_ref(py);
+            return Ok(if self.is_instance_of::<T>(py) {
+                (Some(err), None)
+            } else {
+                (None, Some(err))
+            });
+        }
+        let (matching, rest) = self
+            .value(py)
+            .call_method1(crate::intern!(py, "split"), (T::type_object(py),))?
+            .extract::<(Option<Bound<'_, PyAny>>, Option<Bound<'_, PyAny>>)>()?;
+        Ok((matching.map(PyErr::from_value), rest.map(PyErr::from_value)))
+    }
+
+    /// Returns a group of the errors of this exception group which are instances of `T`, or
+    /// `None` if there are no such errors.
+    ///
+    /// See [`PyErr::split`] for details.
+    #[cfg(Py_3_11)]
+    pub fn subgroup<T>(&self, py: Python<'_>) -> PyResult<Option<PyErr>>
+    where
+        T: PyTypeInfo,
+    {
+        Ok(self.split::<T>(py)?.0)
+    }
+
     #[inline]
     fn from_state(state: PyErrState) -> PyErr {
         PyErr { state }
@@ -860,6 +1135,80 @@ impl PyErrArguments for PyDowncastErrorArguments {
     }
 }
 
+/// Adds notes to errors as they are propagated, in the spirit of `anyhow::Context`.
+///
+/// The notes are added with [`PyErr::add_note`], and are displayed after the exception message
+/// in tracebacks, so that they show the context of the Rust code the error went through. Adding
+/// a note is best-effort: if it fails, the original error is returned unchanged.
+///
+/// # Examples
+/// ```rust
+/// use pyo3::prelude::*;
+/// use pyo3::PyResultContext;
+///
+/// fn parse_port(value: &Bound<'_, PyAny>) -> PyResult<u16> {
+///     value.extract().py_context("while parsing the port")
+/// }
+///
+/// fn parse_address(value: &Bound<'_, PyAny>) -> PyResult<(String, u16)> {
+///     let host = value.get_item(0)?.extract()?;
+///     let port = parse_port(&value.get_item(1)?)
+///         .with_py_context(|| format!("while parsing the address of {}", host))?;
+///     Ok((host, port))
+/// }
+///
+/// Python::with_gil(|py| {
+///     let address = ("localhost", -1).into_pyobject(py).unwrap();
+///     let err = parse_address(&address).unwrap_err();
+///     assert_eq!(
+///         err.notes(py).unwrap(),
+///         ["while parsing the port", "while parsing the address of localhost"]
+///     );
+/// });
+/// ```
+pub trait PyResultContext<T> {
+    /// Adds `note` to the error, if any.
+    fn py_context<C>(self, note: C) -> PyResult<T>
+    where
+        C: std::fmt::Display;
+
+    /// Adds the note returned by `f` to the error, if any.
+    ///
+    /// `f` is only called when there is an error.
+    fn with_py_context<C, F>(self, f: F) -> PyResult<T>
+    where
+        C: std::fmt::Display,
+        F: FnOnce() -> C;
+}
+
+impl<T, E> PyResultContext<T> for Result<T, E>
+where
+    E: Into<PyErr>,
+{
+    #[inline]
+    fn py_context<C>(self, note: C) -> PyResult<T>
+    where
+        C: std::fmt::Display,
+    {
+        self.with_py_context(|| note)
+    }
+
+    fn with_py_context<C, F>(self, f: F) -> PyResult<T>
+    where
+        C: std::fmt::Display,
+        F: FnOnce() -> C,
+    {
+        self.map_err(|err| {
+            let err = err.into();
+            Python::with_gil(|py| {
+                // Failing to add the note must not hide the original error
+                let _ = err.add_note(py, &f().to_string());
+            });
+            err
+        })
+    }
+}
+
 /// Python exceptions that can be converted to [`PyErr`].
 ///
 /// This is used to implement [`From<Bound<'_, T>> for PyErr`].
@@ -878,6 +1227,16 @@ where
     }
 }
 
+/// Convert a list of errors to a Python exception group.
+///
+/// See [`PyErr::new_group`].
+#[cfg(Py_3_11)]
+impl std::convert::From<Vec<PyErr>> for PyErr {
+    fn from(errors: Vec<PyErr>) -> PyErr {
+        PyErr::new_group("multiple errors occurred", errors)
+    }
+}
+
 /// Convert `DowncastError` to Python `TypeError`.
 impl std::convert::From<DowncastError<'_, '_>> for PyErr {
     fn from(err: DowncastError<'_, '_>) -> PyErr {
@@ -1223,4 +1582,134 @@ mod tests {
             warnings.call_method0("resetwarnings").unwrap();
         });
     }
+
+    #[test]
+    #[cfg(Py_3_11)]
+    fn test_exception_group() {
+        use crate::types::PyAnyMethods;
+
+        Python::with_gil(|py| {
+            let err = PyErr::new_group(
+                "errors",
+                vec![
+                    PyValueError::new_err("a"),
+                    PyErr::new_group("nested", vec![PyTypeError::new_err("b")]),
+                    PyValueError::new_err("c"),
+                ],
+            );
+            assert!(err.is_group(py));
+            assert!(err.is_instance_of::<exceptions::PyException>(py));
+            assert_eq!(
+                err.value(py).getattr("message").unwrap().to_string(),
+                "errors"
+            );
+            let exceptions = err.exceptions(py).unwrap();
+            assert_eq!(exceptions.len(), 3);
+            assert!(exceptions[1].is_group(py));
+            assert!(!exceptions[0].is_group(py));
+            assert!(exceptions[0].exceptions(py).is_none());
+
+            let (matching, rest) = err.split::<PyValueError>(py).unwrap();
+            let matching = matching.unwrap().exceptions(py).unwrap();
+            assert_eq!(matching.len(), 2);
+            assert!(matching
+                .iter()
+                .all(|e| e.is_instance_of::<PyValueError>(py)));
+            let rest = rest.unwrap().exceptions(py).unwrap();
+            assert_eq!(rest.len(), 1);
+            assert_eq!(
+                rest[0].exceptions(py).unwrap()[0].to_string(),
+                "TypeError: b"
+            );
+
+            assert!(err
+                .subgroup::<exceptions::PyKeyError>(py)
+                .unwrap()
+                .is_none());
+            let (matching, rest) = err.split::<exceptions::PyException>(py).unwrap();
+            assert!(matching.unwrap().value(py).is(err.value(py)));
+            assert!(rest.is_none());
+
+            // errors which are not groups are matched as a whole
+            let err = PyValueError::new_err("a");
+            let (matching, rest) = err.split::<PyValueError>(py).unwrap();
+            assert!(matching.unwrap().value(py).is(err.value(py)));
+            assert!(rest.is_none());
+            assert!(err.subgroup::<PyTypeError>(py).unwrap().is_none());
+
+            // empty groups are not allowed
+            let err = PyErr::new_group("empty", Vec::new());
+            assert!(err.is_instance_of::<PyValueError>(py));
+        });
+    }
+
+    #[test]
+    fn test_notes() {
+        use super::PyResultContext;
+        use crate::types::PyAnyMethods;
+
+        Python::with_gil(|py| {
+            let err = PyValueError::new_err("error");
+            assert!(err.notes(py).unwrap().is_empty());
+            err.add_note(py, "first").unwrap();
+            err.add_note(py, "second").unwrap();
+            assert_eq!(err.notes(py).unwrap(), ["first", "second"]);
+            // the exception itself is unchanged
+            assert_eq!(err.value(py).to_string(), "error");
+            assert_eq!(
+                err.value(py)
+                    .getattr("args")
+                    .unwrap()
+                    .extract::<(String,)>()
+                    .unwrap(),
+                ("error".to_owned(),)
+            );
+
+            err.value(py).setattr("__notes__", "not a list").unwrap();
+            assert!(err
+                .add_note(py, "third")
+                .unwrap_err()
+                .is_instance_of::<PyTypeError>(py));
+
+            let result: Result<(), PyErr> = Err(PyValueError::new_err("error"));
+            let err = result
+                .py_context("inner")
+                .with_py_context(|| format!("outer {}", 1))
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert_eq!(err.notes(py).unwrap(), ["inner", "outer 1"]);
+
+            let result: Result<u8, _> = "-1".parse::<u8>();
+            let err = result.py_context("parsing").unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert_eq!(err.notes(py).unwrap(), ["parsing"]);
+
+            // the notes are displayed after the exception on all versions
+            let err = PyValueError::new_err("error");
+            err.add_note(py, "first").unwrap();
+            err.add_note(py, "second").unwrap();
+            let sys = py.import("sys").unwrap();
+            let stderr = sys.getattr("stderr").unwrap();
+            let output = py.import("io").unwrap().call_method0("StringIO").unwrap();
+            sys.setattr("stderr", &output).unwrap();
+            err.display(py);
+            sys.setattr("stderr", stderr).unwrap();
+            assert_eq!(
+                output
+                    .call_method0("getvalue")
+                    .unwrap()
+                    .extract::<String>()
+                    .unwrap(),
+                "ValueError: error\nfirst\nsecond\n"
+            );
+
+            let result: super::PyResult<i32> = Ok(1);
+            assert_eq!(
+                result
+                    .with_py_context(|| -> String { panic!("not called on success") })
+                    .unwrap(),
+                1
+            );
+        });
+    }
 }
@@ -252,9 +252,9 @@ macro_rules! create_exception_type_object {
 
         impl $name {
             fn type_object_raw(py: $crate::Python<'_>) -> *mut $crate::ffi::PyTypeObject {
-                use $crate::sync::GILOnceCell;
-                static TYPE_OBJECT: GILOnceCell<$crate::Py<$crate::types::PyType>> =
-                    GILOnceCell::new();
+                use $crate::sync::InterpreterOnceCell;
+                static TYPE_OBJECT: InterpreterOnceCell<$crate::Py<$crate::types::PyType>> =
+                    InterpreterOnceCell::new();
 
                 TYPE_OBJECT
                     .get_or_init(py, ||
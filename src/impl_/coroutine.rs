@@ -1,12 +1,18 @@
 use std::{
     future::Future,
+    mem::ManuallyDrop,
     ops::{Deref, DerefMut},
+    pin::Pin,
+    task::{Context, Poll},
+    thread::{self, ThreadId},
 };
 
 use crate::{
     coroutine::{cancel::ThrowCallback, Coroutine},
+    exceptions::PyRuntimeError,
     instance::Bound,
-    pycell::impl_::PyClassBorrowChecker,
+    panic::PanicPolicy,
+    pycell::impl_::{PyClassBorrowChecker, PyClassObjectLayout},
     pyclass::boolean_struct::False,
     types::{PyAnyMethods, PyString},
     IntoPyObject, Py, PyAny, PyClass, PyErr, PyResult, Python,
@@ -16,6 +22,7 @@ pub fn new_coroutine<'py, F, T, E>(
     name: &Bound<'py, PyString>,
     qualname_prefix: Option<&'static str>,
     throw_callback: Option<ThrowCallback>,
+    panic_policy: PanicPolicy,
     future: F,
 ) -> Coroutine
 where
@@ -23,11 +30,114 @@ where
     T: IntoPyObject<'py>,
     E: Into<PyErr>,
 {
-    Coroutine::new(Some(name.clone()), qualname_prefix, throw_callback, future)
+    Coroutine::new(
+        Some(name.clone()),
+        qualname_prefix,
+        throw_callback,
+        panic_policy,
+        future,
+    )
 }
 
+/// Like [`new_coroutine`], for the `!Send` futures of `#[pyo3(local)]` functions.
+///
+/// `borrow` releases the borrow of the receiver of a method if the future is leaked.
+pub fn new_local_coroutine<'py, F, T, E>(
+    name: &Bound<'py, PyString>,
+    qualname_prefix: Option<&'static str>,
+    throw_callback: Option<ThrowCallback>,
+    panic_policy: PanicPolicy,
+    borrow: Option<BorrowRelease>,
+    future: F,
+) -> Coroutine
+where
+    F: Future<Output = Result<T, E>> + 'static,
+    T: IntoPyObject<'py>,
+    E: Into<PyErr>,
+{
+    Coroutine::new(
+        Some(name.clone()),
+        qualname_prefix,
+        throw_callback,
+        panic_policy,
+        LocalFuture::new(future, borrow),
+    )
+}
+
+/// Future which can only be polled and dropped by the thread which created it.
+struct LocalFuture<F> {
+    future: ManuallyDrop<F>,
+    thread: ThreadId,
+    /// Borrow of the guard held by the future, released if the future is leaked.
+    borrow: Option<BorrowRelease>,
+}
+
+// SAFETY: the future is never polled nor dropped by another thread than the one which created it
+unsafe impl<F> Send for LocalFuture<F> {}
+
+impl<F> LocalFuture<F> {
+    fn new(future: F, borrow: Option<BorrowRelease>) -> Self {
+        Self {
+            future: ManuallyDrop::new(future),
+            thread: thread::current().id(),
+            borrow,
+        }
+    }
+}
+
+impl<F, T, E> Future for LocalFuture<F>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Into<PyErr>,
+{
+    type Output = PyResult<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if thread::current().id() != self.thread {
+            return Poll::Ready(Err(PyRuntimeError::new_err(
+                "local coroutine can only be awaited on the thread which created it",
+            )));
+        }
+        // SAFETY: the future is pinned with `self`, as it is never moved out of it
+        let future = unsafe { self.map_unchecked_mut(|this| &mut *this.future) };
+        future.poll(cx).map(|result| result.map_err(Into::into))
+    }
+}
+
+impl<F> Drop for LocalFuture<F> {
+    fn drop(&mut self) {
+        if thread::current().id() == self.thread {
+            // SAFETY: the future is not used after being dropped
+            unsafe { ManuallyDrop::drop(&mut self.future) };
+        } else {
+            // the future is leaked, as it cannot be dropped by this thread, but the instance it
+            // borrows must stay usable
+            Python::with_gil(|py| {
+                if let Some(borrow) = self.borrow.take() {
+                    (borrow.release)(borrow.obj.bind(py));
+                }
+                PyRuntimeError::new_err(
+                    "local coroutine is being dropped on another thread than the one which created it",
+                )
+                .write_unraisable(py, None)
+            });
+        }
+    }
+}
+
+/// Borrow of a [`RefGuard`] or a [`RefMutGuard`], released by a `LocalFuture` which is leaked
+/// with the guard because it is dropped on another thread.
+pub struct BorrowRelease {
+    obj: Py<PyAny>,
+    release: for<'py> fn(&Bound<'py, PyAny>),
+}
+
+/// Returns the pointer to the contents of `obj`, checking that an unsendable class is accessed
+/// by the thread which created it, as the future holding the guard may be polled on any thread.
 fn get_ptr<T: PyClass>(obj: &Py<T>) -> *mut T {
-    obj.get_class_object().get_ptr()
+    let class_object = obj.get_class_object();
+    class_object.ensure_threadsafe();
+    class_object.get_ptr()
 }
 
 pub struct RefGuard<T: PyClass>(Py<T>);
@@ -35,9 +145,23 @@ pub struct RefGuard<T: PyClass>(Py<T>);
 impl<T: PyClass> RefGuard<T> {
     pub fn new(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
         let bound = obj.downcast::<T>()?;
-        bound.get_class_object().borrow_checker().try_borrow()?;
+        let class_object = bound.get_class_object();
+        class_object.ensure_threadsafe();
+        class_object.borrow_checker().try_borrow()?;
         Ok(RefGuard(bound.clone().unbind()))
     }
+
+    /// Returns the release of the borrow of this guard, for a `LocalFuture` holding it.
+    pub fn borrow_release(&self, py: Python<'_>) -> BorrowRelease {
+        BorrowRelease {
+            obj: self.0.clone_ref(py).into_any(),
+            release: |obj| {
+                // SAFETY: `obj` is the instance of `T` borrowed by the guard
+                let obj = unsafe { obj.downcast_unchecked::<T>() };
+                obj.get_class_object().borrow_checker().release_borrow()
+            },
+        }
+    }
 }
 
 impl<T: PyClass> Deref for RefGuard<T> {
@@ -65,9 +189,23 @@ pub struct RefMutGuard<T: PyClass<Frozen = False>>(Py<T>);
 impl<T: PyClass<Frozen = False>> RefMutGuard<T> {
     pub fn new(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
         let bound = obj.downcast::<T>()?;
-        bound.get_class_object().borrow_checker().try_borrow_mut()?;
+        let class_object = bound.get_class_object();
+        class_object.ensure_threadsafe();
+        class_object.borrow_checker().try_borrow_mut()?;
         Ok(RefMutGuard(bound.clone().unbind()))
     }
+
+    /// Returns the release of the borrow of this guard, for a `LocalFuture` holding it.
+    pub fn borrow_release(&self, py: Python<'_>) -> BorrowRelease {
+        BorrowRelease {
+            obj: self.0.clone_ref(py).into_any(),
+            release: |obj| {
+                // SAFETY: `obj` is the instance of `T` borrowed by the guard
+                let obj = unsafe { obj.downcast_unchecked::<T>() };
+                obj.get_class_object().borrow_checker().release_borrow_mut()
+            },
+        }
+    }
 }
 
 impl<T: PyClass<Frozen = False>> Deref for RefMutGuard<T> {
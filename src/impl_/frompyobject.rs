@@ -10,7 +10,6 @@ pub fn failed_to_extract_enum(
     error_names: &[&str],
     errors: &[PyErr],
 ) -> PyErr {
-    // TODO maybe use ExceptionGroup on Python 3.11+ ?
     let mut err_msg = format!(
         "failed to extract enum {} ('{}')",
         type_name,
@@ -27,7 +26,35 @@ pub fn failed_to_extract_enum(
         )
         .unwrap();
     }
-    PyTypeError::new_err(err_msg)
+    let err = PyTypeError::new_err(err_msg);
+    #[cfg(Py_3_11)]
+    {
+        let group = variant_errors_group(py, type_name, variant_names, error_names, errors)
+            .unwrap_or_else(|err| err);
+        err.set_cause(py, Some(group));
+    }
+    err
+}
+
+/// Groups the errors of all variants in a `BaseExceptionGroup`, with a note naming the variant
+/// added to each of them.
+#[cfg(Py_3_11)]
+fn variant_errors_group(
+    py: Python<'_>,
+    type_name: &str,
+    variant_names: &[&str],
+    error_names: &[&str],
+    errors: &[PyErr],
+) -> PyResult<PyErr> {
+    let mut exceptions = Vec::with_capacity(errors.len());
+    for ((variant_name, error_name), error) in variant_names.iter().zip(error_names).zip(errors) {
+        error.add_note(py, &format!("variant {} ({})", variant_name, error_name))?;
+        exceptions.push(error.clone_ref(py));
+    }
+    Ok(PyErr::new_group(
+        format!("failed to extract enum {}", type_name),
+        exceptions,
+    ))
 }
 
 /// Flattens a chain of errors into a single string.
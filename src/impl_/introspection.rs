@@ -0,0 +1,519 @@
+//! Runtime support for the introspection data emitted by PyO3's macros when the
+//! `experimental-inspect` feature is enabled.
+//!
+//! The macros generate one function per `#[pyfunction]`, `#[pymethods]` method and `#[getter]`
+//! which builds the corresponding [`FunctionInfo`] or [`TypeInfo`]. Those functions are attached
+//! to the method definitions and registered here when the Python objects are created, so that
+//! [`ModuleInfo::from_module`](crate::inspect::interface::ModuleInfo::from_module) can find them
+//! back from the objects stored in a module.
+
+use std::borrow::Cow;
+use std::ffi::CStr;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+use crate::conversion::FromPyObjectBound;
+use crate::impl_::pyclass::MaybeRuntimePyMethodDef;
+use crate::impl_::pymethods::{PyMethodDef, PyMethodDefType};
+use crate::inspect::interface::{
+    AttributeInfo, ClassInfo, FunctionInfo, FunctionKind, ParameterInfo, ParameterKind,
+    PropertyInfo,
+};
+use crate::inspect::types::{ModuleName, TypeInfo};
+use crate::types::any::PyAnyMethods;
+use crate::types::tuple::PyTupleMethods;
+use crate::types::typeobject::PyTypeMethods;
+use crate::types::{PyCFunction, PyNone, PyTuple, PyType};
+use crate::{
+    ffi, Borrowed, Bound, IntoPyObject, Py, PyAny, PyClass, PyRef, PyRefMut, PyTypeInfo, Python,
+};
+
+/// Builds the description of a function.
+pub type FunctionIntrospection = for<'py> fn(Python<'py>) -> FunctionInfo;
+
+/// Builds the description of a type, used for `#[getter]`s.
+pub type TypeIntrospection = for<'py> fn(Python<'py>) -> TypeInfo;
+
+type ClassIntrospection = for<'py> fn(Python<'py>) -> ClassInfo;
+
+/// Functions created from a `PyMethodDef` with introspection data, keyed by their C function pointer.
+static FUNCTIONS: Mutex<Vec<(usize, FunctionIntrospection)>> = Mutex::new(Vec::new());
+
+/// Type objects created for `#[pyclass]`es, keyed by their address.
+static CLASSES: Mutex<Vec<(usize, ClassIntrospection)>> = Mutex::new(Vec::new());
+
+fn register<F>(registry: &Mutex<Vec<(usize, F)>>, key: usize, value: F) {
+    let mut registry = registry.lock().unwrap();
+    if !registry.iter().any(|(k, _)| *k == key) {
+        registry.push((key, value));
+    }
+}
+
+fn lookup<F: Copy>(registry: &Mutex<Vec<(usize, F)>>, key: usize) -> Option<F> {
+    let registry = registry.lock().unwrap();
+    registry.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
+/// Records the introspection data of a function about to be created from `def`.
+pub(crate) fn register_function(def: &ffi::PyMethodDef, introspection: FunctionIntrospection) {
+    // Safety: all variants of the union are function pointers, so reading any of them as an
+    // address is sound.
+    let key = unsafe { def.ml_meth.PyCFunction } as usize;
+    register(&FUNCTIONS, key, introspection);
+}
+
+/// Records the introspection data of the `#[pyclass]` `T`, whose type object has just been created.
+pub(crate) fn register_class<T: PyClass>(type_object: *mut ffi::PyTypeObject) {
+    register(&CLASSES, type_object as usize, class_info::<T>);
+}
+
+/// Returns the description of a function created by `#[pyfunction]`, if it has been registered.
+pub(crate) fn function_info(function: &Bound<'_, PyCFunction>) -> Option<FunctionInfo> {
+    let meth = unsafe { ffi::PyCFunction_GetFunction(function.as_ptr()) }?;
+    lookup(&FUNCTIONS, meth as usize).map(|introspection| introspection(function.py()))
+}
+
+/// Returns the description of a `#[pyclass]`, if it has been registered.
+pub(crate) fn class_info_of(class: &Bound<'_, PyType>) -> Option<ClassInfo> {
+    lookup(&CLASSES, class.as_type_ptr() as usize).map(|introspection| introspection(class.py()))
+}
+
+/// Builds the description of a `#[pyclass]` from its items.
+fn class_info<T: PyClass>(py: Python<'_>) -> ClassInfo {
+    let type_object = T::type_object(py);
+    let mut info = ClassInfo {
+        name: T::NAME.to_owned(),
+        doc: T::doc(py).ok().and_then(doc_from_cstr),
+        bases: bases_of(&type_object),
+        methods: Vec::new(),
+        properties: Vec::new(),
+        attributes: Vec::new(),
+    };
+    if let Some(constructor) = T::constructor_introspection() {
+        info.methods.push(constructor(py));
+    }
+    for items in T::items_iter() {
+        for def in items.methods {
+            let built_def;
+            let def = match def {
+                MaybeRuntimePyMethodDef::Runtime(builder) => {
+                    built_def = builder();
+                    &built_def
+                }
+                MaybeRuntimePyMethodDef::Static(def) => def,
+            };
+            match def {
+                PyMethodDefType::Method(def) | PyMethodDefType::AbstractMethod(def) => info
+                    .methods
+                    .push(method_info(py, def, FunctionKind::Method)),
+                PyMethodDefType::Class(def) => {
+                    info.methods
+                        .push(method_info(py, def, FunctionKind::ClassMethod))
+                }
+                PyMethodDefType::Static(def) => {
+                    info.methods
+                        .push(method_info(py, def, FunctionKind::StaticMethod))
+                }
+                PyMethodDefType::ClassAttribute(def) => {
+                    let name = def.name.to_string_lossy();
+                    let annotation = type_object
+                        .getattr(&*name)
+                        .map_or(TypeInfo::Any, |value| type_hint_of(&value.get_type()));
+                    info.attributes.push(AttributeInfo {
+                        name: name.into_owned(),
+                        annotation,
+                    });
+                }
+                PyMethodDefType::Getter(def) => {
+                    let property = property_entry(&mut info.properties, def.name);
+                    property.doc = doc_from_cstr(def.doc);
+                    if let Some(introspection) = def.introspection {
+                        property.annotation = introspection(py);
+                    }
+                }
+                PyMethodDefType::Setter(def) => {
+                    property_entry(&mut info.properties, def.name).writable = true;
+                }
+                PyMethodDefType::StructMember(def) => {
+                    let name = unsafe { CStr::from_ptr(def.name) };
+                    property_entry(&mut info.properties, name).writable =
+                        def.flags & ffi::Py_READONLY == 0;
+                }
+            }
+        }
+    }
+    if let Ok(dict) = type_object.getattr("__dict__") {
+        for (name, parameters, return_type) in SLOT_METHODS {
+            if info.methods.iter().all(|m| m.name != *name) && dict.contains(*name).unwrap_or(false)
+            {
+                info.methods
+                    .push(slot_method_info(name, parameters, return_type));
+            }
+        }
+    }
+    info
+}
+
+/// Protocol methods which are implemented with type slots, with their parameters and the name of
+/// their return type (`Any` if empty). `*` and `**` prefixes mark variadic parameters.
+#[rustfmt::skip]
+const SLOT_METHODS: &[(&str, &[&str], &str)] = &[
+    ("__repr__", &[], "str"), ("__str__", &[], "str"), ("__hash__", &[], "int"),
+    ("__len__", &[], "int"), ("__bool__", &[], "bool"), ("__contains__", &["key"], "bool"),
+    ("__iter__", &[], ""), ("__next__", &[], ""),
+    ("__aiter__", &[], ""), ("__anext__", &[], ""), ("__await__", &[], ""),
+    ("__getitem__", &["key"], ""), ("__setitem__", &["key", "value"], "None"),
+    ("__delitem__", &["key"], "None"),
+    ("__getattr__", &["name"], ""), ("__getattribute__", &["name"], ""),
+    ("__setattr__", &["name", "value"], "None"), ("__delattr__", &["name"], "None"),
+    ("__get__", &["instance", "owner"], ""), ("__set__", &["instance", "value"], "None"),
+    ("__delete__", &["instance"], "None"),
+    ("__call__", &["*args", "**kwargs"], ""),
+    ("__lt__", &["other"], ""), ("__le__", &["other"], ""), ("__eq__", &["other"], ""),
+    ("__ne__", &["other"], ""), ("__gt__", &["other"], ""), ("__ge__", &["other"], ""),
+    ("__add__", &["other"], ""), ("__radd__", &["other"], ""), ("__iadd__", &["other"], ""),
+    ("__sub__", &["other"], ""), ("__rsub__", &["other"], ""), ("__isub__", &["other"], ""),
+    ("__mul__", &["other"], ""), ("__rmul__", &["other"], ""), ("__imul__", &["other"], ""),
+    ("__matmul__", &["other"], ""), ("__rmatmul__", &["other"], ""), ("__imatmul__", &["other"], ""),
+    ("__truediv__", &["other"], ""), ("__rtruediv__", &["other"], ""), ("__itruediv__", &["other"], ""),
+    ("__floordiv__", &["other"], ""), ("__rfloordiv__", &["other"], ""), ("__ifloordiv__", &["other"], ""),
+    ("__mod__", &["other"], ""), ("__rmod__", &["other"], ""), ("__imod__", &["other"], ""),
+    ("__divmod__", &["other"], ""), ("__rdivmod__", &["other"], ""),
+    ("__pow__", &["other", "modulo=None"], ""), ("__rpow__", &["other", "modulo=None"], ""), ("__ipow__", &["other"], ""),
+    ("__lshift__", &["other"], ""), ("__rlshift__", &["other"], ""), ("__ilshift__", &["other"], ""),
+    ("__rshift__", &["other"], ""), ("__rrshift__", &["other"], ""), ("__irshift__", &["other"], ""),
+    ("__and__", &["other"], ""), ("__rand__", &["other"], ""), ("__iand__", &["other"], ""),
+    ("__xor__", &["other"], ""), ("__rxor__", &["other"], ""), ("__ixor__", &["other"], ""),
+    ("__or__", &["other"], ""), ("__ror__", &["other"], ""), ("__ior__", &["other"], ""),
+    ("__neg__", &[], ""), ("__pos__", &[], ""), ("__abs__", &[], ""), ("__invert__", &[], ""),
+    ("__int__", &[], "int"), ("__float__", &[], "float"), ("__index__", &[], "int"),
+];
+
+fn slot_method_info(
+    name: &'static str,
+    parameters: &[&'static str],
+    return_type: &str,
+) -> FunctionInfo {
+    FunctionInfo {
+        name: Cow::Borrowed(name),
+        doc: None,
+        kind: FunctionKind::Method,
+        parameters: parameters
+            .iter()
+            .map(|parameter| {
+                let (name, kind) = if let Some(name) = parameter.strip_prefix("**") {
+                    (name, ParameterKind::VarKeyword)
+                } else if let Some(name) = parameter.strip_prefix('*') {
+                    (name, ParameterKind::VarPositional)
+                } else {
+                    (*parameter, ParameterKind::PositionalOnly)
+                };
+                let (name, default) = match name.split_once('=') {
+                    Some((name, default)) => (name, Some(Cow::Borrowed(default))),
+                    None => (name, None),
+                };
+                ParameterInfo {
+                    name: Cow::Borrowed(name),
+                    kind,
+                    default,
+                    annotation: TypeInfo::Any,
+                }
+            })
+            .collect(),
+        return_type: match return_type {
+            "" => TypeInfo::Any,
+            "None" => TypeInfo::None,
+            name => TypeInfo::Class {
+                module: ModuleName::Builtin,
+                name: Cow::Owned(name.to_owned()),
+                type_vars: Vec::new(),
+            },
+        },
+    }
+}
+
+fn method_info(py: Python<'_>, def: &PyMethodDef, kind: FunctionKind) -> FunctionInfo {
+    let mut info = match def.introspection {
+        Some(introspection) => introspection(py),
+        None => FunctionInfo {
+            name: Cow::Owned(def.ml_name.to_string_lossy().into_owned()),
+            doc: None,
+            kind,
+            parameters: vec![
+                ParameterInfo {
+                    name: Cow::Borrowed("args"),
+                    kind: ParameterKind::VarPositional,
+                    default: None,
+                    annotation: TypeInfo::Any,
+                },
+                ParameterInfo {
+                    name: Cow::Borrowed("kwargs"),
+                    kind: ParameterKind::VarKeyword,
+                    default: None,
+                    annotation: TypeInfo::Any,
+                },
+            ],
+            return_type: TypeInfo::Any,
+        },
+    };
+    info.doc = doc_from_cstr(def.ml_doc);
+    info
+}
+
+/// Finds the property called `name`, creating it if needed.
+fn property_entry<'a>(properties: &'a mut Vec<PropertyInfo>, name: &CStr) -> &'a mut PropertyInfo {
+    let name = name.to_string_lossy();
+    let index = match properties.iter().position(|p| p.name == name) {
+        Some(index) => index,
+        None => {
+            properties.push(PropertyInfo {
+                name: name.into_owned(),
+                doc: None,
+                annotation: TypeInfo::Any,
+                writable: false,
+            });
+            properties.len() - 1
+        }
+    };
+    &mut properties[index]
+}
+
+/// Extracts the docstring from a doc which may start with a `__text_signature__`.
+fn doc_from_cstr(doc: &CStr) -> Option<String> {
+    let doc = doc.to_string_lossy();
+    let doc = match doc.find("\n--\n\n") {
+        Some(index) => &doc[index + 5..],
+        None => &doc,
+    };
+    if doc.is_empty() {
+        None
+    } else {
+        Some(doc.to_owned())
+    }
+}
+
+/// Returns the base classes of a type, omitting `object`.
+pub(crate) fn bases_of(class: &Bound<'_, PyType>) -> Vec<TypeInfo> {
+    class
+        .getattr("__bases__")
+        .and_then(|bases| bases.downcast_into::<PyTuple>().map_err(Into::into))
+        .map(|bases| {
+            bases
+                .iter()
+                .filter_map(|base| base.downcast_into::<PyType>().ok())
+                .map(|base| type_hint_of(&base))
+                .filter(|base| *base != TypeInfo::Any)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Converts a type object into a type hint.
+pub(crate) fn type_hint_of(class: &Bound<'_, PyType>) -> TypeInfo {
+    let py = class.py();
+    if class.is(&PyAny::type_object(py)) {
+        return TypeInfo::Any;
+    }
+    if class.is(&PyNone::type_object(py)) {
+        return TypeInfo::None;
+    }
+    let (module, name) = match (class.module(), class.qualname()) {
+        (Ok(module), Ok(name)) => (module.to_string(), name),
+        _ => return TypeInfo::Any,
+    };
+    TypeInfo::Class {
+        module: if module == "builtins" {
+            ModuleName::Builtin
+        } else {
+            ModuleName::Module(Cow::Owned(module))
+        },
+        name: Cow::Owned(name.to_string()),
+        type_vars: Vec::new(),
+    }
+}
+
+/// Attaches the type of a `#[pyo3(get)]` field to its getter.
+///
+/// Fields which are exposed as struct members keep the `Any` type.
+pub fn with_getter_introspection(
+    def: PyMethodDefType,
+    introspection: TypeIntrospection,
+) -> PyMethodDefType {
+    match def {
+        PyMethodDefType::Getter(def) => {
+            PyMethodDefType::Getter(def.with_introspection(introspection))
+        }
+        def => def,
+    }
+}
+
+/// Wraps the return type of an `async fn`.
+pub fn coroutine_of(t: TypeInfo) -> TypeInfo {
+    TypeInfo::Class {
+        module: ModuleName::Module(Cow::Borrowed("typing")),
+        name: Cow::Borrowed("Coroutine"),
+        type_vars: vec![TypeInfo::Any, TypeInfo::Any, t],
+    }
+}
+
+/// Marker used by the macros to select the most precise type hint of `T` using autoref
+/// specialization, in order of preference:
+/// - [`PyTypeHint`], for types which are or reference a [`PyTypeInfo`] type,
+/// - [`InputTypeHint`] and [`OutputTypeHint`], which use [`FromPyObject::type_input`](crate::FromPyObject::type_input)
+///   and [`IntoPyObject::type_output`],
+/// - [`AnyTypeHint`], which falls back to `typing.Any`.
+pub struct TypeHint<T>(PhantomData<T>);
+
+impl<T> TypeHint<T> {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        TypeHint(PhantomData)
+    }
+}
+
+impl<T> Clone for TypeHint<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TypeHint<T> {}
+
+/// Types which are a reference to a Python object of known type.
+pub trait PyTypeReference {
+    type Target: PyTypeInfo;
+}
+
+impl<T: PyClass> PyTypeReference for T {
+    type Target = T;
+}
+
+impl<T: PyTypeInfo> PyTypeReference for Bound<'_, T> {
+    type Target = T;
+}
+
+impl<T: PyTypeInfo> PyTypeReference for &Bound<'_, T> {
+    type Target = T;
+}
+
+impl<T: PyTypeInfo> PyTypeReference for Borrowed<'_, '_, T> {
+    type Target = T;
+}
+
+impl<T: PyTypeInfo> PyTypeReference for Py<T> {
+    type Target = T;
+}
+
+impl<T: PyClass> PyTypeReference for PyRef<'_, T> {
+    type Target = T;
+}
+
+impl<T: PyClass<Frozen = crate::pyclass::boolean_struct::False>> PyTypeReference
+    for PyRefMut<'_, T>
+{
+    type Target = T;
+}
+
+pub trait PyTypeHint {
+    fn input_hint(self, py: Python<'_>) -> TypeInfo;
+    fn output_hint(self, py: Python<'_>) -> TypeInfo;
+}
+
+impl<T: PyTypeReference> PyTypeHint for &&TypeHint<T> {
+    fn input_hint(self, py: Python<'_>) -> TypeInfo {
+        type_hint_of(&T::Target::type_object(py))
+    }
+
+    fn output_hint(self, py: Python<'_>) -> TypeInfo {
+        type_hint_of(&T::Target::type_object(py))
+    }
+}
+
+pub trait InputTypeHint {
+    fn input_hint(self, py: Python<'_>) -> TypeInfo;
+}
+
+impl<'a, 'py, T: FromPyObjectBound<'a, 'py>> InputTypeHint for &TypeHint<T> {
+    fn input_hint(self, _py: Python<'_>) -> TypeInfo {
+        T::type_input()
+    }
+}
+
+pub trait OutputTypeHint {
+    fn output_hint(self, py: Python<'_>) -> TypeInfo;
+}
+
+impl<'py, T: IntoPyObject<'py>> OutputTypeHint for &TypeHint<T> {
+    fn output_hint(self, _py: Python<'_>) -> TypeInfo {
+        T::type_output()
+    }
+}
+
+pub trait AnyTypeHint {
+    fn input_hint(self, py: Python<'_>) -> TypeInfo;
+    fn output_hint(self, py: Python<'_>) -> TypeInfo;
+}
+
+impl<T> AnyTypeHint for TypeHint<T> {
+    fn input_hint(self, _py: Python<'_>) -> TypeInfo {
+        TypeInfo::Any
+    }
+
+    fn output_hint(self, _py: Python<'_>) -> TypeInfo {
+        TypeInfo::Any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PyDict, PyString};
+
+    #[test]
+    #[allow(clippy::needless_borrow)]
+    fn test_type_hints() {
+        Python::with_gil(|py| {
+            assert_eq!(
+                (&&TypeHint::<i32>::new()).input_hint(py),
+                TypeInfo::builtin("int")
+            );
+            assert_eq!(
+                (&&TypeHint::<Vec<String>>::new()).output_hint(py),
+                TypeInfo::list_of(TypeInfo::builtin("str"))
+            );
+            assert_eq!(
+                (&&TypeHint::<Bound<'_, PyDict>>::new()).input_hint(py),
+                TypeInfo::builtin("dict")
+            );
+            assert_eq!(
+                (&&TypeHint::<Py<PyString>>::new()).output_hint(py),
+                TypeInfo::builtin("str")
+            );
+            assert_eq!(
+                (&&TypeHint::<Bound<'_, PyAny>>::new()).input_hint(py),
+                TypeInfo::Any
+            );
+            assert_eq!(
+                (&&TypeHint::<std::cell::Cell<u8>>::new()).input_hint(py),
+                TypeInfo::Any
+            );
+            assert_eq!(
+                (&&TypeHint::<&std::cell::Cell<u8>>::new()).output_hint(py),
+                TypeInfo::Any
+            );
+        })
+    }
+
+    #[test]
+    fn test_doc_from_cstr() {
+        assert_eq!(doc_from_cstr(ffi::c_str!("")), None);
+        assert_eq!(
+            doc_from_cstr(ffi::c_str!("f(a, b)\n--\n\nSome doc")),
+            Some("Some doc".to_owned())
+        );
+        assert_eq!(
+            doc_from_cstr(ffi::c_str!("Some doc")),
+            Some("Some doc".to_owned())
+        );
+    }
+}
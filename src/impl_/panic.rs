@@ -1,3 +1,5 @@
+use crate::{ffi, ffi_ptr_ext::FfiPtrExt, panic::PanicPolicy, Python};
+
 /// Type which will panic if dropped.
 ///
 /// If this is dropped during a panic, this will cause an abort.
@@ -25,3 +27,17 @@ impl Drop for PanicTrap {
         panic!("{}", self.msg)
     }
 }
+
+/// Returns the panic policy of the module `owner` belongs to: the module of a function, or the
+/// instance or class of a method. Null pointers get the default policy.
+///
+/// # Safety
+///
+/// `owner` must be null or a valid pointer to a Python object.
+#[inline]
+pub unsafe fn panic_policy(py: Python<'_>, owner: *mut ffi::PyObject) -> PanicPolicy {
+    match owner.assume_borrowed_or_opt(py) {
+        Some(owner) => crate::impl_::pymodule::module_panic_policy(&owner),
+        None => PanicPolicy::Raise,
+    }
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "experimental-inspect")]
+use crate::impl_::introspection::FunctionIntrospection;
 use crate::{
     exceptions::{PyAttributeError, PyNotImplementedError, PyRuntimeError, PyValueError},
     ffi,
@@ -5,11 +7,11 @@ use crate::{
         freelist::FreeList,
         pycell::{GetBorrowChecker, PyClassMutability, PyClassObjectLayout},
         pyclass_init::PyObjectInit,
-        pymethods::{PyGetterDef, PyMethodDefType},
+        pymethods::{PyGetterDef, PyMethodDef, PyMethodDefType},
     },
     pycell::PyBorrowError,
-    types::{any::PyAnyMethods, PyBool},
-    Borrowed, BoundObject, IntoPyObject, IntoPyObjectExt, Py, PyAny, PyClass, PyErr, PyRef,
+    types::{any::PyAnyMethods, PyBool, PyType},
+    Borrowed, Bound, BoundObject, IntoPyObject, IntoPyObjectExt, Py, PyAny, PyClass, PyErr, PyRef,
     PyResult, PyTypeInfo, Python,
 };
 #[allow(deprecated)]
@@ -23,21 +25,25 @@ use std::{
     thread,
 };
 
+#[cfg(feature = "macros")]
+mod abc;
 mod assertions;
 mod lazy_type_object;
+mod pickle;
 mod probes;
 
 pub use assertions::*;
 pub use lazy_type_object::LazyTypeObject;
+pub use pickle::*;
 pub use probes::*;
 
-/// Gets the offset of the dictionary from the start of the object in bytes.
+/// Gets the offset of the dictionary from the start of the contents of the object in bytes.
 #[inline]
 pub fn dict_offset<T: PyClass>() -> ffi::Py_ssize_t {
     PyClassObject::<T>::dict_offset()
 }
 
-/// Gets the offset of the weakref list from the start of the object in bytes.
+/// Gets the offset of the weakref list from the start of the contents of the object in bytes.
 #[inline]
 pub fn weaklist_offset<T: PyClass>() -> ffi::Py_ssize_t {
     PyClassObject::<T>::weaklist_offset()
@@ -176,6 +182,24 @@ pub trait PyClassImpl: Sized + 'static {
     /// #[pyclass(sequence)]
     const IS_SEQUENCE: bool = false;
 
+    /// #[pyclass(abstract)]
+    const IS_ABSTRACT: bool = false;
+
+    /// #[pyclass(register = "...")]
+    const REGISTER_ABC: Option<&'static str> = None;
+
+    /// #[pyclass(extends = "...")], the import path of the Python class this class extends.
+    const PYTHON_BASE: Option<&'static str> = None;
+
+    /// Whether the contents of instances are placed after the runtime size of the base class,
+    /// rather than after its Rust layout.
+    const DYNAMIC_LAYOUT: bool =
+        Self::PYTHON_BASE.is_some() || <Self::BaseType as PyClassBaseType>::DYNAMIC_LAYOUT;
+
+    /// Whether a Python class is among the bases of this class.
+    const EXTENDS_PYTHON_CLASS: bool =
+        Self::PYTHON_BASE.is_some() || <Self::BaseType as PyClassBaseType>::EXTENDS_PYTHON_CLASS;
+
     /// Base class
     type BaseType: PyTypeInfo + PyClassBaseType;
 
@@ -220,6 +244,39 @@ pub trait PyClassImpl: Sized + 'static {
     }
 
     fn lazy_type_object() -> &'static LazyTypeObject<Self>;
+
+    /// Description of the `#[new]` constructor, used by `experimental-inspect`.
+    #[cfg(feature = "experimental-inspect")]
+    #[inline]
+    fn constructor_introspection() -> Option<FunctionIntrospection> {
+        None
+    }
+
+    /// Vectorcall implementation of `#[new]`, set as `tp_vectorcall` of the type object.
+    #[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))]
+    #[inline]
+    fn new_vectorcall() -> Option<ffi::vectorcallfunc> {
+        None
+    }
+
+    /// Vectorcall implementation of `__call__`, stored in each instance after the class layout.
+    #[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))]
+    #[inline]
+    fn call_vectorcall() -> Option<ffi::vectorcallfunc> {
+        None
+    }
+
+    /// #[pyclass(metaclass = ...)]
+    #[inline]
+    fn metaclass(_py: Python<'_>) -> PyResult<Option<Bound<'_, PyType>>> {
+        Ok(None)
+    }
+
+    /// Reconstructor of `#[pyclass(pickle)]` instances, added to the module of the class.
+    #[inline]
+    fn pickle_reconstructor() -> Option<&'static PyMethodDef> {
+        None
+    }
 }
 
 /// Runtime helper to build a class docstring from the `doc` and `text_signature`.
@@ -245,6 +302,18 @@ pub fn build_pyclass_doc(
     }
 }
 
+/// Imports the class at `path`, such as `"collections.abc.Sequence"`, for the `#[pyclass]`
+/// options which refer to Python classes.
+pub fn import_type<'py>(py: Python<'py>, path: &str) -> PyResult<Bound<'py, PyType>> {
+    let (module, name) = path
+        .rsplit_once('.')
+        .expect("import paths are validated by the `#[pyclass]` macro");
+    py.import(module)?
+        .getattr(name)?
+        .downcast_into()
+        .map_err(Into::into)
+}
+
 /// Iterator used to process all class items during type instantiation.
 pub struct PyClassItemsIter {
     /// Iteration state
@@ -1035,6 +1104,48 @@ impl<T> PyClassNewTextSignature<T> for &'_ PyClassImplCollector<T> {
     }
 }
 
+// Vectorcall implementation of __new__
+#[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))]
+pub trait PyClassNewVectorcall<T> {
+    fn new_vectorcall(self) -> Option<ffi::vectorcallfunc>;
+}
+
+#[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))]
+impl<T> PyClassNewVectorcall<T> for &'_ PyClassImplCollector<T> {
+    #[inline]
+    fn new_vectorcall(self) -> Option<ffi::vectorcallfunc> {
+        None
+    }
+}
+
+// Vectorcall implementation of __call__
+#[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))]
+pub trait PyClassCallVectorcall<T> {
+    fn call_vectorcall(self) -> Option<ffi::vectorcallfunc>;
+}
+
+#[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))]
+impl<T> PyClassCallVectorcall<T> for &'_ PyClassImplCollector<T> {
+    #[inline]
+    fn call_vectorcall(self) -> Option<ffi::vectorcallfunc> {
+        None
+    }
+}
+
+// Description of __new__, used by `experimental-inspect`
+#[cfg(feature = "experimental-inspect")]
+pub trait PyClassNewIntrospection<T> {
+    fn new_introspection(self) -> Option<FunctionIntrospection>;
+}
+
+#[cfg(feature = "experimental-inspect")]
+impl<T> PyClassNewIntrospection<T> for &'_ PyClassImplCollector<T> {
+    #[inline]
+    fn new_introspection(self) -> Option<FunctionIntrospection> {
+        None
+    }
+}
+
 // Thread checkers
 
 #[doc(hidden)]
@@ -1118,7 +1229,7 @@ impl<T> PyClassThreadChecker<T> for ThreadCheckerImpl {
 /// Trait denoting that this class is suitable to be used as a base type for PyClass.
 
 #[cfg_attr(
-    all(diagnostic_namespace, Py_LIMITED_API),
+    all(diagnostic_namespace, Py_LIMITED_API, not(Py_3_12)),
     diagnostic::on_unimplemented(
         message = "pyclass `{Self}` cannot be subclassed",
         label = "required for `#[pyclass(extends={Self})]`",
@@ -1127,7 +1238,7 @@ impl<T> PyClassThreadChecker<T> for ThreadCheckerImpl {
     )
 )]
 #[cfg_attr(
-    all(diagnostic_namespace, not(Py_LIMITED_API)),
+    all(diagnostic_namespace, any(not(Py_LIMITED_API), Py_3_12)),
     diagnostic::on_unimplemented(
         message = "pyclass `{Self}` cannot be subclassed",
         label = "required for `#[pyclass(extends={Self})]`",
@@ -1139,6 +1250,10 @@ pub trait PyClassBaseType: Sized {
     type BaseNativeType;
     type Initializer: PyObjectInit<Self>;
     type PyClassMutability: PyClassMutability;
+    /// Whether subclasses must place their contents after the runtime size of this type.
+    const DYNAMIC_LAYOUT: bool = false;
+    /// Whether a Python class is among the bases of this type.
+    const EXTENDS_PYTHON_CLASS: bool = false;
 }
 
 /// Implementation of tp_dealloc for pyclasses without gc
@@ -1201,7 +1316,7 @@ pub unsafe trait OffsetCalculator<T: PyClass, U> {
 
 // Used in generated implementations of OffsetCalculator
 pub fn class_offset<T: PyClass>() -> usize {
-    offset_of!(PyClassObject<T>, contents)
+    PyClassObject::<T>::contents_offset()
 }
 
 // Used in generated implementations of OffsetCalculator
@@ -1292,6 +1407,8 @@ impl<
                 name,
                 meth: pyo3_get_value_topyobject::<ClassT, Py<U>, Offset>,
                 doc,
+                #[cfg(feature = "experimental-inspect")]
+                introspection: None,
             })
         }
     }
@@ -1312,6 +1429,8 @@ impl<
             name,
             meth: pyo3_get_value_topyobject::<ClassT, FieldT, Offset>,
             doc,
+            #[cfg(feature = "experimental-inspect")]
+            introspection: None,
         })
     }
 }
@@ -1346,6 +1465,8 @@ where
             name,
             meth: pyo3_get_value_into_pyobject_ref::<ClassT, FieldT, Offset>,
             doc,
+            #[cfg(feature = "experimental-inspect")]
+            introspection: None,
         })
     }
 }
@@ -1373,6 +1494,8 @@ where
             name,
             meth: pyo3_get_value_into_pyobject::<ClassT, FieldT, Offset>,
             doc,
+            #[cfg(feature = "experimental-inspect")]
+            introspection: None,
         })
     }
 }
@@ -1391,6 +1514,8 @@ where
             name,
             meth: pyo3_get_value::<ClassT, FieldT, Offset>,
             doc,
+            #[cfg(feature = "experimental-inspect")]
+            introspection: None,
         })
     }
 }
@@ -0,0 +1,175 @@
+//! Support for `#[pyclass(abstract)]`, `#[pyclass(register = "...")]` and
+//! `#[pyo3(abstractmethod)]`.
+
+use std::ffi::CStr;
+
+use crate::exceptions::PyAttributeError;
+use crate::prelude::*;
+use crate::types::{PyCFunction, PyDict, PyFrozenSet, PyString, PySuper, PyTuple, PyType};
+use crate::{ffi, intern, pyclass, pymethods};
+
+/// Descriptor wrapping the builtin method of an abstract method.
+///
+/// Builtin methods don't accept attributes, so this stands in for them to carry
+/// `__isabstractmethod__`, which is what `abc` and `inspect` look for.
+#[pyclass(
+    crate = "crate",
+    frozen,
+    module = "builtins",
+    name = "abstractmethod_descriptor"
+)]
+struct AbstractMethod {
+    method: PyObject,
+}
+
+#[pymethods(crate = "crate")]
+impl AbstractMethod {
+    fn __get__<'py>(
+        slf: PyRef<'py, Self>,
+        instance: &Bound<'py, PyAny>,
+        owner: Option<&Bound<'py, PyType>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let py = slf.py();
+        if instance.is_none() {
+            return Ok(slf.into_pyobject(py)?.into_any());
+        }
+        slf.method
+            .bind(py)
+            .call_method1(intern!(py, "__get__"), (instance, owner))
+    }
+
+    #[pyo3(signature = (*args, **kwargs))]
+    fn __call__<'py>(
+        &self,
+        py: Python<'py>,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.method.bind(py).call(args.clone(), kwargs)
+    }
+
+    #[getter]
+    fn __isabstractmethod__(&self) -> bool {
+        true
+    }
+
+    #[getter]
+    fn __wrapped__(&self, py: Python<'_>) -> PyObject {
+        self.method.clone_ref(py)
+    }
+}
+
+/// Finishes the setup of a `#[pyclass]` once its `__dict__` has been filled.
+pub(crate) fn initialize_abc(
+    type_object: &Bound<'_, PyType>,
+    abstract_methods: &[&'static CStr],
+    is_abstract: bool,
+    register: Option<&'static str>,
+) -> PyResult<()> {
+    let py = type_object.py();
+
+    for name in abstract_methods {
+        let name = name.to_str()?;
+        let method = type_object.getattr(name)?.unbind();
+        type_object.setattr(name, AbstractMethod { method })?;
+    }
+
+    if is_abstract {
+        // Classes created by `type.__new__` don't compute their abstract methods, only
+        // `ABCMeta` does, so subclasses defined in Python recompute them here instead.
+        let dict = type_object.getattr(intern!(py, "__dict__"))?;
+        if !dict.contains(intern!(py, "__init_subclass__"))? {
+            let ty = type_object.clone().unbind();
+            let init_subclass = PyCFunction::new_closure(
+                py,
+                Some(ffi::c_str!("__init_subclass__")),
+                None,
+                move |args: &Bound<'_, PyTuple>, kwargs: Option<&Bound<'_, PyDict>>| {
+                    let py = args.py();
+                    let cls = args.get_item(0)?.downcast_into::<PyType>()?;
+                    update_abstract_methods(&cls)?;
+                    PySuper::new(ty.bind(py), &cls)?
+                        .getattr(intern!(py, "__init_subclass__"))?
+                        .call((), kwargs)
+                        .map(|_| ())
+                },
+            )?;
+            let classmethod = py
+                .import(intern!(py, "builtins"))?
+                .getattr(intern!(py, "classmethod"))?
+                .call1((init_subclass,))?;
+            type_object.setattr(intern!(py, "__init_subclass__"), classmethod)?;
+        }
+    }
+
+    let mut has_abstract_base = false;
+    for base in type_object.getattr(intern!(py, "__bases__"))?.try_iter()? {
+        let base = base?.downcast_into::<PyType>()?;
+        has_abstract_base |=
+            unsafe { ffi::PyType_GetFlags(base.as_type_ptr()) } & ffi::Py_TPFLAGS_IS_ABSTRACT != 0;
+    }
+    if is_abstract || has_abstract_base {
+        update_abstract_methods(type_object)?;
+    }
+
+    if let Some(register) = register {
+        super::import_type(py, register)?.call_method1(intern!(py, "register"), (type_object,))?;
+    }
+
+    Ok(())
+}
+
+/// Recomputes `__abstractmethods__` of `cls`, like `abc.update_abstractmethods`.
+fn update_abstract_methods(cls: &Bound<'_, PyType>) -> PyResult<()> {
+    let py = cls.py();
+    let is_abstract_method = |value: &Bound<'_, PyAny>| -> PyResult<bool> {
+        match getattr_opt(value, intern!(py, "__isabstractmethod__"))? {
+            Some(flag) => flag.is_truthy(),
+            None => Ok(false),
+        }
+    };
+
+    let mut names = Vec::new();
+    for base in cls.getattr(intern!(py, "__bases__"))?.try_iter()? {
+        let base_abstracts = match getattr_opt(&base?, intern!(py, "__abstractmethods__"))? {
+            Some(base_abstracts) => base_abstracts,
+            None => continue,
+        };
+        for name in base_abstracts.try_iter()? {
+            let name = name?.downcast_into::<PyString>()?;
+            if let Some(value) = getattr_opt(cls.as_any(), &name)? {
+                if is_abstract_method(&value)? {
+                    names.push(name);
+                }
+            }
+        }
+    }
+
+    let dict = cls.getattr(intern!(py, "__dict__"))?;
+    for item in dict.call_method0(intern!(py, "items"))?.try_iter()? {
+        let (name, value): (Bound<'_, PyString>, Bound<'_, PyAny>) = item?.extract()?;
+        if is_abstract_method(&value)? {
+            names.push(name);
+        }
+    }
+
+    if !names.is_empty() || dict.contains(intern!(py, "__abstractmethods__"))? {
+        cls.setattr(
+            intern!(py, "__abstractmethods__"),
+            PyFrozenSet::new(py, &names)?,
+        )?;
+    }
+    Ok(())
+}
+
+/// `getattr(obj, name, None)`, distinguishing a missing attribute from `None`.
+fn getattr_opt<'py>(
+    obj: &Bound<'py, PyAny>,
+    name: &Bound<'py, PyString>,
+) -> PyResult<Option<Bound<'py, PyAny>>> {
+    match obj.getattr(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(err) if err.is_instance_of::<PyAttributeError>(obj.py()) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
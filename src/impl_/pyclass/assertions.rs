@@ -1,3 +1,7 @@
+use crate::impl_::pyclass::PyClassBaseType;
+use crate::impl_::pyclass_init::PyNativeTypeInitializer;
+use crate::PyTypeInfo;
+
 /// Helper function that can be used at compile time to emit a diagnostic if
 /// the type does not implement `Sync` when it should.
 ///
@@ -26,6 +30,64 @@ pub trait PyClassSync<T: Sync = Self> {}
 
 impl<T> PyClassSync for T where T: Sync {}
 
+/// Helper function that can be used at compile time to emit a diagnostic if
+/// a `#[pyclass(dataclass)]` extends another `#[pyclass]`.
+///
+/// The constructor generated by `dataclass` only initializes the fields of the
+/// class itself, so the base must be a native type.
+#[allow(unused)]
+pub const fn assert_dataclass_base<T>()
+where
+    T: PyClassBaseType,
+    T::Initializer: DataclassBase,
+{
+}
+
+#[cfg_attr(
+    diagnostic_namespace,
+    diagnostic::on_unimplemented(
+        message = "`#[pyclass(dataclass)]` cannot extend another `#[pyclass]`",
+        label = "extends a `#[pyclass]`",
+        note = "the constructor generated by `dataclass` cannot initialize the base class",
+    )
+)]
+pub trait DataclassBase {}
+
+impl<T: PyTypeInfo> DataclassBase for PyNativeTypeInitializer<T> {}
+
+/// Helper function that can be used at compile time to emit a diagnostic if
+/// a class with an `#[init]` method has no `#[new]` constructor.
+///
+/// Without `__new__`, the class cannot be instantiated from Python, so its
+/// `__init__` would never run.
+#[allow(unused)]
+pub const fn assert_pyclass_has_new<T: PyClassHasNew>() {}
+
+#[cfg_attr(
+    diagnostic_namespace,
+    diagnostic::on_unimplemented(
+        message = "`#[init]` requires `{Self}` to have a `#[new]` method",
+        label = "`{Self}` has no constructor",
+        note = "without `#[new]`, the class cannot be instantiated from Python, so `__init__` never runs",
+    )
+)]
+pub trait PyClassHasNew {}
+
+/// Helper function that can be used at compile time to emit a diagnostic if
+/// generic `#[pymethods]` are implemented for a class without `generic(...)`.
+#[allow(unused)]
+pub const fn assert_generic_pyclass<T: GenericPyClass>() {}
+
+#[cfg_attr(
+    diagnostic_namespace,
+    diagnostic::on_unimplemented(
+        message = "`{Self}` is not a `#[pyclass(generic(...))]`",
+        label = "generic `#[pymethods]` are expanded for the instantiations listed with `generic(...)`",
+        note = "list the instantiations to expose to Python with `#[pyclass(generic(...))]`",
+    )
+)]
+pub trait GenericPyClass {}
+
 mod tests {
     #[cfg(feature = "macros")]
     #[test]
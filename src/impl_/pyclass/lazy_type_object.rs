@@ -9,11 +9,16 @@ use crate::{
     ffi,
     impl_::pyclass::MaybeRuntimePyMethodDef,
     impl_::pymethods::PyMethodDefType,
-    pyclass::{create_type_object, PyClassTypeObject},
-    sync::GILOnceCell,
+    pyclass::{create_type_object, DynamicLayout, PyClassTypeObject},
+    sync::InterpreterOnceCell,
     types::PyType,
     Bound, PyClass, PyErr, PyObject, PyResult, Python,
 };
+#[cfg(all(
+    any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+    not(any(PyPy, GraalPy))
+))]
+use crate::{pyclass::create_module_type_object, types::PyModule};
 
 use std::sync::Mutex;
 
@@ -25,11 +30,11 @@ pub struct LazyTypeObject<T>(LazyTypeObjectInner, PhantomData<T>);
 
 // Non-generic inner of LazyTypeObject to keep code size down
 struct LazyTypeObjectInner {
-    value: GILOnceCell<PyClassTypeObject>,
+    value: InterpreterOnceCell<PyClassTypeObject>,
     // Threads which have begun initialization of the `tp_dict`. Used for
     // reentrant initialization detection.
     initializing_threads: Mutex<Vec<ThreadId>>,
-    tp_dict_filled: GILOnceCell<()>,
+    tp_dict_filled: InterpreterOnceCell<()>,
 }
 
 impl<T> LazyTypeObject<T> {
@@ -38,13 +43,34 @@ impl<T> LazyTypeObject<T> {
     pub const fn new() -> Self {
         LazyTypeObject(
             LazyTypeObjectInner {
-                value: GILOnceCell::new(),
+                value: InterpreterOnceCell::new(),
                 initializing_threads: Mutex::new(Vec::new()),
-                tp_dict_filled: GILOnceCell::new(),
+                tp_dict_filled: InterpreterOnceCell::new(),
             },
             PhantomData,
         )
     }
+
+    /// Gets the type object, if it has been created.
+    pub(crate) fn get<'py>(&self, py: Python<'py>) -> Option<&Bound<'py, PyType>> {
+        self.0
+            .value
+            .get(py)
+            .map(|type_object| type_object.type_object.bind(py))
+    }
+
+    /// Gets the layout of instances of a class with a dynamic layout, which is computed when the
+    /// type object is created in the current interpreter.
+    ///
+    /// # Panics
+    /// If the type object has not been created in the current interpreter.
+    pub(crate) fn dynamic_layout(&self, py: Python<'_>) -> &DynamicLayout {
+        self.0
+            .value
+            .get(py)
+            .and_then(|type_object| type_object.dynamic_layout.as_ref())
+            .expect("type object of a class with a dynamic layout should have been created")
+    }
 }
 
 impl<T: PyClass> LazyTypeObject<T> {
@@ -58,8 +84,38 @@ impl<T: PyClass> LazyTypeObject<T> {
 
     /// Fallible version of the above.
     pub(crate) fn get_or_try_init<'py>(&self, py: Python<'py>) -> PyResult<&Bound<'py, PyType>> {
-        self.0
-            .get_or_try_init(py, create_type_object::<T>, T::NAME, T::items_iter())
+        self.0.get_or_try_init(
+            py,
+            &create_type_object::<T>,
+            T::NAME,
+            T::items_iter(),
+            AbcOptions {
+                is_abstract: T::IS_ABSTRACT,
+                register: T::REGISTER_ABC,
+            },
+        )
+    }
+
+    /// Gets the type object of `T`, creating it associated with `module` if needed, so that its
+    /// methods can access the state and the panic policy of the module.
+    #[cfg(all(
+        any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+        not(any(PyPy, GraalPy))
+    ))]
+    pub(crate) fn get_or_try_init_in_module<'py>(
+        &self,
+        module: &Bound<'py, PyModule>,
+    ) -> PyResult<&Bound<'py, PyType>> {
+        self.0.get_or_try_init(
+            module.py(),
+            &|_| create_module_type_object::<T>(module),
+            T::NAME,
+            T::items_iter(),
+            AbcOptions {
+                is_abstract: T::IS_ABSTRACT,
+                register: T::REGISTER_ABC,
+            },
+        )
     }
 }
 
@@ -70,9 +126,10 @@ impl LazyTypeObjectInner {
     fn get_or_try_init<'py>(
         &self,
         py: Python<'py>,
-        init: fn(Python<'py>) -> PyResult<PyClassTypeObject>,
+        init: &dyn Fn(Python<'py>) -> PyResult<PyClassTypeObject>,
         name: &str,
         items_iter: PyClassItemsIter,
+        abc: AbcOptions,
     ) -> PyResult<&Bound<'py, PyType>> {
         (|| -> PyResult<_> {
             let type_object = self
@@ -80,7 +137,7 @@ impl LazyTypeObjectInner {
                 .get_or_try_init(py, || init(py))?
                 .type_object
                 .bind(py);
-            self.ensure_init(type_object, name, items_iter)?;
+            self.ensure_init(type_object, name, items_iter, abc)?;
             Ok(type_object)
         })()
         .map_err(|err| {
@@ -97,6 +154,7 @@ impl LazyTypeObjectInner {
         type_object: &Bound<'_, PyType>,
         name: &str,
         items_iter: PyClassItemsIter,
+        abc: AbcOptions,
     ) -> PyResult<()> {
         let py = type_object.py();
 
@@ -148,6 +206,7 @@ impl LazyTypeObjectInner {
         // means that another thread can continue the initialization in the
         // meantime: at worst, we'll just make a useless computation.
         let mut items = vec![];
+        let mut abstract_methods = vec![];
         for class_items in items_iter {
             for def in class_items.methods {
                 let built_method;
@@ -158,7 +217,9 @@ impl LazyTypeObjectInner {
                     }
                     MaybeRuntimePyMethodDef::Static(method) => method,
                 };
-                if let PyMethodDefType::ClassAttribute(attr) = method {
+                if let PyMethodDefType::AbstractMethod(def) = method {
+                    abstract_methods.push(def.ml_name);
+                } else if let PyMethodDefType::ClassAttribute(attr) = method {
                     match (attr.meth)(py) {
                         Ok(val) => items.push((attr.name, val)),
                         Err(err) => {
@@ -180,7 +241,8 @@ impl LazyTypeObjectInner {
         // Now we hold the GIL and we can assume it won't be released until we
         // return from the function.
         let result = self.tp_dict_filled.get_or_try_init(py, move || {
-            let result = initialize_tp_dict(py, type_object.as_ptr(), items);
+            let result = initialize_tp_dict(py, type_object.as_ptr(), items)
+                .and_then(|()| initialize_abc(type_object, &abstract_methods, abc));
 
             // Initialization successfully complete, can clear the thread list.
             // (No further calls to get_or_init() will try to init, on any thread.)
@@ -219,6 +281,30 @@ fn initialize_tp_dict(
     Ok(())
 }
 
+/// The `#[pyclass]` options handled by [`initialize_abc`].
+struct AbcOptions {
+    is_abstract: bool,
+    register: Option<&'static str>,
+}
+
+#[cfg(feature = "macros")]
+fn initialize_abc(
+    type_object: &Bound<'_, PyType>,
+    abstract_methods: &[&'static CStr],
+    abc: AbcOptions,
+) -> PyResult<()> {
+    super::abc::initialize_abc(type_object, abstract_methods, abc.is_abstract, abc.register)
+}
+
+#[cfg(not(feature = "macros"))]
+fn initialize_abc(
+    _type_object: &Bound<'_, PyType>,
+    _abstract_methods: &[&'static CStr],
+    _abc: AbcOptions,
+) -> PyResult<()> {
+    Ok(())
+}
+
 // This is necessary for making static `LazyTypeObject`s
 unsafe impl<T> Sync for LazyTypeObject<T> {}
 
@@ -0,0 +1,206 @@
+//! Runtime support for `#[pyclass(pickle)]`.
+//!
+//! `__reduce__` returns the reconstructor of the class, a private function added to the module of
+//! the class by `add_class`, called with the type of the instance and the state of the Rust value,
+//! including the state of its Rust base classes. It rebuilds the value without calling `#[new]`.
+//! `__getstate__` and `__setstate__` only handle the instance `__dict__`, set by Python subclasses
+//! or the `dict` option.
+
+use std::marker::PhantomData;
+
+use crate::exceptions::{PyAttributeError, PyKeyError, PyTypeError};
+use crate::impl_::pyclass::{PyClassBaseType, PyClassImpl};
+use crate::impl_::pyclass_init::PyNativeTypeInitializer;
+use crate::impl_::pymethods::{tp_new_impl, PyMethodDef};
+use crate::impl_::trampoline;
+use crate::types::{
+    PyAnyMethods, PyCFunction, PyDict, PyDictMethods, PyModule, PyString, PyTuple, PyType,
+    PyTypeMethods,
+};
+use crate::{
+    ffi, intern, Borrowed, Bound, FromPyObject, PyAny, PyClass, PyClassInitializer, PyResult,
+    PyTypeInfo, Python,
+};
+
+/// Converts the fields declared by a `#[pyclass(pickle)]` to and from their pickled state.
+pub trait PyClassPickle: PyClass {
+    /// Returns the state of the fields of `slf`, excluding the fields of its base classes.
+    fn fields_state<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>>;
+
+    /// Rebuilds the value from the state returned by `fields_state`.
+    fn from_fields_state(state: &Bound<'_, PyAny>) -> PyResult<Self>;
+}
+
+/// Pickled state of a class together with its base classes, implemented by their initializers.
+pub trait PickleInitializer: Sized {
+    fn state<'py>(obj: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>>;
+
+    fn from_state(state: &Bound<'_, PyAny>) -> PyResult<Self>;
+}
+
+impl<T: PyTypeInfo> PickleInitializer for PyNativeTypeInitializer<T> {
+    fn state<'py>(obj: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        Ok(obj.py().None().into_bound(obj.py()))
+    }
+
+    fn from_state(_state: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(PyNativeTypeInitializer(PhantomData))
+    }
+}
+
+impl<T> PickleInitializer for PyClassInitializer<T>
+where
+    T: PyClassPickle,
+    <T::BaseType as PyClassBaseType>::Initializer: PickleInitializer,
+{
+    fn state<'py>(obj: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        let base = <T::BaseType as PyClassBaseType>::Initializer::state(obj)?;
+        let fields = T::fields_state(obj.downcast::<T>()?)?;
+        Ok(PyTuple::new(obj.py(), [base, fields])?.into_any())
+    }
+
+    fn from_state(state: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let (base, fields): (Bound<'_, PyAny>, Bound<'_, PyAny>) = state.extract()?;
+        Ok(PyClassInitializer::new(
+            T::from_fields_state(&fields)?,
+            <T::BaseType as PyClassBaseType>::Initializer::from_state(&base)?,
+        ))
+    }
+}
+
+/// Implementation of `__reduce__`, returning the reconstructor of `T` with the type and the state
+/// of `slf`, and the state returned by `__getstate__`.
+#[allow(clippy::type_complexity)]
+pub fn pickle_reduce<'py, T>(
+    slf: &Bound<'py, T>,
+) -> PyResult<(
+    Bound<'py, PyAny>,
+    (Bound<'py, PyType>, Bound<'py, PyAny>),
+    Bound<'py, PyAny>,
+)>
+where
+    T: PyClassImpl + PyClass,
+    PyClassInitializer<T>: PickleInitializer,
+{
+    let py = slf.py();
+    let reconstructor = find_reconstructor::<T>(py)?;
+    let slf = slf.as_any();
+    let state = PyClassInitializer::<T>::state(slf)?;
+    let dict_state = slf.call_method0(intern!(py, "__getstate__"))?;
+    Ok((reconstructor, (slf.get_type(), state), dict_state))
+}
+
+/// Returns the reconstructor of `T` from the module of `T`, where pickle looks it up.
+///
+/// If it is not there, e.g. because the class was not added to a module, a reconstructor without
+/// module is returned instead, which `copy` can call but `pickle` reports as not found.
+fn find_reconstructor<T: PyClassImpl + PyClass>(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+    let def = T::pickle_reconstructor().expect("`#[pyclass(pickle)]` defines a reconstructor");
+    let name = def.ml_name.to_str().expect("reconstructor name is UTF-8");
+    let module = T::type_object(py).getattr(intern!(py, "__module__"))?;
+    let found = module
+        .downcast_into::<PyString>()
+        .ok()
+        .and_then(|module| py.import(module).ok())
+        .and_then(|module| module.getattr(name).ok());
+    match found {
+        Some(reconstructor) => Ok(reconstructor),
+        None => Ok(PyCFunction::internal_new(py, def, None)?.into_any()),
+    }
+}
+
+/// Adds the reconstructor of `T` to `module`, if `T` is a `#[pyclass(pickle)]`.
+pub(crate) fn add_pickle_reconstructor<T: PyClassImpl>(
+    module: &Bound<'_, PyModule>,
+) -> PyResult<()> {
+    use crate::types::PyModuleMethods;
+    match T::pickle_reconstructor() {
+        Some(def) => {
+            module.add_function(PyCFunction::internal_new(module.py(), def, Some(module))?)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Defines the reconstructor of `T`, named `name`.
+pub const fn pickle_reconstructor_def<T>(name: &'static std::ffi::CStr) -> PyMethodDef
+where
+    T: PyClass,
+    PyClassInitializer<T>: PickleInitializer,
+{
+    PyMethodDef::cfunction_with_keywords(name, pickle_reconstruct::<T>, ffi::c_str!(""))
+}
+
+/// Reconstructor of `T`, called by pickle with the arguments returned by `__reduce__`: the type
+/// to create, `T` or a subclass, and the state of the Rust value.
+unsafe extern "C" fn pickle_reconstruct<T>(
+    module: *mut ffi::PyObject,
+    args: *mut ffi::PyObject,
+    kwargs: *mut ffi::PyObject,
+) -> *mut ffi::PyObject
+where
+    T: PyClass,
+    PyClassInitializer<T>: PickleInitializer,
+{
+    unsafe fn reconstruct<T>(
+        py: Python<'_>,
+        _module: *mut ffi::PyObject,
+        args: *mut ffi::PyObject,
+        kwargs: *mut ffi::PyObject,
+    ) -> PyResult<*mut ffi::PyObject>
+    where
+        T: PyClass,
+        PyClassInitializer<T>: PickleInitializer,
+    {
+        let args = Borrowed::from_ptr(py, args).downcast_unchecked::<PyTuple>();
+        if !kwargs.is_null() && ffi::PyDict_Size(kwargs) != 0 {
+            return Err(PyTypeError::new_err(
+                "the reconstructor does not take keyword arguments",
+            ));
+        }
+        let (cls, state): (Bound<'_, PyType>, Bound<'_, PyAny>) = args.extract()?;
+        if !cls.is_subclass_of::<T>()? {
+            return Err(PyTypeError::new_err(format!(
+                "{} is not a subtype of {}",
+                cls.qualname()?,
+                T::NAME
+            )));
+        }
+        let initializer = PyClassInitializer::<T>::from_state(&state)?;
+        tp_new_impl(py, initializer, cls.as_type_ptr())
+    }
+
+    trampoline::cfunction_with_keywords(module, args, kwargs, reconstruct::<T>)
+}
+
+/// Implementation of `__getstate__`, returning the instance `__dict__` if it is not empty.
+pub fn pickle_getstate<'py>(obj: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let py = obj.py();
+    match obj.getattr(intern!(py, "__dict__")) {
+        Ok(dict) if dict.is_truthy()? => Ok(dict),
+        Ok(_) => Ok(py.None().into_bound(py)),
+        Err(err) if err.is_instance_of::<PyAttributeError>(py) => Ok(py.None().into_bound(py)),
+        Err(err) => Err(err),
+    }
+}
+
+/// Implementation of `__setstate__`, restoring the instance `__dict__`.
+pub fn pickle_setstate(obj: &Bound<'_, PyAny>, state: &Bound<'_, PyAny>) -> PyResult<()> {
+    if state.is_none() {
+        return Ok(());
+    }
+    let dict = obj.getattr(intern!(obj.py(), "__dict__"))?;
+    dict.downcast::<PyDict>()?
+        .update(state.downcast::<PyDict>()?.as_mapping())
+}
+
+/// Extracts the field `name` from the state built by `PyClassPickle::fields_state`.
+pub fn pickle_extract_field<'py, T: FromPyObject<'py>>(
+    state: &Bound<'py, PyDict>,
+    name: &str,
+) -> PyResult<T> {
+    match state.get_item(name)? {
+        Some(value) => value.extract(),
+        None => Err(PyKeyError::new_err(name.to_owned())),
+    }
+}
@@ -1,8 +1,9 @@
 //! Contains initialization utilities for `#[pyclass]`.
 use crate::ffi_ptr_ext::FfiPtrExt;
-use crate::internal::get_slot::TP_ALLOC;
-use crate::types::PyType;
-use crate::{ffi, Borrowed, PyErr, PyResult, Python};
+use crate::internal::get_slot::{TP_ALLOC, TP_BASE, TP_DEALLOC, TP_NEW};
+use crate::internal_tricks::dealloc_eq;
+use crate::types::{PyType, PyTypeMethods};
+use crate::{ffi, Borrowed, Bound, PyErr, PyResult, Python};
 use crate::{ffi::PyTypeObject, sealed::Sealed, type_object::PyTypeInfo};
 use std::marker::PhantomData;
 
@@ -32,58 +33,89 @@ impl<T: PyTypeInfo> PyObjectInit<T> for PyNativeTypeInitializer<T> {
         py: Python<'_>,
         subtype: *mut PyTypeObject,
     ) -> PyResult<*mut ffi::PyObject> {
-        unsafe fn inner(
-            py: Python<'_>,
-            type_object: *mut PyTypeObject,
-            subtype: *mut PyTypeObject,
-        ) -> PyResult<*mut ffi::PyObject> {
-            // HACK (due to FIXME below): PyBaseObject_Type's tp_new isn't happy with NULL arguments
-            let is_base_object = type_object == std::ptr::addr_of_mut!(ffi::PyBaseObject_Type);
-            let subtype_borrowed: Borrowed<'_, '_, PyType> = subtype
-                .cast::<ffi::PyObject>()
-                .assume_borrowed_unchecked(py)
-                .downcast_unchecked();
+        native_type_new(py, T::type_object_raw(py), subtype)
+    }
 
-            if is_base_object {
-                let alloc = subtype_borrowed
-                    .get_slot(TP_ALLOC)
-                    .unwrap_or(ffi::PyType_GenericAlloc);
+    #[inline]
+    fn can_be_subclassed(&self) -> bool {
+        true
+    }
+}
 
-                let obj = alloc(subtype, 0);
-                return if obj.is_null() {
-                    Err(PyErr::fetch(py))
-                } else {
-                    Ok(obj)
-                };
-            }
+/// Creates an instance of `subtype`, a subclass of the `#[pyclass]` `type_object` which extends a
+/// Python class, using the `tp_new` of the closest native ancestor.
+///
+/// The Python classes in between are checked not to define `__new__` when `type_object` is
+/// created.
+///
+/// # Safety
+/// `subtype` must be a subtype of `type_object`.
+pub(crate) unsafe fn python_base_new(
+    type_object: &Bound<'_, PyType>,
+    subtype: *mut PyTypeObject,
+) -> PyResult<*mut ffi::PyObject> {
+    let py = type_object.py();
+    let base = PyType::from_borrowed_type_ptr(py, type_object.get_slot(TP_BASE));
+    let native = native_base_of_python_class(&base);
+    native_type_new(py, native.as_type_ptr(), subtype)
+}
+
+/// Gets the closest ancestor of the Python class `cls` which isn't defined in Python.
+pub(crate) fn native_base_of_python_class<'py>(cls: &Bound<'py, PyType>) -> Bound<'py, PyType> {
+    // Classes defined in Python all share the same `tp_dealloc`.
+    let python_dealloc = cls
+        .get_slot(TP_DEALLOC)
+        .expect("Python classes should have tp_dealloc");
+    let mut native = cls.clone();
+    while dealloc_eq(native.get_slot(TP_DEALLOC), python_dealloc) {
+        // Safety: the base of a Python class is a valid type object
+        native = unsafe { PyType::from_borrowed_type_ptr(cls.py(), native.get_slot(TP_BASE)) };
+    }
+    native
+}
+
+/// Creates an instance of `subtype` using the `tp_new` of its native base `type_object`.
+///
+/// # Safety
+/// `subtype` must be a subtype of `type_object`.
+unsafe fn native_type_new(
+    py: Python<'_>,
+    type_object: *mut PyTypeObject,
+    subtype: *mut PyTypeObject,
+) -> PyResult<*mut ffi::PyObject> {
+    // HACK (due to FIXME below): PyBaseObject_Type's tp_new isn't happy with NULL arguments
+    let is_base_object = type_object == std::ptr::addr_of_mut!(ffi::PyBaseObject_Type);
+    let subtype_borrowed: Borrowed<'_, '_, PyType> = subtype
+        .cast::<ffi::PyObject>()
+        .assume_borrowed_unchecked(py)
+        .downcast_unchecked();
 
-            #[cfg(Py_LIMITED_API)]
-            unreachable!("subclassing native types is not possible with the `abi3` feature");
+    if is_base_object {
+        let alloc = subtype_borrowed
+            .get_slot(TP_ALLOC)
+            .unwrap_or(ffi::PyType_GenericAlloc);
 
-            #[cfg(not(Py_LIMITED_API))]
-            {
-                match (*type_object).tp_new {
-                    // FIXME: Call __new__ with actual arguments
-                    Some(newfunc) => {
-                        let obj = newfunc(subtype, std::ptr::null_mut(), std::ptr::null_mut());
-                        if obj.is_null() {
-                            Err(PyErr::fetch(py))
-                        } else {
-                            Ok(obj)
-                        }
-                    }
-                    None => Err(crate::exceptions::PyTypeError::new_err(
-                        "base type without tp_new",
-                    )),
-                }
-            }
-        }
-        let type_object = T::type_object_raw(py);
-        inner(py, type_object, subtype)
+        let obj = alloc(subtype, 0);
+        return if obj.is_null() {
+            Err(PyErr::fetch(py))
+        } else {
+            Ok(obj)
+        };
     }
 
-    #[inline]
-    fn can_be_subclassed(&self) -> bool {
-        true
+    let type_object = PyType::from_borrowed_type_ptr(py, type_object);
+    match type_object.get_slot(TP_NEW) {
+        // FIXME: Call __new__ with actual arguments
+        Some(newfunc) => {
+            let obj = newfunc(subtype, std::ptr::null_mut(), std::ptr::null_mut());
+            if obj.is_null() {
+                Err(PyErr::fetch(py))
+            } else {
+                Ok(obj)
+            }
+        }
+        None => Err(crate::exceptions::PyTypeError::new_err(
+            "base type without tp_new",
+        )),
     }
 }
@@ -1,4 +1,5 @@
 use crate::exceptions::PyStopAsyncIteration;
+use crate::exceptions::PyTypeError;
 use crate::gil::LockGIL;
 use crate::impl_::callback::IntoPyCallbackOutput;
 use crate::impl_::panic::PanicTrap;
@@ -8,7 +9,7 @@ use crate::pycell::impl_::PyClassBorrowChecker as _;
 use crate::pycell::{PyBorrowError, PyBorrowMutError};
 use crate::pyclass::boolean_struct::False;
 use crate::types::any::PyAnyMethods;
-use crate::types::PyType;
+use crate::types::{PyType, PyTypeMethods};
 use crate::{
     ffi, Bound, DowncastError, Py, PyAny, PyClass, PyClassInitializer, PyErr, PyObject, PyRef,
     PyRefMut, PyResult, PyTraverseError, PyTypeCheck, PyVisit, Python,
@@ -20,6 +21,8 @@ use std::os::raw::{c_int, c_void};
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::ptr::null_mut;
 
+#[cfg(feature = "experimental-inspect")]
+use super::introspection::{FunctionIntrospection, TypeIntrospection};
 use super::trampoline;
 use crate::internal_tricks::{clear_eq, traverse_eq};
 
@@ -66,6 +69,8 @@ pub enum PyMethodDefType {
     Static(PyMethodDef),
     /// Represents normal method
     Method(PyMethodDef),
+    /// Represents abstract method, used by `#[pyo3(abstractmethod)]`
+    AbstractMethod(PyMethodDef),
     /// Represents class attribute, used by `#[attribute]`
     ClassAttribute(PyClassAttributeDef),
     /// Represents getter descriptor, used by `#[getter]`
@@ -95,6 +100,8 @@ pub struct PyMethodDef {
     pub(crate) ml_meth: PyMethodType,
     pub(crate) ml_flags: c_int,
     pub(crate) ml_doc: &'static CStr,
+    #[cfg(feature = "experimental-inspect")]
+    pub(crate) introspection: Option<FunctionIntrospection>,
 }
 
 #[derive(Copy, Clone)]
@@ -108,6 +115,8 @@ pub struct PyGetterDef {
     pub(crate) name: &'static CStr,
     pub(crate) meth: Getter,
     pub(crate) doc: &'static CStr,
+    #[cfg(feature = "experimental-inspect")]
+    pub(crate) introspection: Option<TypeIntrospection>,
 }
 
 #[derive(Clone)]
@@ -135,6 +144,8 @@ impl PyMethodDef {
             ml_meth: PyMethodType::PyCFunction(cfunction),
             ml_flags: ffi::METH_NOARGS,
             ml_doc,
+            #[cfg(feature = "experimental-inspect")]
+            introspection: None,
         }
     }
 
@@ -149,6 +160,8 @@ impl PyMethodDef {
             ml_meth: PyMethodType::PyCFunctionWithKeywords(cfunction),
             ml_flags: ffi::METH_VARARGS | ffi::METH_KEYWORDS,
             ml_doc,
+            #[cfg(feature = "experimental-inspect")]
+            introspection: None,
         }
     }
 
@@ -164,6 +177,8 @@ impl PyMethodDef {
             ml_meth: PyMethodType::PyCFunctionFastWithKeywords(cfunction),
             ml_flags: ffi::METH_FASTCALL | ffi::METH_KEYWORDS,
             ml_doc,
+            #[cfg(feature = "experimental-inspect")]
+            introspection: None,
         }
     }
 
@@ -172,6 +187,13 @@ impl PyMethodDef {
         self
     }
 
+    /// Attaches the description of the function, used by `experimental-inspect`.
+    #[cfg(feature = "experimental-inspect")]
+    pub const fn with_introspection(mut self, introspection: FunctionIntrospection) -> Self {
+        self.introspection = Some(introspection);
+        self
+    }
+
     /// Convert `PyMethodDef` to Python method definition struct `ffi::PyMethodDef`
     pub(crate) fn as_method_def(&self) -> ffi::PyMethodDef {
         let meth = match self.ml_meth {
@@ -224,8 +246,17 @@ impl PyGetterDef {
             name,
             meth: getter,
             doc,
+            #[cfg(feature = "experimental-inspect")]
+            introspection: None,
         }
     }
+
+    /// Attaches the type of the property, used by `experimental-inspect`.
+    #[cfg(feature = "experimental-inspect")]
+    pub const fn with_introspection(mut self, introspection: TypeIntrospection) -> Self {
+        self.introspection = Some(introspection);
+        self
+    }
 }
 
 impl PySetterDef {
@@ -320,7 +351,7 @@ where
         // `.try_borrow()` above created a borrow, we need to release it when we're done
         // traversing the object. This allows us to read `instance` safely.
         let _guard = TraverseGuard(class_object);
-        let instance = &*class_object.contents.value.get();
+        let instance = &*class_object.get_ptr();
 
         let visit = PyVisit { visit, arg, _guard: PhantomData };
 
@@ -399,14 +430,17 @@ pub unsafe fn _call_clear(
     impl_: for<'py> unsafe fn(Python<'py>, *mut ffi::PyObject) -> PyResult<()>,
     current_clear: ffi::inquiry,
 ) -> c_int {
-    trampoline::trampoline(move |py| {
-        let super_retval = call_super_clear(py, slf, current_clear);
-        if super_retval != 0 {
-            return Err(PyErr::fetch(py));
-        }
-        impl_(py, slf)?;
-        Ok(0)
-    })
+    trampoline::trampoline(
+        move |py| {
+            let super_retval = call_super_clear(py, slf, current_clear);
+            if super_retval != 0 {
+                return Err(PyErr::fetch(py));
+            }
+            impl_(py, slf)?;
+            Ok(0)
+        },
+        slf,
+    )
 }
 
 /// Call super-type traverse method, if necessary.
@@ -697,6 +731,50 @@ impl<'py, T> std::ops::Deref for BoundRef<'_, 'py, T> {
     }
 }
 
+/// Raises the error of `object.__new__` when `subtype` still has abstract methods.
+///
+/// # Safety
+/// `subtype` must be a valid pointer to a type object.
+#[inline]
+pub unsafe fn ensure_not_abstract(py: Python<'_>, subtype: *mut ffi::PyTypeObject) -> PyResult<()> {
+    if ffi::PyType_GetFlags(subtype) & ffi::Py_TPFLAGS_IS_ABSTRACT == 0 {
+        return Ok(());
+    }
+    Err(abstract_class_error(&PyType::from_borrowed_type_ptr(
+        py, subtype,
+    )))
+}
+
+#[cold]
+fn abstract_class_error(subtype: &Bound<'_, PyType>) -> PyErr {
+    let py = subtype.py();
+    let mut names = match subtype
+        .getattr(crate::intern!(py, "__abstractmethods__"))
+        .and_then(|names| {
+            names
+                .try_iter()?
+                .map(|name| name?.extract::<String>())
+                .collect::<PyResult<Vec<_>>>()
+        }) {
+        Ok(names) => names,
+        Err(err) => return err,
+    };
+    names.sort();
+    let name = subtype
+        .name()
+        .map_or_else(|_| "<unknown>".into(), |name| name.to_string());
+    PyTypeError::new_err(format!(
+        "Can't instantiate abstract class {} without an implementation for abstract method{} {}",
+        name,
+        if names.len() == 1 { "" } else { "s" },
+        names
+            .iter()
+            .map(|name| format!("'{}'", name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+}
+
 pub unsafe fn tp_new_impl<T: PyClass>(
     py: Python<'_>,
     initializer: PyClassInitializer<T>,
@@ -707,6 +785,62 @@ pub unsafe fn tp_new_impl<T: PyClass>(
         .map(Bound::into_ptr)
 }
 
+/// Runs `__init__` on `obj` created by the vectorcall implementation of `#[new]`, as
+/// `type.__call__` does after `tp_new`.
+///
+/// This is skipped when `__init__` is inherited from `object`, which ignores the arguments, so that
+/// they are only packed into a tuple and dict for types inheriting another `__init__`.
+#[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))]
+pub(crate) unsafe fn vectorcall_init(
+    py: Python<'_>,
+    subtype: *mut ffi::PyTypeObject,
+    obj: &Bound<'_, PyAny>,
+    args: *const *mut ffi::PyObject,
+    nargs: ffi::Py_ssize_t,
+    kwnames: *mut ffi::PyObject,
+) -> PyResult<()> {
+    use crate::types::{PyDict, PyDictMethods, PyTuple, PyTupleMethods};
+    use crate::Borrowed;
+
+    let init = match (*subtype).tp_init {
+        Some(init) if Some(init as usize) != ffi::PyBaseObject_Type.tp_init.map(|f| f as usize) => {
+            init
+        }
+        _ => return Ok(()),
+    };
+    if ffi::PyObject_TypeCheck(obj.as_ptr(), subtype) == 0 {
+        return Ok(());
+    }
+
+    let kwnames = Borrowed::from_ptr_or_opt(py, kwnames)
+        .map(|kwnames| kwnames.downcast_unchecked::<PyTuple>());
+    let nargs = nargs as usize;
+    let nkwargs = kwnames.map_or(0, |kwnames| kwnames.len());
+    let args = if args.is_null() {
+        &[]
+    } else {
+        std::slice::from_raw_parts(args, nargs + nkwargs)
+    };
+    let (args, kwargs) = args.split_at(nargs);
+    let args = PyTuple::new(py, args.iter().map(|arg| Borrowed::from_ptr(py, *arg)))?;
+    let kwargs = match kwnames {
+        Some(kwnames) => {
+            let dict = PyDict::new(py);
+            for (name, value) in kwnames.iter_borrowed().zip(kwargs) {
+                dict.set_item(name, Borrowed::from_ptr(py, *value))?;
+            }
+            Some(dict)
+        }
+        None => None,
+    };
+    let kwargs = kwargs.as_ref().map_or(null_mut(), |kwargs| kwargs.as_ptr());
+    if init(obj.as_ptr(), args.as_ptr(), kwargs) < 0 {
+        Err(PyErr::fetch(py))
+    } else {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
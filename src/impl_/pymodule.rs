@@ -1,6 +1,14 @@
 //! Implementation details of `#[pymodule]` which need to be accessible from proc-macro generated code.
 
-use std::{cell::UnsafeCell, ffi::CStr, marker::PhantomData};
+use std::{
+    any::{Any, TypeId},
+    cell::UnsafeCell,
+    ffi::CStr,
+    marker::PhantomData,
+    os::raw::{c_int, c_void},
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{Mutex, Once},
+};
 
 #[cfg(all(
     not(any(PyPy, GraalPy)),
@@ -18,23 +26,53 @@ use portable_atomic::AtomicI64;
 use std::sync::atomic::AtomicI64;
 use std::sync::atomic::{AtomicBool, Ordering};
 
+#[cfg(any(PyPy, GraalPy, not(Py_3_9), all(windows, Py_LIMITED_API, not(Py_3_10))))]
+use crate::sync::GILOnceCell;
+#[cfg(all(
+    any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+    not(any(PyPy, GraalPy))
+))]
+use crate::types::{PyType, PyTypeMethods};
 #[cfg(not(any(PyPy, GraalPy)))]
-use crate::exceptions::PyImportError;
-#[cfg(all(not(Py_LIMITED_API), Py_GIL_DISABLED))]
-use crate::PyErr;
+use crate::{exceptions::PyImportError, ffi_ptr_ext::FfiPtrExt};
 use crate::{
+    exceptions::{PyRuntimeError, PyTypeError},
     ffi,
-    impl_::pymethods::PyMethodDef,
-    sync::GILOnceCell,
-    types::{PyCFunction, PyModule, PyModuleMethods},
-    Bound, Py, PyClass, PyResult, PyTypeInfo, Python,
+    gil::LockGIL,
+    impl_::{panic::PanicTrap, pymethods::PyMethodDef},
+    panic::{install_backtrace_hook, PanicPolicy},
+    pyclass::{PyTraverseError, PyVisit},
+    types::{PyAnyMethods, PyCFunction, PyModule, PyModuleMethods, PyModuleState},
+    Bound, Py, PyAny, PyClass, PyErr, PyResult, PyTypeInfo, Python,
 };
 
 /// `Sync` wrapper of `ffi::PyModuleDef`.
+///
+/// On CPython, modules use multi-phase initialization ([PEP 489]): `PyInit_<name>` returns the
+/// module definition and the module contents are created by its `Py_mod_exec` slot, so a new
+/// module object is created each time the module is imported.
+///
+/// [PEP 489]: https://peps.python.org/pep-0489/
+// `repr(C)` so that the `ModuleDef` can be recovered from the `ffi::PyModuleDef` pointer stored
+// in the module object, see `module_exec`.
+#[repr(C)]
 pub struct ModuleDef {
     // wrapped in UnsafeCell so that Rust compiler treats this as interior mutability
     ffi_def: UnsafeCell<ffi::PyModuleDef>,
     initializer: ModuleInitializer,
+    /// Constructor of the module state, if the module has one.
+    state: Option<fn() -> BoxedModuleState>,
+    /// Type of the module state, if the module has one.
+    state_type: Option<fn() -> TypeId>,
+    /// Whether the module can be loaded in several interpreters, see [`crate::interpreter`].
+    multiple_interpreters: bool,
+    /// Policy applied to the panics of the code of the module, see [`crate::panic`].
+    panic_policy: Option<PanicPolicy>,
+    /// Slots of the multi-phase initialization, filled on first use.
+    #[cfg(not(any(PyPy, GraalPy)))]
+    slots: UnsafeCell<[ffi::PyModuleDef_Slot; 4]>,
+    /// Guards the completion of `ffi_def` on first use.
+    def_init: Once,
     /// Interpreter ID where module was initialized (not applicable on PyPy).
     #[cfg(all(
         not(any(PyPy, GraalPy)),
@@ -42,7 +80,16 @@ pub struct ModuleDef {
         not(all(windows, Py_LIMITED_API, not(Py_3_10)))
     ))]
     interpreter: AtomicI64,
-    /// Initialized module object, cached to avoid reinitialization.
+    /// Whether the module was initialized, used instead of the interpreter ID when it is not
+    /// available.
+    #[cfg(all(
+        not(any(PyPy, GraalPy)),
+        not(all(Py_3_9, not(all(windows, Py_LIMITED_API, not(Py_3_10)))))
+    ))]
+    initialized: AtomicBool,
+    /// Initialized module object, cached as single-phase initialization can only happen once,
+    /// and as modules can only be executed once when the interpreter ID is not available.
+    #[cfg(any(PyPy, GraalPy, not(Py_3_9), all(windows, Py_LIMITED_API, not(Py_3_10))))]
     module: GILOnceCell<Py<PyModule>>,
     /// Whether or not the module supports running without the GIL
     gil_used: AtomicBool,
@@ -73,6 +120,12 @@ impl ModuleDef {
             m_free: None,
         };
 
+        #[cfg(not(any(PyPy, GraalPy)))]
+        const END_SLOT: ffi::PyModuleDef_Slot = ffi::PyModuleDef_Slot {
+            slot: 0,
+            value: std::ptr::null_mut(),
+        };
+
         let ffi_def = UnsafeCell::new(ffi::PyModuleDef {
             m_name: name.as_ptr(),
             m_doc: doc.as_ptr(),
@@ -82,6 +135,13 @@ impl ModuleDef {
         ModuleDef {
             ffi_def,
             initializer,
+            state: None,
+            state_type: None,
+            multiple_interpreters: false,
+            panic_policy: None,
+            #[cfg(not(any(PyPy, GraalPy)))]
+            slots: UnsafeCell::new([END_SLOT; 4]),
+            def_init: Once::new(),
             // -1 is never expected to be a valid interpreter ID
             #[cfg(all(
                 not(any(PyPy, GraalPy)),
@@ -89,75 +149,552 @@ impl ModuleDef {
                 not(all(windows, Py_LIMITED_API, not(Py_3_10)))
             ))]
             interpreter: AtomicI64::new(-1),
+            #[cfg(all(
+                not(any(PyPy, GraalPy)),
+                not(all(Py_3_9, not(all(windows, Py_LIMITED_API, not(Py_3_10)))))
+            ))]
+            initialized: AtomicBool::new(false),
+            #[cfg(any(PyPy, GraalPy, not(Py_3_9), all(windows, Py_LIMITED_API, not(Py_3_10))))]
             module: GILOnceCell::new(),
             gil_used: AtomicBool::new(true),
         }
     }
+
+    /// Gives each module created from this definition a state of type `T`, see
+    /// [`PyModuleState`].
+    pub const fn with_state<T: PyModuleState>(mut self) -> Self {
+        self.state = Some(new_module_state::<T>);
+        self.state_type = Some(TypeId::of::<T>);
+        self
+    }
+
+    /// Allows the module to be loaded in subinterpreters, see [`crate::interpreter`].
+    pub const fn with_multiple_interpreters(mut self) -> Self {
+        self.multiple_interpreters = true;
+        self
+    }
+
+    /// Sets the policy applied to the panics of the code of the module, see [`PanicPolicy`].
+    pub const fn with_panic_policy(mut self, policy: PanicPolicy) -> Self {
+        self.panic_policy = Some(policy);
+        self
+    }
+
+    /// Returns the value of `PyInit_<name>`: the module definition on CPython, and the module
+    /// object on PyPy and GraalPy, which only support single-phase initialization.
+    ///
+    /// # Safety
+    ///
+    /// Must be called with the GIL held.
+    pub unsafe fn module_init(&'static self, gil_used: bool) -> *mut ffi::PyObject {
+        #[cfg(not(any(PyPy, GraalPy)))]
+        {
+            ffi::PyModuleDef_Init(self.prepare_def(gil_used))
+        }
+        #[cfg(any(PyPy, GraalPy))]
+        {
+            crate::impl_::trampoline::module_init(|py| self.make_module(py, gil_used))
+        }
+    }
+
     /// Builds a module using user given initializer. Used for [`#[pymodule]`][crate::pymodule].
-    #[cfg_attr(any(Py_LIMITED_API, not(Py_GIL_DISABLED)), allow(unused_variables))]
+    ///
+    /// On CPython 3.9 and later a new module object is created by each call. Elsewhere the
+    /// module can only be initialized once, so the same module object is returned by each call.
     pub fn make_module(&'static self, py: Python<'_>, gil_used: bool) -> PyResult<Py<PyModule>> {
-        // Check the interpreter ID has not changed, since we currently have no way to guarantee
-        // that static data is not reused across interpreters.
-        //
-        // PyPy does not have subinterpreters, so no need to check interpreter ID.
-        #[cfg(not(any(PyPy, GraalPy)))]
+        #[cfg(all(
+            not(any(PyPy, GraalPy)),
+            Py_3_9,
+            not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+        ))]
         {
-            // PyInterpreterState_Get is only available on 3.9 and later, but is missing
-            // from python3.dll for Windows stable API on 3.9
-            #[cfg(all(Py_3_9, not(all(windows, Py_LIMITED_API, not(Py_3_10)))))]
-            {
-                let current_interpreter =
-                    unsafe { ffi::PyInterpreterState_GetID(ffi::PyInterpreterState_Get()) };
-                crate::err::error_on_minusone(py, current_interpreter)?;
-                if let Err(initialized_interpreter) = self.interpreter.compare_exchange(
-                    -1,
-                    current_interpreter,
-                    Ordering::SeqCst,
-                    Ordering::SeqCst,
-                ) {
-                    if initialized_interpreter != current_interpreter {
-                        return Err(PyImportError::new_err(
-                            "PyO3 modules do not yet support subinterpreters, see https://github.com/PyO3/pyo3/issues/576",
-                        ));
-                    }
-                }
+            self.create_module(py, gil_used)
+        }
+        #[cfg(all(
+            not(any(PyPy, GraalPy)),
+            not(all(Py_3_9, not(all(windows, Py_LIMITED_API, not(Py_3_10)))))
+        ))]
+        {
+            // the module may also have been created by the import system, see `exec`
+            self.module
+                .get_or_try_init(py, || self.create_module(py, gil_used))
+                .map(|py_module| py_module.clone_ref(py))
+        }
+        #[cfg(any(PyPy, GraalPy))]
+        {
+            self.module
+                .get_or_try_init(py, || {
+                    let module = unsafe {
+                        Py::<PyModule>::from_owned_ptr_or_err(
+                            py,
+                            ffi::PyModule_Create(self.prepare_def(gil_used)),
+                        )?
+                    };
+                    self.init_state(module.bind(py))?;
+                    self.initializer.0(module.bind(py))?;
+                    Ok(module)
+                })
+                .map(|py_module| py_module.clone_ref(py))
+        }
+    }
+
+    /// Creates and executes a new module object from the multi-phase definition.
+    #[cfg(not(any(PyPy, GraalPy)))]
+    fn create_module(&'static self, py: Python<'_>, gil_used: bool) -> PyResult<Py<PyModule>> {
+        let def = self.prepare_def(gil_used);
+        // SAFETY: `m_name` is the static string given to `ModuleDef::new`
+        let name = unsafe { CStr::from_ptr((*def).m_name) }.to_str()?;
+        let spec = py
+            .import("importlib.machinery")?
+            .getattr("ModuleSpec")?
+            .call1((name, py.None()))?;
+        unsafe {
+            let module = Py::<PyModule>::from_owned_ptr_or_err(
+                py,
+                ffi::PyModule_FromDefAndSpec(def, spec.as_ptr()),
+            )?;
+            crate::err::error_on_minusone(py, ffi::PyModule_ExecDef(module.as_ptr(), def))?;
+            Ok(module)
+        }
+    }
+
+    /// Returns the `ffi::PyModuleDef`, after filling its module state hooks and multi-phase
+    /// initialization slots.
+    fn prepare_def(&'static self, gil_used: bool) -> *mut ffi::PyModuleDef {
+        self.def_init.call_once(|| {
+            self.gil_used.store(gil_used, Ordering::Relaxed);
+            install_backtrace_hook();
+            if self.state.is_some() {
+                // SAFETY: the definition is only written here, before it is first used.
+                let def = unsafe { &mut *self.ffi_def.get() };
+                def.m_size = std::mem::size_of::<*mut BoxedModuleState>() as ffi::Py_ssize_t;
+                def.m_traverse = Some(module_state_traverse);
+                def.m_clear = Some(module_state_clear);
+                def.m_free = Some(module_state_free);
+                STATE_DEFS.lock().unwrap().push(self);
             }
-            #[cfg(not(all(Py_3_9, not(all(windows, Py_LIMITED_API, not(Py_3_10))))))]
-            {
-                // CPython before 3.9 does not have APIs to check the interpreter ID, so best that can be
-                // done to guard against subinterpreters is fail if the module is initialized twice
-                if self.module.get(py).is_some() {
+            if self.panic_policy.is_some() {
+                PANIC_POLICY_DEFS.lock().unwrap().push(self);
+                HAS_PANIC_POLICIES.store(true, Ordering::Relaxed);
+            }
+            #[cfg(not(any(PyPy, GraalPy)))]
+            self.fill_slots(gil_used);
+        });
+        self.ffi_def.get()
+    }
+
+    /// Fills the multi-phase initialization slots of the definition.
+    #[cfg(not(any(PyPy, GraalPy)))]
+    #[cfg_attr(not(Py_3_13), allow(unused_variables))]
+    fn fill_slots(&self, gil_used: bool) {
+        // SAFETY: the slots are only written here, before the definition is first used.
+        // Unused slots are left zeroed, which terminates the list.
+        let slots = unsafe { &mut *self.slots.get() };
+        slots[0] = ffi::PyModuleDef_Slot {
+            slot: ffi::Py_mod_exec,
+            value: module_exec as *mut c_void,
+        };
+        #[cfg(Py_3_12)]
+        {
+            slots[1] = ffi::PyModuleDef_Slot {
+                slot: ffi::Py_mod_multiple_interpreters,
+                // PyO3 relies on the GIL being shared by all interpreters
+                value: if self.multiple_interpreters {
+                    ffi::Py_MOD_MULTIPLE_INTERPRETERS_SUPPORTED
+                } else {
+                    ffi::Py_MOD_MULTIPLE_INTERPRETERS_NOT_SUPPORTED
+                },
+            };
+        }
+        #[cfg(Py_3_13)]
+        {
+            slots[2] = ffi::PyModuleDef_Slot {
+                slot: ffi::Py_mod_gil,
+                value: if gil_used {
+                    ffi::Py_MOD_GIL_USED
+                } else {
+                    ffi::Py_MOD_GIL_NOT_USED
+                },
+            };
+        }
+        unsafe { (*self.ffi_def.get()).m_slots = slots.as_mut_ptr() };
+    }
+
+    /// Fills `module`, called by the `Py_mod_exec` slot.
+    #[cfg(not(any(PyPy, GraalPy)))]
+    fn exec(&self, module: &Bound<'_, PyModule>) -> PyResult<()> {
+        self.check_interpreter(module.py())?;
+        self.init_state(module)?;
+        self.initializer.0(module)?;
+        // This module is the only one which can be executed, so `make_module` returns it too.
+        #[cfg(not(all(Py_3_9, not(all(windows, Py_LIMITED_API, not(Py_3_10))))))]
+        let _ = self.module.set(module.py(), module.clone().unbind());
+        Ok(())
+    }
+
+    /// Creates the state of `module`, if this definition has one.
+    fn init_state(&self, module: &Bound<'_, PyModule>) -> PyResult<()> {
+        if let Some(new_state) = self.state {
+            let slot = state_slot(module.as_ptr());
+            if slot.is_null() {
+                return Err(PyErr::fetch(module.py()));
+            }
+            let state = Box::into_raw(Box::new(new_state()));
+            // SAFETY: `slot` points to the module state memory, sized for a pointer by `prepare_def`
+            let old_state = unsafe { std::mem::replace(&mut *slot, state) };
+            if !old_state.is_null() {
+                drop(unsafe { Box::from_raw(old_state) });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the interpreter ID has not changed, unless the module supports multiple
+    /// interpreters, since its static data may otherwise be reused across interpreters.
+    ///
+    /// PyPy does not have subinterpreters, so no need to check interpreter ID.
+    #[cfg(not(any(PyPy, GraalPy)))]
+    fn check_interpreter(&self, py: Python<'_>) -> PyResult<()> {
+        // PyInterpreterState_Get is only available on 3.9 and later, but is missing
+        // from python3.dll for Windows stable API on 3.9
+        #[cfg(all(Py_3_9, not(all(windows, Py_LIMITED_API, not(Py_3_10)))))]
+        {
+            let current_interpreter = crate::interpreter::register_interpreter(py);
+            crate::err::error_on_minusone(py, current_interpreter)?;
+            if self.multiple_interpreters {
+                return Ok(());
+            }
+            if let Err(initialized_interpreter) = self.interpreter.compare_exchange(
+                -1,
+                current_interpreter,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                if initialized_interpreter != current_interpreter {
                     return Err(PyImportError::new_err(
-                        "PyO3 modules compiled for CPython 3.8 or older may only be initialized once per interpreter process"
+                        "this PyO3 module does not support loading in subinterpreters",
                     ));
                 }
             }
         }
-        self.module
-            .get_or_try_init(py, || {
-                let module = unsafe {
-                    Py::<PyModule>::from_owned_ptr_or_err(
-                        py,
-                        ffi::PyModule_Create(self.ffi_def.get()),
-                    )?
-                };
-                #[cfg(all(not(Py_LIMITED_API), Py_GIL_DISABLED))]
-                {
-                    let gil_used_ptr = {
-                        if gil_used {
-                            ffi::Py_MOD_GIL_USED
-                        } else {
-                            ffi::Py_MOD_GIL_NOT_USED
-                        }
-                    };
-                    if unsafe { ffi::PyUnstable_Module_SetGIL(module.as_ptr(), gil_used_ptr) } < 0 {
-                        return Err(PyErr::fetch(py));
-                    }
-                }
-                self.initializer.0(module.bind(py))?;
-                Ok(module)
-            })
-            .map(|py_module| py_module.clone_ref(py))
+        #[cfg(not(all(Py_3_9, not(all(windows, Py_LIMITED_API, not(Py_3_10))))))]
+        {
+            // CPython before 3.9 does not have APIs to check the interpreter ID, so best that can be
+            // done to guard against subinterpreters is fail if the module is initialized twice
+            let _ = py;
+            if self.initialized.swap(true, Ordering::SeqCst) {
+                return Err(PyImportError::new_err(
+                    "PyO3 modules compiled for CPython 3.8 or older may only be initialized once per interpreter process"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The `Py_mod_exec` slot of all modules defined by PyO3.
+#[cfg(not(any(PyPy, GraalPy)))]
+unsafe extern "C" fn module_exec(module: *mut ffi::PyObject) -> c_int {
+    unsafe fn exec(py: Python<'_>, module: *mut ffi::PyObject) -> PyResult<()> {
+        let def = ffi::PyModule_GetDef(module);
+        if def.is_null() {
+            return Err(crate::PyErr::fetch(py));
+        }
+        // SAFETY: this slot is only used by `ModuleDef`, whose first field is the `PyModuleDef`
+        let def = &*def.cast::<ModuleDef>();
+        def.exec(&module.assume_borrowed(py).downcast_unchecked::<PyModule>())
+    }
+    crate::impl_::trampoline::module_exec(module, exec)
+}
+
+/// Type-erased [`PyModuleState`], stored in the module state memory behind a thin pointer.
+trait ErasedModuleState: Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+    fn traverse(&self, visit: PyVisit<'_>) -> Result<(), PyTraverseError>;
+}
+
+impl<T: PyModuleState> ErasedModuleState for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn traverse(&self, visit: PyVisit<'_>) -> Result<(), PyTraverseError> {
+        self.__traverse__(visit)
+    }
+}
+
+type BoxedModuleState = Box<dyn ErasedModuleState>;
+
+fn new_module_state<T: PyModuleState>() -> BoxedModuleState {
+    Box::new(T::default())
+}
+
+/// Returns the pointer to the state of `module`, or null if the module has no state memory.
+fn state_slot(module: *mut ffi::PyObject) -> *mut *mut BoxedModuleState {
+    unsafe { ffi::PyModule_GetState(module) }.cast()
+}
+
+/// Drops the state of `module`, if any.
+unsafe fn clear_state(module: *mut ffi::PyObject) {
+    let slot = state_slot(module);
+    if !slot.is_null() {
+        let state = std::mem::replace(&mut *slot, std::ptr::null_mut());
+        if !state.is_null() {
+            drop(Box::from_raw(state));
+        }
+    }
+}
+
+/// The `m_traverse` hook of modules with a state.
+unsafe extern "C" fn module_state_traverse(
+    module: *mut ffi::PyObject,
+    visit: ffi::visitproc,
+    arg: *mut c_void,
+) -> c_int {
+    // Same restrictions as for `__traverse__` of classes, see `_call_traverse`.
+    let trap = PanicTrap::new("uncaught panic inside module state __traverse__ handler");
+    let lock = LockGIL::during_traverse();
+
+    let slot = state_slot(module);
+    let retval = if slot.is_null() || (*slot).is_null() {
+        0
+    } else {
+        let state = &**slot;
+        let visit = PyVisit {
+            visit,
+            arg,
+            _guard: PhantomData,
+        };
+        match catch_unwind(AssertUnwindSafe(move || state.traverse(visit))) {
+            Ok(Ok(())) => 0,
+            Ok(Err(traverse_error)) => traverse_error.into_inner(),
+            Err(_err) => -1,
+        }
+    };
+
+    // Drop lock before trap just in case dropping lock panics
+    drop(lock);
+    trap.disarm();
+    retval
+}
+
+/// The `m_clear` hook of modules with a state.
+unsafe extern "C" fn module_state_clear(module: *mut ffi::PyObject) -> c_int {
+    crate::impl_::trampoline::trampoline(
+        |_py| {
+            clear_state(module);
+            Ok(0)
+        },
+        module,
+    )
+}
+
+/// The `m_free` hook of modules with a state.
+unsafe extern "C" fn module_state_free(module: *mut c_void) {
+    // The module is being deallocated, so it is not passed as context of unraisable errors.
+    crate::impl_::trampoline::trampoline_unraisable(
+        |_py| {
+            clear_state(module.cast());
+            Ok(())
+        },
+        std::ptr::null_mut(),
+    )
+}
+
+/// The definitions of the modules with a state which have been initialized, used to find the
+/// module of a class by the type of its state.
+static STATE_DEFS: Mutex<Vec<&'static ModuleDef>> = Mutex::new(Vec::new());
+
+/// Returns the module with a state of type `T` associated with `ty` or with the first of its
+/// bases which has one, see [`PyTypeMethods::module_with_state`].
+#[cfg(all(
+    any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+    not(any(PyPy, GraalPy))
+))]
+pub(crate) fn module_with_state<'py, T: PyModuleState>(
+    ty: &Bound<'py, PyType>,
+) -> PyResult<Bound<'py, PyModule>> {
+    let defs: Vec<&'static ModuleDef> = STATE_DEFS
+        .lock()
+        .unwrap()
+        .iter()
+        .copied()
+        .filter(|def| def.state_type.map(|state_type| state_type()) == Some(TypeId::of::<T>()))
+        .collect();
+    for def in defs {
+        if let Some(module) = module_by_def(ty, def.ffi_def.get())? {
+            return Ok(module);
+        }
+    }
+    Err(PyTypeError::new_err(format!(
+        "type '{}' is not associated with a module with state `{}`",
+        ty.name()?,
+        std::any::type_name::<T>()
+    )))
+}
+
+/// Returns the module created from `def` associated with `ty` or with the first of its bases
+/// which has one, like `PyType_GetModuleByDef`.
+#[cfg(all(
+    any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+    not(any(PyPy, GraalPy))
+))]
+fn module_by_def<'py>(
+    ty: &Bound<'py, PyType>,
+    def: *mut ffi::PyModuleDef,
+) -> PyResult<Option<Bound<'py, PyModule>>> {
+    let py = ty.py();
+    #[cfg(any(Py_3_13, all(Py_3_11, not(Py_LIMITED_API))))]
+    {
+        let module = unsafe { ffi::PyType_GetModuleByDef(ty.as_type_ptr(), def) };
+        if module.is_null() {
+            // raised when no class of the MRO is associated with a module created from `def`
+            let err = PyErr::fetch(py);
+            return if err.is_instance_of::<PyTypeError>(py) {
+                Ok(None)
+            } else {
+                Err(err)
+            };
+        }
+        Ok(Some(unsafe {
+            module
+                .assume_borrowed(py)
+                .to_owned()
+                .downcast_into_unchecked()
+        }))
+    }
+    #[cfg(not(any(Py_3_13, all(Py_3_11, not(Py_LIMITED_API)))))]
+    {
+        use crate::types::PyTupleMethods;
+        for base in ty.mro().iter() {
+            let base = base.downcast_into::<PyType>()?;
+            if unsafe { ffi::PyType_GetFlags(base.as_type_ptr()) } & ffi::Py_TPFLAGS_HEAPTYPE == 0 {
+                continue;
+            }
+            let module = unsafe { ffi::PyType_GetModule(base.as_type_ptr()) };
+            if module.is_null() {
+                // raised for the classes which are not associated with a module
+                drop(PyErr::take(py));
+                continue;
+            }
+            if unsafe { ffi::PyModule_GetDef(module) } == def {
+                return Ok(Some(unsafe {
+                    module
+                        .assume_borrowed(py)
+                        .to_owned()
+                        .downcast_into_unchecked()
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// The definitions of the modules with a panic policy which have been initialized.
+static PANIC_POLICY_DEFS: Mutex<Vec<&'static ModuleDef>> = Mutex::new(Vec::new());
+/// Whether `PANIC_POLICY_DEFS` is not empty, so that it is only locked when needed.
+static HAS_PANIC_POLICIES: AtomicBool = AtomicBool::new(false);
+
+/// Returns the panic policy of the module `owner` belongs to, see [`PanicPolicy`].
+///
+/// `owner` is the module of a function, or the instance or class of a method, whose class is
+/// associated with its module by `add_class`.
+pub(crate) fn module_panic_policy(owner: &Bound<'_, PyAny>) -> PanicPolicy {
+    if !HAS_PANIC_POLICIES.load(Ordering::Relaxed) {
+        return PanicPolicy::Raise;
+    }
+    let defs: Vec<&'static ModuleDef> = PANIC_POLICY_DEFS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    if let Ok(module) = owner.downcast::<PyModule>() {
+        // null, without an exception, if the module was not created from a definition
+        let def = unsafe { ffi::PyModule_GetDef(module.as_ptr()) };
+        return defs
+            .iter()
+            .find(|module_def| module_def.ffi_def.get() == def)
+            .and_then(|module_def| module_def.panic_policy)
+            .unwrap_or_default();
+    }
+    #[cfg(all(
+        any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+        not(any(PyPy, GraalPy))
+    ))]
+    {
+        let ty = match owner.downcast::<PyType>() {
+            Ok(ty) => ty.clone(),
+            Err(_) => owner.get_type(),
+        };
+        for def in &defs {
+            if let Ok(Some(_)) = module_by_def(&ty, def.ffi_def.get()) {
+                return def.panic_policy.unwrap_or_default();
+            }
+        }
+    }
+    PanicPolicy::Raise
+}
+
+/// Returns whether the classes added to `module` are associated with it, so that their methods
+/// can find its state and its panic policy.
+#[cfg(all(
+    any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+    not(any(PyPy, GraalPy))
+))]
+pub(crate) fn associates_classes(module: &Bound<'_, PyModule>) -> bool {
+    if has_module_state(module) {
+        return true;
+    }
+    if !HAS_PANIC_POLICIES.load(Ordering::Relaxed) {
+        return false;
+    }
+    let def = unsafe { ffi::PyModule_GetDef(module.as_ptr()) };
+    !def.is_null()
+        && PANIC_POLICY_DEFS
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|module_def| module_def.ffi_def.get() == def)
+}
+
+/// Returns whether `module` was created from a [`ModuleDef`] with a state.
+pub(crate) fn has_module_state(module: &Bound<'_, PyModule>) -> bool {
+    let def = unsafe { ffi::PyModule_GetDef(module.as_ptr()) };
+    // Modules with a PyO3 state are recognized by their `m_free` hook.
+    !def.is_null()
+        && unsafe { (*def).m_free }.map(|free| free as usize)
+            == Some(module_state_free as ffi::freefunc as usize)
+}
+
+/// Returns the state of `module`, which must have been created from a [`ModuleDef`] with a state
+/// of type `T`.
+pub(crate) fn module_state<'a, T: PyModuleState>(
+    module: &'a Bound<'_, PyModule>,
+) -> PyResult<&'a T> {
+    if !has_module_state(module) {
+        return Err(PyTypeError::new_err(format!(
+            "module '{}' has no PyO3 module state",
+            module.name()?
+        )));
+    }
+    let slot = state_slot(module.as_ptr());
+    // SAFETY: the state is only dropped when the module is cleared or deallocated, which cannot
+    // happen while `module` holds a reference to it.
+    let state = match unsafe { slot.as_ref().and_then(|state| state.as_ref()) } {
+        Some(state) => state,
+        None => {
+            return Err(PyRuntimeError::new_err(format!(
+                "state of module '{}' is not initialized",
+                module.name()?
+            )))
+        }
+    };
+    match state.as_any().downcast_ref::<T>() {
+        Some(state) => Ok(state),
+        None => Err(PyTypeError::new_err(format!(
+            "state of module '{}' is not of type `{}`",
+            module.name()?,
+            std::any::type_name::<T>()
+        ))),
     }
 }
 
@@ -274,6 +811,128 @@ mod tests {
         })
     }
 
+    #[test]
+    #[cfg(all(
+        not(any(PyPy, GraalPy)),
+        Py_3_9,
+        not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+    ))]
+    fn module_init_creates_new_modules() {
+        use std::sync::atomic::AtomicUsize;
+
+        static INIT_COUNT: AtomicUsize = AtomicUsize::new(0);
+        static MODULE_DEF: ModuleDef = unsafe {
+            ModuleDef::new(
+                ffi::c_str!("test_module_reinit"),
+                ffi::c_str!(""),
+                ModuleInitializer(|m| {
+                    m.add("COUNT", INIT_COUNT.fetch_add(1, Ordering::SeqCst))?;
+                    Ok(())
+                }),
+            )
+        };
+        Python::with_gil(|py| {
+            let first = MODULE_DEF.make_module(py, false).unwrap().into_bound(py);
+            let second = MODULE_DEF.make_module(py, false).unwrap().into_bound(py);
+            assert!(!first.is(&second));
+            assert_eq!(
+                first.getattr("COUNT").unwrap().extract::<usize>().unwrap(),
+                0
+            );
+            assert_eq!(
+                second.getattr("COUNT").unwrap().extract::<usize>().unwrap(),
+                1
+            );
+        })
+    }
+
+    #[test]
+    #[cfg(not(all(
+        not(any(PyPy, GraalPy)),
+        Py_3_9,
+        not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+    )))]
+    fn module_init_is_cached() {
+        static MODULE_DEF: ModuleDef = unsafe {
+            ModuleDef::new(
+                ffi::c_str!("test_module_cached"),
+                ffi::c_str!(""),
+                ModuleInitializer(|_| Ok(())),
+            )
+        };
+        Python::with_gil(|py| {
+            let first = MODULE_DEF.make_module(py, false).unwrap();
+            let second = MODULE_DEF.make_module(py, false).unwrap();
+            assert!(first.is(&second));
+        })
+    }
+
+    #[test]
+    #[cfg(all(
+        not(any(PyPy, GraalPy)),
+        Py_3_9,
+        not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+    ))]
+    fn module_state_is_traversed_and_cleared() {
+        use std::sync::Mutex;
+
+        use crate::{
+            pyclass::{PyTraverseError, PyVisit},
+            types::PyModuleState,
+            PyObject,
+        };
+
+        static DROPPED: AtomicBool = AtomicBool::new(false);
+
+        #[derive(Default)]
+        struct State {
+            module: Mutex<Option<PyObject>>,
+        }
+
+        impl PyModuleState for State {
+            fn __traverse__(&self, visit: PyVisit<'_>) -> Result<(), PyTraverseError> {
+                if let Some(module) = &*self.module.lock().unwrap() {
+                    visit.call(module)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl Drop for State {
+            fn drop(&mut self) {
+                DROPPED.store(true, Ordering::SeqCst);
+            }
+        }
+
+        static MODULE_DEF: ModuleDef = unsafe {
+            ModuleDef::new(
+                ffi::c_str!("test_module_state"),
+                ffi::c_str!(""),
+                ModuleInitializer(|m| {
+                    // reference cycle through the module state
+                    *m.state::<State>()?.module.lock().unwrap() =
+                        Some(m.clone().into_any().unbind());
+                    Ok(())
+                }),
+            )
+            .with_state::<State>()
+        };
+        Python::with_gil(|py| {
+            let module = MODULE_DEF.make_module(py, false).unwrap();
+            assert!(module
+                .bind(py)
+                .state::<State>()
+                .unwrap()
+                .module
+                .lock()
+                .unwrap()
+                .is_some());
+            drop(module);
+            py.import("gc").unwrap().call_method0("collect").unwrap();
+            assert!(DROPPED.load(Ordering::SeqCst));
+        })
+    }
+
     #[test]
     fn module_def_new() {
         // To get coverage for ModuleDef::new() need to create a non-static ModuleDef, however init
@@ -11,15 +11,32 @@ use std::{
 
 use crate::gil::GILGuard;
 use crate::{
-    ffi, ffi_ptr_ext::FfiPtrExt, impl_::callback::PyCallbackOutput, impl_::panic::PanicTrap,
-    impl_::pymethods::IPowModulo, panic::PanicException, types::PyModule, Py, PyResult, Python,
+    ffi,
+    ffi_ptr_ext::FfiPtrExt,
+    impl_::callback::PyCallbackOutput,
+    impl_::panic::{panic_policy, PanicTrap},
+    impl_::pymethods::IPowModulo,
+    panic::PanicException,
+    types::PyModule,
+    Py, PyResult, Python,
 };
 
 #[inline]
 pub unsafe fn module_init(
     f: for<'py> unsafe fn(Python<'py>) -> PyResult<Py<PyModule>>,
 ) -> *mut ffi::PyObject {
-    trampoline(|py| f(py).map(|module| module.into_ptr()))
+    trampoline(
+        |py| f(py).map(|module| module.into_ptr()),
+        std::ptr::null_mut(),
+    )
+}
+
+#[inline]
+pub unsafe fn module_exec(
+    module: *mut ffi::PyObject,
+    f: for<'py> unsafe fn(Python<'py>, *mut ffi::PyObject) -> PyResult<()>,
+) -> c_int {
+    trampoline(|py| f(py, module).map(|()| 0), module)
 }
 
 #[inline]
@@ -31,17 +48,18 @@ pub unsafe fn noargs(
 ) -> *mut ffi::PyObject {
     #[cfg(not(GraalPy))] // this is not specified and GraalPy does not pass null here
     debug_assert!(_args.is_null());
-    trampoline(|py| f(py, slf))
+    trampoline(|py| f(py, slf), slf)
 }
 
 macro_rules! trampoline {
-    (pub fn $name:ident($($arg_names:ident: $arg_types:ty),* $(,)?) -> $ret:ty;) => {
+    (pub fn $name:ident($owner:ident: $owner_type:ty $(, $arg_names:ident: $arg_types:ty)* $(,)?) -> $ret:ty;) => {
         #[inline]
         pub unsafe fn $name(
+            $owner: $owner_type,
             $($arg_names: $arg_types,)*
-            f: for<'py> unsafe fn (Python<'py>, $($arg_types),*) -> PyResult<$ret>,
+            f: for<'py> unsafe fn (Python<'py>, $owner_type, $($arg_types),*) -> PyResult<$ret>,
         ) -> $ret {
-            trampoline(|py| f(py, $($arg_names,)*))
+            trampoline(|py| f(py, $owner, $($arg_names,)*), $owner.cast())
         }
     }
 }
@@ -122,6 +140,82 @@ trampolines!(
     pub fn unaryfunc(slf: *mut ffi::PyObject) -> *mut ffi::PyObject;
 );
 
+/// Vectorcall implementation of `__call__`, wrapping the fastcall implementation `f`.
+#[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))]
+#[inline]
+pub unsafe fn vectorcallfunc(
+    slf: *mut ffi::PyObject,
+    args: *const *mut ffi::PyObject,
+    nargsf: libc::size_t,
+    kwnames: *mut ffi::PyObject,
+    f: for<'py> unsafe fn(
+        Python<'py>,
+        *mut ffi::PyObject,
+        *const *mut ffi::PyObject,
+        ffi::Py_ssize_t,
+        *mut ffi::PyObject,
+    ) -> PyResult<*mut ffi::PyObject>,
+) -> *mut ffi::PyObject {
+    trampoline(
+        |py| f(py, slf, args, ffi::PyVectorcall_NARGS(nargsf), kwnames),
+        slf,
+    )
+}
+
+/// Vectorcall implementation of calling a type object, wrapping the fastcall implementation `f`
+/// of `#[new]`.
+///
+/// Like `type.__call__`, this then runs `__init__` on the new object.
+#[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))]
+#[inline]
+pub unsafe fn vectorcall_new(
+    subtype: *mut ffi::PyObject,
+    args: *const *mut ffi::PyObject,
+    nargsf: libc::size_t,
+    kwnames: *mut ffi::PyObject,
+    f: for<'py> unsafe fn(
+        Python<'py>,
+        *mut ffi::PyTypeObject,
+        *const *mut ffi::PyObject,
+        ffi::Py_ssize_t,
+        *mut ffi::PyObject,
+    ) -> PyResult<*mut ffi::PyObject>,
+) -> *mut ffi::PyObject {
+    trampoline(
+        |py| {
+            let subtype = subtype.cast::<ffi::PyTypeObject>();
+            let nargs = ffi::PyVectorcall_NARGS(nargsf);
+            let obj = f(py, subtype, args, nargs, kwnames)?.assume_owned(py);
+            crate::impl_::pymethods::vectorcall_init(py, subtype, &obj, args, nargs, kwnames)?;
+            Ok(obj.into_ptr())
+        },
+        subtype,
+    )
+}
+
+/// Implementation of `tp_init`, wrapping the varargs implementation `f` of `#[init]`.
+#[inline]
+pub unsafe fn initproc(
+    slf: *mut ffi::PyObject,
+    args: *mut ffi::PyObject,
+    kwargs: *mut ffi::PyObject,
+    f: for<'py> unsafe fn(
+        Python<'py>,
+        *mut ffi::PyObject,
+        *mut ffi::PyObject,
+        *mut ffi::PyObject,
+    ) -> PyResult<*mut ffi::PyObject>,
+) -> c_int {
+    trampoline(
+        |py| {
+            // `#[init]` returns `()`, converted to `None`
+            f(py, slf, args, kwargs)?.assume_owned(py);
+            Ok(0)
+        },
+        slf,
+    )
+}
+
 #[cfg(any(not(Py_LIMITED_API), Py_3_11))]
 trampoline! {
     pub fn getbufferproc(slf: *mut ffi::PyObject, buf: *mut ffi::Py_buffer, flags: c_int) -> c_int;
@@ -166,14 +260,45 @@ trampoline!(
     ) -> *mut ffi::PyObject;
 );
 
+/// Runs the body of a `#[pyo3(traceback)]` function, adding a frame for the function to the
+/// traceback of the error it returns or of the panic it raises.
+///
+/// `line` is the line of the function name, so the frame marks the entry point of the function
+/// rather than where the error was raised, which a `PyErr` does not record.
+///
+/// # Safety
+///
+/// `owner`, whose module gives the panic policy, must be null or a valid pointer to a Python
+/// object.
+#[inline]
+pub unsafe fn traceable<R>(
+    py: Python<'_>,
+    owner: *mut ffi::PyObject,
+    function: &str,
+    file: &str,
+    line: u32,
+    body: impl FnOnce() -> PyResult<R>,
+) -> PyResult<R> {
+    let err = match panic::catch_unwind(panic::AssertUnwindSafe(body)) {
+        Ok(Ok(value)) => return Ok(value),
+        Ok(Err(err)) => err,
+        Err(payload) => PanicException::from_panic_payload(py, payload, panic_policy(py, owner)),
+    };
+    Err(err.with_rust_frame(py, function, file, line))
+}
+
 /// Implementation of trampoline functions, which sets up a GILPool and calls F.
 ///
 /// Panics during execution are trapped so that they don't propagate through any
-/// outer FFI boundary.
+/// outer FFI boundary. They are handled with the panic policy of the module of `owner`, the
+/// module of a function or the instance or class of a method.
+///
+/// # Safety
 ///
-/// The GIL must already be held when this is called.
+/// - owner must be either a valid ffi::PyObject or NULL
+/// - The GIL must already be held when this is called.
 #[inline]
-pub(crate) unsafe fn trampoline<F, R>(body: F) -> R
+pub(crate) unsafe fn trampoline<F, R>(body: F, owner: *mut ffi::PyObject) -> R
 where
     F: for<'py> FnOnce(Python<'py>) -> PyResult<R> + UnwindSafe,
     R: PyCallbackOutput,
@@ -186,6 +311,7 @@ where
     let out = panic_result_into_callback_output(
         py,
         panic::catch_unwind(move || -> PyResult<_> { body(py) }),
+        owner,
     );
     trap.disarm();
     out
@@ -194,9 +320,10 @@ where
 /// Converts the output of std::panic::catch_unwind into a Python function output, either by raising a Python
 /// exception or by unwrapping the contained success output.
 #[inline]
-fn panic_result_into_callback_output<R>(
+unsafe fn panic_result_into_callback_output<R>(
     py: Python<'_>,
     panic_result: Result<PyResult<R>, Box<dyn Any + Send + 'static>>,
+    owner: *mut ffi::PyObject,
 ) -> R
 where
     R: PyCallbackOutput,
@@ -204,7 +331,7 @@ where
     let py_err = match panic_result {
         Ok(Ok(value)) => return value,
         Ok(Err(py_err)) => py_err,
-        Err(payload) => PanicException::from_panic_payload(payload),
+        Err(payload) => PanicException::from_panic_payload(py, payload, panic_policy(py, owner)),
     };
     py_err.restore(py);
     R::ERR_VALUE
@@ -215,14 +342,15 @@ where
 /// Panics during execution are trapped so that they don't propagate through any
 /// outer FFI boundary.
 ///
-/// Exceptions produced are sent to `sys.unraisablehook`.
+/// Exceptions produced are sent to `sys.unraisablehook`. Panics are handled with the panic
+/// policy of the module of `ctx`.
 ///
 /// # Safety
 ///
 /// - ctx must be either a valid ffi::PyObject or NULL
 /// - The GIL must already be held when this is called.
 #[inline]
-unsafe fn trampoline_unraisable<F>(body: F, ctx: *mut ffi::PyObject)
+pub(crate) unsafe fn trampoline_unraisable<F>(body: F, ctx: *mut ffi::PyObject)
 where
     F: for<'py> FnOnce(Python<'py>) -> PyResult<()> + UnwindSafe,
 {
@@ -232,9 +360,13 @@ where
     let guard = GILGuard::assume();
     let py = guard.python();
 
-    if let Err(py_err) = panic::catch_unwind(move || body(py))
-        .unwrap_or_else(|payload| Err(PanicException::from_panic_payload(payload)))
-    {
+    if let Err(py_err) = panic::catch_unwind(move || body(py)).unwrap_or_else(|payload| {
+        Err(PanicException::from_panic_payload(
+            py,
+            payload,
+            panic_policy(py, ctx),
+        ))
+    }) {
         py_err.write_unraisable(py, ctx.assume_borrowed_or_opt(py).as_deref());
     }
     trap.disarm();
@@ -0,0 +1,329 @@
+//! Data types used to describe the Python interface of modules, classes and functions.
+//!
+//! These are produced by PyO3's macros and gathered at runtime by
+//! [`ModuleInfo::from_module`](crate::inspect::interface::ModuleInfo::from_module), and can be
+//! rendered into stub files using the [`stubs`](crate::inspect::stubs) module.
+
+use std::borrow::Cow;
+
+use crate::impl_::introspection;
+use crate::inspect::types::{ModuleName, TypeInfo};
+use crate::types::any::PyAnyMethods;
+use crate::types::module::PyModuleMethods;
+use crate::types::typeobject::PyTypeMethods;
+use crate::types::{PyCFunction, PyModule, PyType};
+use crate::{Bound, PyAny, PyResult};
+
+/// Description of a Python module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleInfo {
+    /// The name of the module, without the name of its parent package.
+    pub name: String,
+    /// The module docstring.
+    pub doc: Option<String>,
+    /// The functions defined in the module.
+    pub functions: Vec<FunctionInfo>,
+    /// The classes defined in the module.
+    pub classes: Vec<ClassInfo>,
+    /// Other values (constants, instances...) stored in the module.
+    pub attributes: Vec<AttributeInfo>,
+    /// The submodules of this module.
+    pub submodules: Vec<ModuleInfo>,
+}
+
+/// Description of a Python class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassInfo {
+    /// The name of the class.
+    pub name: String,
+    /// The class docstring.
+    pub doc: Option<String>,
+    /// The base classes, `object` being omitted.
+    pub bases: Vec<TypeInfo>,
+    /// The methods of the class, including the constructor.
+    pub methods: Vec<FunctionInfo>,
+    /// The properties of the class, created by `#[getter]`, `#[setter]` and `#[pyo3(get, set)]`.
+    pub properties: Vec<PropertyInfo>,
+    /// The class attributes, created by `#[classattr]`.
+    pub attributes: Vec<AttributeInfo>,
+}
+
+/// Description of a Python function or method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionInfo {
+    /// The name of the function.
+    pub name: Cow<'static, str>,
+    /// The function docstring.
+    pub doc: Option<String>,
+    /// How the function is bound when it is a class member.
+    pub kind: FunctionKind,
+    /// The parameters accepted by the function, not including `self`, `cls` or the module.
+    pub parameters: Vec<ParameterInfo>,
+    /// The type returned by the function.
+    pub return_type: TypeInfo,
+}
+
+/// How a function is bound when it is a class member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionKind {
+    /// A module-level function.
+    Function,
+    /// An instance method, taking `self`.
+    Method,
+    /// A `@classmethod`, taking `cls`.
+    ClassMethod,
+    /// A `@staticmethod`.
+    StaticMethod,
+    /// The class constructor, `__new__`.
+    Constructor,
+}
+
+/// Description of a parameter of a Python function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParameterInfo {
+    /// The name of the parameter.
+    pub name: Cow<'static, str>,
+    /// How the parameter can be passed.
+    pub kind: ParameterKind,
+    /// The Python representation of the default value, if the parameter is optional.
+    ///
+    /// This is `...` when the default value has no simple Python representation.
+    pub default: Option<Cow<'static, str>>,
+    /// The type accepted by the parameter.
+    pub annotation: TypeInfo,
+}
+
+/// How a parameter can be passed to a function, mirroring `inspect.Parameter.kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterKind {
+    /// A parameter which can only be passed by position.
+    PositionalOnly,
+    /// A parameter which can be passed either by position or by keyword.
+    PositionalOrKeyword,
+    /// The `*args` parameter.
+    VarPositional,
+    /// A parameter which can only be passed by keyword.
+    KeywordOnly,
+    /// The `**kwargs` parameter.
+    VarKeyword,
+}
+
+/// Description of a property of a Python class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyInfo {
+    /// The name of the property.
+    pub name: String,
+    /// The property docstring.
+    pub doc: Option<String>,
+    /// The type of the property.
+    pub annotation: TypeInfo,
+    /// Whether the property has a setter.
+    pub writable: bool,
+}
+
+/// Description of a value stored in a module or class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeInfo {
+    /// The name of the attribute.
+    pub name: String,
+    /// The type of the attribute.
+    pub annotation: TypeInfo,
+}
+
+impl ModuleInfo {
+    /// Describes a module by walking through its contents.
+    ///
+    /// Functions and classes created by PyO3's macros are described using the metadata emitted
+    /// by the macros. Other objects are described using their runtime type only.
+    pub fn from_module(module: &Bound<'_, PyModule>) -> PyResult<ModuleInfo> {
+        let full_name = module.name()?.to_string();
+        let mut info = ModuleInfo {
+            name: full_name
+                .rsplit('.')
+                .next()
+                .unwrap_or(&full_name)
+                .to_owned(),
+            doc: docstring(module.as_any()),
+            functions: Vec::new(),
+            classes: Vec::new(),
+            attributes: Vec::new(),
+            submodules: Vec::new(),
+        };
+
+        for (key, value) in module.dict() {
+            let name = key.extract::<String>()?;
+            if name.starts_with("__") && name.ends_with("__") {
+                continue;
+            }
+
+            if let Ok(submodule) = value.downcast::<PyModule>() {
+                // Only describe the modules which are children of this one, not imported ones.
+                let submodule_name = submodule.name()?.to_string();
+                if submodule_name == name || submodule_name == format!("{}.{}", full_name, name) {
+                    info.submodules.push(ModuleInfo::from_module(submodule)?);
+                }
+            } else if let Ok(function) = value.downcast::<PyCFunction>() {
+                let mut function_info = match introspection::function_info(function) {
+                    Some(function_info) => function_info,
+                    None => signature_from_inspect(function.as_any(), &name),
+                };
+                function_info.name = Cow::Owned(name);
+                function_info.doc = docstring(function.as_any());
+                info.functions.push(function_info);
+            } else if let Ok(class) = value.downcast::<PyType>() {
+                if let Some(mut class_info) = introspection::class_info_of(class) {
+                    class_info.name = name;
+                    info.classes.push(class_info);
+                } else if class.module()?.to_string() == full_name {
+                    // For instance exceptions created by `create_exception!`
+                    info.classes.push(ClassInfo {
+                        name,
+                        doc: docstring(class.as_any()),
+                        bases: introspection::bases_of(class),
+                        methods: Vec::new(),
+                        properties: Vec::new(),
+                        attributes: Vec::new(),
+                    });
+                } else {
+                    info.attributes.push(AttributeInfo {
+                        name,
+                        annotation: TypeInfo::Class {
+                            module: ModuleName::Builtin,
+                            name: Cow::Borrowed("type"),
+                            type_vars: vec![introspection::type_hint_of(class)],
+                        },
+                    });
+                }
+            } else {
+                info.attributes.push(AttributeInfo {
+                    name,
+                    annotation: introspection::type_hint_of(&value.get_type()),
+                });
+            }
+        }
+
+        info.localize(&full_name);
+        Ok(info)
+    }
+
+    /// Marks the types declared in the module `module_name` as belonging to the current module.
+    fn localize(&mut self, module_name: &str) {
+        let localize_function = |function: &mut FunctionInfo| {
+            localize_type(&mut function.return_type, module_name);
+            for parameter in &mut function.parameters {
+                localize_type(&mut parameter.annotation, module_name);
+            }
+        };
+        self.functions.iter_mut().for_each(localize_function);
+        for attribute in &mut self.attributes {
+            localize_type(&mut attribute.annotation, module_name);
+        }
+        for class in &mut self.classes {
+            class
+                .bases
+                .iter_mut()
+                .for_each(|base| localize_type(base, module_name));
+            class.methods.iter_mut().for_each(localize_function);
+            for property in &mut class.properties {
+                localize_type(&mut property.annotation, module_name);
+            }
+            for attribute in &mut class.attributes {
+                localize_type(&mut attribute.annotation, module_name);
+            }
+        }
+    }
+}
+
+fn localize_type(t: &mut TypeInfo, module_name: &str) {
+    match t {
+        TypeInfo::Any | TypeInfo::None | TypeInfo::NoReturn | TypeInfo::Tuple(None) => {}
+        TypeInfo::Callable(parameters, output) => {
+            parameters
+                .iter_mut()
+                .flatten()
+                .for_each(|t| localize_type(t, module_name));
+            localize_type(output, module_name);
+        }
+        TypeInfo::Tuple(Some(types)) => {
+            types.iter_mut().for_each(|t| localize_type(t, module_name))
+        }
+        TypeInfo::UnsizedTypedTuple(t) => localize_type(t, module_name),
+        TypeInfo::Class {
+            module, type_vars, ..
+        } => {
+            if matches!(module, ModuleName::Module(name) if name == module_name) {
+                *module = ModuleName::CurrentModule;
+            }
+            type_vars
+                .iter_mut()
+                .for_each(|t| localize_type(t, module_name));
+        }
+    }
+}
+
+fn docstring(object: &Bound<'_, PyAny>) -> Option<String> {
+    object
+        .getattr("__doc__")
+        .and_then(|doc| doc.extract::<Option<String>>())
+        .ok()
+        .flatten()
+        .filter(|doc| !doc.is_empty())
+}
+
+/// Describes a function which has not been created by `#[pyfunction]` using `inspect.signature`.
+fn signature_from_inspect(function: &Bound<'_, PyAny>, name: &str) -> FunctionInfo {
+    let parameters = (|| -> PyResult<Vec<ParameterInfo>> {
+        let py = function.py();
+        let signature = py
+            .import("inspect")?
+            .call_method1("signature", (function,))?;
+        let mut parameters = Vec::new();
+        for parameter in signature
+            .getattr("parameters")?
+            .call_method0("values")?
+            .try_iter()?
+        {
+            let parameter = parameter?;
+            let kind = match parameter.getattr("kind")?.extract::<u8>()? {
+                0 => ParameterKind::PositionalOnly,
+                1 => ParameterKind::PositionalOrKeyword,
+                2 => ParameterKind::VarPositional,
+                3 => ParameterKind::KeywordOnly,
+                _ => ParameterKind::VarKeyword,
+            };
+            let has_default = !parameter
+                .getattr("default")?
+                .is(&parameter.getattr("empty")?);
+            parameters.push(ParameterInfo {
+                name: Cow::Owned(parameter.getattr("name")?.extract()?),
+                kind,
+                default: has_default.then_some(Cow::Borrowed("...")),
+                annotation: TypeInfo::Any,
+            });
+        }
+        Ok(parameters)
+    })()
+    .unwrap_or_else(|_| {
+        vec![
+            ParameterInfo {
+                name: Cow::Borrowed("args"),
+                kind: ParameterKind::VarPositional,
+                default: None,
+                annotation: TypeInfo::Any,
+            },
+            ParameterInfo {
+                name: Cow::Borrowed("kwargs"),
+                kind: ParameterKind::VarKeyword,
+                default: None,
+                annotation: TypeInfo::Any,
+            },
+        ]
+    });
+    FunctionInfo {
+        name: Cow::Owned(name.to_owned()),
+        doc: None,
+        kind: FunctionKind::Function,
+        parameters,
+        return_type: TypeInfo::Any,
+    }
+}
@@ -0,0 +1,458 @@
+//! Generation of Python stub files (`.pyi`) from the description of a module.
+//!
+//! ```rust,no_run
+//! use pyo3::inspect::interface::ModuleInfo;
+//! use pyo3::inspect::stubs::write_package;
+//! use pyo3::prelude::*;
+//!
+//! #[pymodule]
+//! fn my_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+//!     Ok(())
+//! }
+//!
+//! # fn main() -> PyResult<()> {
+//! Python::with_gil(|py| {
+//!     let module = pyo3::wrap_pymodule!(my_module)(py);
+//!     let info = ModuleInfo::from_module(module.bind(py).downcast()?)?;
+//!     // Writes `stubs/my_module/__init__.pyi`
+//!     write_package(&info, "stubs")?;
+//!     Ok(())
+//! })
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::inspect::interface::{
+    AttributeInfo, ClassInfo, FunctionInfo, FunctionKind, ModuleInfo, ParameterInfo, ParameterKind,
+    PropertyInfo,
+};
+use crate::inspect::types::{ModuleName, TypeInfo};
+
+/// Renders the contents of the stub file of `module`.
+///
+/// Submodules are imported but not rendered, see [`write_package`].
+pub fn module_stub(module: &ModuleInfo) -> String {
+    let mut imports = Imports::default();
+    module.functions.iter().for_each(|f| imports.function(f));
+    module
+        .attributes
+        .iter()
+        .for_each(|a| imports.add(&a.annotation));
+    for class in &module.classes {
+        class.bases.iter().for_each(|t| imports.add(t));
+        class.methods.iter().for_each(|f| imports.function(f));
+        class
+            .properties
+            .iter()
+            .for_each(|p| imports.add(&p.annotation));
+        if !class.attributes.is_empty() {
+            imports.typing("ClassVar");
+        }
+        class
+            .attributes
+            .iter()
+            .for_each(|a| imports.add(&a.annotation));
+    }
+
+    let mut sections = Vec::new();
+    if let Some(doc) = &module.doc {
+        sections.push(docstring(doc, ""));
+    }
+    let mut header = String::new();
+    for (module_name, names) in &imports.0 {
+        let names = names.iter().map(String::as_str).collect::<Vec<_>>();
+        writeln!(header, "from {} import {}", module_name, names.join(", ")).unwrap();
+    }
+    for submodule in &module.submodules {
+        writeln!(header, "from . import {}", submodule.name).unwrap();
+    }
+    if !header.is_empty() {
+        sections.push(header);
+    }
+    if !module.attributes.is_empty() {
+        let mut attributes = String::new();
+        for attribute in &module.attributes {
+            write_attribute(&mut attributes, attribute, "", false);
+        }
+        sections.push(attributes);
+    }
+    for function in &module.functions {
+        let mut output = String::new();
+        write_function(&mut output, function, "");
+        sections.push(output);
+    }
+    for class in &module.classes {
+        sections.push(class_stub(class));
+    }
+    sections.join("\n")
+}
+
+/// Writes the stubs of `module` and of its submodules as a package in `directory`.
+///
+/// The stubs of the module are written to `<directory>/<module name>/__init__.pyi`, next to a
+/// `py.typed` marker. Submodules without children are written to `<submodule name>.pyi` files
+/// in the package, others to their own sub-package.
+pub fn write_package(module: &ModuleInfo, directory: impl AsRef<Path>) -> io::Result<()> {
+    let package = directory.as_ref().join(&module.name);
+    fs::create_dir_all(&package)?;
+    fs::write(package.join("py.typed"), "")?;
+    write_package_contents(module, &package)
+}
+
+fn write_package_contents(module: &ModuleInfo, package: &Path) -> io::Result<()> {
+    fs::write(package.join("__init__.pyi"), module_stub(module))?;
+    for submodule in &module.submodules {
+        if submodule.submodules.is_empty() {
+            fs::write(
+                package.join(format!("{}.pyi", submodule.name)),
+                module_stub(submodule),
+            )?;
+        } else {
+            let subpackage = package.join(&submodule.name);
+            fs::create_dir_all(&subpackage)?;
+            write_package_contents(submodule, &subpackage)?;
+        }
+    }
+    Ok(())
+}
+
+/// The `from <module> import <names>` statements required by a stub file.
+#[derive(Default)]
+struct Imports(BTreeMap<String, BTreeSet<String>>);
+
+impl Imports {
+    fn typing(&mut self, name: &str) {
+        self.insert("typing", name);
+    }
+
+    fn insert(&mut self, module: &str, name: &str) {
+        self.0
+            .entry(module.to_owned())
+            .or_default()
+            .insert(name.to_owned());
+    }
+
+    fn function(&mut self, function: &FunctionInfo) {
+        function
+            .parameters
+            .iter()
+            .for_each(|p| self.add(&p.annotation));
+        self.add(&function.return_type);
+    }
+
+    fn add(&mut self, t: &TypeInfo) {
+        match t {
+            TypeInfo::None => {}
+            TypeInfo::Any | TypeInfo::NoReturn => self.typing(&t.name()),
+            TypeInfo::Callable(parameters, output) => {
+                self.typing("Callable");
+                parameters.iter().flatten().for_each(|t| self.add(t));
+                self.add(output);
+            }
+            TypeInfo::Tuple(types) => {
+                self.typing("Tuple");
+                types.iter().flatten().for_each(|t| self.add(t));
+            }
+            TypeInfo::UnsizedTypedTuple(t) => {
+                self.typing("Tuple");
+                self.add(t);
+            }
+            TypeInfo::Class {
+                module,
+                name,
+                type_vars,
+            } => {
+                if let ModuleName::Module(module) = module {
+                    // Nested classes are accessed through their outermost class
+                    self.insert(module, name.split('.').next().unwrap_or(name));
+                }
+                type_vars.iter().for_each(|t| self.add(t));
+            }
+        }
+    }
+}
+
+fn class_stub(class: &ClassInfo) -> String {
+    let mut output = String::new();
+    write!(output, "class {}", class.name).unwrap();
+    if !class.bases.is_empty() {
+        let bases = class
+            .bases
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        write!(output, "({})", bases.join(", ")).unwrap();
+    }
+    output.push_str(":\n");
+
+    let indent = "    ";
+    let mut is_empty = true;
+    if let Some(doc) = &class.doc {
+        output.push_str(&docstring(doc, indent));
+        is_empty = false;
+    }
+    for attribute in &class.attributes {
+        write_attribute(&mut output, attribute, indent, true);
+        is_empty = false;
+    }
+    for property in &class.properties {
+        write_property(&mut output, property, indent);
+        is_empty = false;
+    }
+    for method in &class.methods {
+        write_function(&mut output, method, indent);
+        is_empty = false;
+    }
+    if is_empty {
+        writeln!(output, "{}...", indent).unwrap();
+    }
+    output
+}
+
+fn write_attribute(output: &mut String, attribute: &AttributeInfo, indent: &str, class_var: bool) {
+    if class_var {
+        writeln!(
+            output,
+            "{}{}: ClassVar[{}]",
+            indent, attribute.name, attribute.annotation
+        )
+        .unwrap();
+    } else {
+        writeln!(
+            output,
+            "{}{}: {}",
+            indent, attribute.name, attribute.annotation
+        )
+        .unwrap();
+    }
+}
+
+fn write_property(output: &mut String, property: &PropertyInfo, indent: &str) {
+    writeln!(output, "{}@property", indent).unwrap();
+    write!(
+        output,
+        "{}def {}(self) -> {}:",
+        indent, property.name, property.annotation
+    )
+    .unwrap();
+    write_body(output, property.doc.as_deref(), indent);
+    if property.writable {
+        writeln!(output, "{}@{}.setter", indent, property.name).unwrap();
+        writeln!(
+            output,
+            "{}def {}(self, value: {}) -> None: ...",
+            indent, property.name, property.annotation
+        )
+        .unwrap();
+    }
+}
+
+fn write_function(output: &mut String, function: &FunctionInfo, indent: &str) {
+    let receiver = match function.kind {
+        FunctionKind::Function => None,
+        FunctionKind::Method => Some("self"),
+        FunctionKind::StaticMethod => {
+            writeln!(output, "{}@staticmethod", indent).unwrap();
+            None
+        }
+        FunctionKind::ClassMethod => {
+            writeln!(output, "{}@classmethod", indent).unwrap();
+            Some("cls")
+        }
+        FunctionKind::Constructor => Some("cls"),
+    };
+    let mut parameters = receiver.map(str::to_owned).into_iter().collect::<Vec<_>>();
+    parameters.extend(render_parameters(&function.parameters));
+    write!(
+        output,
+        "{}def {}({}) -> {}:",
+        indent,
+        function.name,
+        parameters.join(", "),
+        function.return_type
+    )
+    .unwrap();
+    write_body(output, function.doc.as_deref(), indent);
+}
+
+fn render_parameters(parameters: &[ParameterInfo]) -> Vec<String> {
+    let mut rendered = Vec::new();
+    let has_varargs = parameters
+        .iter()
+        .any(|p| p.kind == ParameterKind::VarPositional);
+    let mut previous_kind = None;
+    for parameter in parameters {
+        if previous_kind == Some(ParameterKind::PositionalOnly)
+            && parameter.kind != ParameterKind::PositionalOnly
+        {
+            rendered.push("/".to_owned());
+        }
+        if parameter.kind == ParameterKind::KeywordOnly
+            && !has_varargs
+            && previous_kind != Some(ParameterKind::KeywordOnly)
+        {
+            rendered.push("*".to_owned());
+        }
+        let prefix = match parameter.kind {
+            ParameterKind::VarPositional => "*",
+            ParameterKind::VarKeyword => "**",
+            _ => "",
+        };
+        let mut parameter_stub = format!("{}{}: {}", prefix, parameter.name, parameter.annotation);
+        if let Some(default) = &parameter.default {
+            write!(parameter_stub, " = {}", default).unwrap();
+        }
+        rendered.push(parameter_stub);
+        previous_kind = Some(parameter.kind);
+    }
+    if previous_kind == Some(ParameterKind::PositionalOnly) {
+        rendered.push("/".to_owned());
+    }
+    rendered
+}
+
+/// Writes the end of a `def` line, followed by the docstring if there is one.
+fn write_body(output: &mut String, doc: Option<&str>, indent: &str) {
+    match doc {
+        Some(doc) => {
+            output.push('\n');
+            output.push_str(&docstring(doc, &format!("{}    ", indent)));
+        }
+        None => output.push_str(" ...\n"),
+    }
+}
+
+fn docstring(doc: &str, indent: &str) -> String {
+    let doc = doc.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\"");
+    let mut output = format!("{}\"\"\"", indent);
+    for (i, line) in doc.lines().enumerate() {
+        if i > 0 {
+            output.push('\n');
+            if !line.is_empty() {
+                output.push_str(indent);
+            }
+        }
+        output.push_str(line);
+    }
+    if doc.contains('\n') {
+        output.push('\n');
+        output.push_str(indent);
+    }
+    output.push_str("\"\"\"\n");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+
+    fn parameter(name: &'static str, kind: ParameterKind) -> ParameterInfo {
+        ParameterInfo {
+            name: Cow::Borrowed(name),
+            kind,
+            default: None,
+            annotation: TypeInfo::builtin("int"),
+        }
+    }
+
+    #[test]
+    fn test_render_parameters() {
+        let parameters = [
+            parameter("a", ParameterKind::PositionalOnly),
+            parameter("b", ParameterKind::PositionalOrKeyword),
+            ParameterInfo {
+                default: Some(Cow::Borrowed("1")),
+                ..parameter("c", ParameterKind::KeywordOnly)
+            },
+            parameter("kwargs", ParameterKind::VarKeyword),
+        ];
+        assert_eq!(
+            render_parameters(&parameters),
+            ["a: int", "/", "b: int", "*", "c: int = 1", "**kwargs: int"]
+        );
+        let parameters = [
+            parameter("args", ParameterKind::VarPositional),
+            parameter("c", ParameterKind::KeywordOnly),
+        ];
+        assert_eq!(render_parameters(&parameters), ["*args: int", "c: int"]);
+    }
+
+    #[test]
+    fn test_module_stub() {
+        let module = ModuleInfo {
+            name: "example".into(),
+            doc: Some("An example module.".into()),
+            functions: vec![FunctionInfo {
+                name: Cow::Borrowed("double"),
+                doc: None,
+                kind: FunctionKind::Function,
+                parameters: vec![parameter("x", ParameterKind::PositionalOrKeyword)],
+                return_type: TypeInfo::list_of(TypeInfo::builtin("int")),
+            }],
+            classes: vec![ClassInfo {
+                name: "Point".into(),
+                doc: Some("A point.\n\nWith two coordinates.".into()),
+                bases: vec![],
+                methods: vec![FunctionInfo {
+                    name: Cow::Borrowed("__new__"),
+                    doc: None,
+                    kind: FunctionKind::Constructor,
+                    parameters: vec![
+                        parameter("x", ParameterKind::PositionalOrKeyword),
+                        parameter("y", ParameterKind::PositionalOrKeyword),
+                    ],
+                    return_type: TypeInfo::Class {
+                        module: ModuleName::CurrentModule,
+                        name: Cow::Borrowed("Point"),
+                        type_vars: vec![],
+                    },
+                }],
+                properties: vec![PropertyInfo {
+                    name: "x".into(),
+                    doc: None,
+                    annotation: TypeInfo::builtin("int"),
+                    writable: true,
+                }],
+                attributes: vec![AttributeInfo {
+                    name: "ORIGIN".into(),
+                    annotation: TypeInfo::Any,
+                }],
+            }],
+            attributes: vec![AttributeInfo {
+                name: "VERSION".into(),
+                annotation: TypeInfo::builtin("str"),
+            }],
+            submodules: vec![],
+        };
+        assert_eq!(
+            module_stub(&module),
+            r#""""An example module."""
+
+from typing import Any, ClassVar, List
+
+VERSION: str
+
+def double(x: int) -> List[int]: ...
+
+class Point:
+    """A point.
+
+    With two coordinates.
+    """
+    ORIGIN: ClassVar[Any]
+    @property
+    def x(self) -> int: ...
+    @x.setter
+    def x(self, value: int) -> None: ...
+    def __new__(cls, x: int, y: int) -> Point: ...
+"#
+        );
+    }
+}
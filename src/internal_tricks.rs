@@ -45,6 +45,20 @@ pub(crate) fn clear_eq(f: Option<ffi::inquiry>, g: ffi::inquiry) -> bool {
     }
 }
 
+// TODO: use ptr::fn_addr_eq on MSRV 1.85
+pub(crate) fn dealloc_eq(f: Option<ffi::destructor>, g: ffi::destructor) -> bool {
+    #[cfg(fn_ptr_eq)]
+    {
+        let Some(f) = f else { return false };
+        std::ptr::fn_addr_eq(f, g)
+    }
+
+    #[cfg(not(fn_ptr_eq))]
+    {
+        f == Some(g)
+    }
+}
+
 // TODO: use ptr::fn_addr_eq on MSRV 1.85
 pub(crate) fn traverse_eq(f: Option<ffi::traverseproc>, g: ffi::traverseproc) -> bool {
     #[cfg(fn_ptr_eq)]
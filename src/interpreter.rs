@@ -0,0 +1,219 @@
+//! Support for Python subinterpreters.
+//!
+//! By default, modules defined with [`#[pymodule]`](crate::pymodule) can only be loaded in a single
+//! interpreter per process. Modules declared with `#[pymodule(multiple_interpreters = true)]`
+//! can also be loaded in [subinterpreters] which share the GIL of the main interpreter, such as
+//! the ones created by [`PySubinterpreter`].
+//!
+//! PyO3 keeps the type objects of `#[pyclass]` types, the strings created by
+//! [`intern!`](crate::intern) and the exception types of
+//! [`import_exception!`](crate::import_exception) and
+//! [`create_exception!`](crate::create_exception) separately for each interpreter, using
+//! [`InterpreterOnceCell`](crate::sync::InterpreterOnceCell). Other `static` data is shared by
+//! all interpreters: in particular, a [`GILOnceCell`](crate::sync::GILOnceCell) containing Python
+//! objects must not be used by modules which support multiple interpreters.
+//!
+//! Subinterpreters are only supported on CPython 3.9 and up.
+//!
+//! [subinterpreters]: https://docs.python.org/3/c-api/init.html#sub-interpreter-support
+
+use crate::Python;
+#[cfg(all(
+    not(any(PyPy, GraalPy)),
+    Py_3_9,
+    not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+))]
+use crate::{exceptions::PyRuntimeError, ffi, marker::Ungil, PyResult};
+#[cfg(all(
+    not(any(PyPy, GraalPy)),
+    Py_3_9,
+    not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+))]
+use std::{
+    marker::PhantomData,
+    ptr::NonNull,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// ID of the main interpreter.
+pub(crate) const MAIN_INTERPRETER_ID: i64 = 0;
+
+/// Whether PyO3 code may run in a subinterpreter, set before any such code runs.
+///
+/// As long as this is not set, the ID of the current interpreter does not need to be looked up.
+#[cfg(all(
+    not(any(PyPy, GraalPy)),
+    Py_3_9,
+    not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+))]
+static SUBINTERPRETERS_USED: AtomicBool = AtomicBool::new(false);
+
+/// Returns the ID of the interpreter the current thread runs in.
+#[inline]
+pub(crate) fn current_interpreter_id(py: Python<'_>) -> i64 {
+    #[cfg(all(
+        not(any(PyPy, GraalPy)),
+        Py_3_9,
+        not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+    ))]
+    if SUBINTERPRETERS_USED.load(Ordering::Acquire) {
+        return interpreter_id(py);
+    }
+    let _ = py;
+    MAIN_INTERPRETER_ID
+}
+
+/// Looks up the ID of the current interpreter, and records when it is a subinterpreter.
+///
+/// Must be called before any PyO3 code runs in a subinterpreter.
+#[cfg(all(
+    not(any(PyPy, GraalPy)),
+    Py_3_9,
+    not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+))]
+pub(crate) fn register_interpreter(py: Python<'_>) -> i64 {
+    let id = interpreter_id(py);
+    if id != MAIN_INTERPRETER_ID {
+        SUBINTERPRETERS_USED.store(true, Ordering::Release);
+    }
+    id
+}
+
+#[cfg(all(
+    not(any(PyPy, GraalPy)),
+    Py_3_9,
+    not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+))]
+fn interpreter_id(_py: Python<'_>) -> i64 {
+    // SAFETY: the GIL is held, so there is a current interpreter
+    unsafe { ffi::PyInterpreterState_GetID(ffi::PyInterpreterState_Get()) }
+}
+
+/// A Python subinterpreter sharing the GIL of the main interpreter.
+///
+/// The subinterpreter has its own `sys.modules` and builtins, so that modules imported in it
+/// are isolated from the other interpreters. It is ended when this value is dropped.
+///
+/// Python objects belong to the interpreter they were created in. Since [`Py<T>`](crate::Py) can
+/// be moved out of the closure given to [`with_gil`](PySubinterpreter::with_gil), which therefore
+/// is `unsafe`, keeping objects in their interpreter is up to the caller.
+///
+/// The values stored by the subinterpreter in [`InterpreterOnceCell`](crate::sync::InterpreterOnceCell)s,
+/// such as the type objects of `#[pyclass]` types, are never freed, see its documentation.
+///
+/// # Examples
+///
+/// ```rust
+/// use pyo3::prelude::*;
+///
+/// # fn main() -> PyResult<()> {
+/// Python::with_gil(|py| -> PyResult<()> {
+///     py.run(pyo3::ffi::c_str!("import sys; sys.answer = 42"), None, None)?;
+///
+///     let interpreter = py.new_subinterpreter()?;
+///     // SAFETY: no Python object leaves the closure
+///     let has_answer = unsafe {
+///         interpreter.with_gil(|py| -> PyResult<bool> { py.import("sys")?.hasattr("answer") })
+///     }?;
+///     assert!(!has_answer);
+///     Ok(())
+/// })
+/// # }
+/// ```
+#[cfg(all(
+    not(any(PyPy, GraalPy)),
+    Py_3_9,
+    not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+))]
+pub struct PySubinterpreter {
+    /// Thread state of the subinterpreter, bound to the thread which created it.
+    tstate: NonNull<ffi::PyThreadState>,
+    id: i64,
+    // The thread state can only be used by the thread which created it.
+    _not_send: PhantomData<*mut ffi::PyThreadState>,
+}
+
+#[cfg(all(
+    not(any(PyPy, GraalPy)),
+    Py_3_9,
+    not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+))]
+impl PySubinterpreter {
+    /// Creates a new subinterpreter.
+    ///
+    /// See also [`Python::new_subinterpreter`].
+    pub fn new(py: Python<'_>) -> PyResult<PySubinterpreter> {
+        unsafe {
+            let previous = ffi::PyThreadState_Get();
+            // Creates the interpreter and makes its thread state current
+            let tstate = NonNull::new(ffi::Py_NewInterpreter());
+            let id = tstate.map(|_| register_interpreter(py));
+            ffi::PyThreadState_Swap(previous);
+            match (tstate, id) {
+                (Some(tstate), Some(id)) => Ok(PySubinterpreter {
+                    tstate,
+                    id,
+                    _not_send: PhantomData,
+                }),
+                // No exception is set on failure, as there may be no current thread state
+                _ => Err(PyRuntimeError::new_err("failed to create a subinterpreter")),
+            }
+        }
+    }
+
+    /// Returns the ID of the subinterpreter.
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    /// Acquires the GIL and runs `f` in the subinterpreter.
+    ///
+    /// The `Python` token given to `f` belongs to the subinterpreter. The `Ungil` bounds prevent
+    /// borrowed Python objects of the calling interpreter from being used in `f`, and borrowed
+    /// objects of the subinterpreter from being returned.
+    ///
+    /// # Safety
+    ///
+    /// Owned Python objects, such as [`Py<T>`](crate::Py) and [`PyErr`](crate::PyErr), are not
+    /// tied to an interpreter by the type system. The caller must ensure that:
+    /// - `f` does not use objects of other interpreters, including ones captured by `f`.
+    /// - objects of the subinterpreter do not leave `f`, whether returned or stored elsewhere,
+    ///   except in [`InterpreterOnceCell`](crate::sync::InterpreterOnceCell)s. Otherwise they
+    ///   could be used or dropped outside of their interpreter, or after it has ended.
+    pub unsafe fn with_gil<F, R>(&self, f: F) -> R
+    where
+        F: for<'py> FnOnce(Python<'py>) -> R + Ungil,
+        R: Ungil,
+    {
+        Python::with_gil(|_| {
+            struct RestoreThreadState(*mut ffi::PyThreadState);
+
+            impl Drop for RestoreThreadState {
+                fn drop(&mut self) {
+                    unsafe { ffi::PyThreadState_Swap(self.0) };
+                }
+            }
+
+            // SAFETY: the subinterpreter shares the GIL, which is held, and its thread state
+            // belongs to the current thread.
+            let _restore = RestoreThreadState(ffi::PyThreadState_Swap(self.tstate.as_ptr()));
+            f(Python::assume_gil_acquired())
+        })
+    }
+}
+
+#[cfg(all(
+    not(any(PyPy, GraalPy)),
+    Py_3_9,
+    not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+))]
+impl Drop for PySubinterpreter {
+    fn drop(&mut self) {
+        Python::with_gil(|_| unsafe {
+            let previous = ffi::PyThreadState_Swap(self.tstate.as_ptr());
+            // Leaves no current thread state
+            ffi::Py_EndInterpreter(self.tstate.as_ptr());
+            ffi::PyThreadState_Swap(previous);
+        })
+    }
+}
@@ -834,6 +834,18 @@ impl<'py> Python<'py> {
     pub fn check_signals(self) -> PyResult<()> {
         err::error_on_minusone(self, unsafe { ffi::PyErr_CheckSignals() })
     }
+
+    /// Creates a new Python subinterpreter, sharing the GIL of the current interpreter.
+    ///
+    /// See [`PySubinterpreter`](crate::interpreter::PySubinterpreter) for details.
+    #[cfg(all(
+        not(any(PyPy, GraalPy)),
+        Py_3_9,
+        not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+    ))]
+    pub fn new_subinterpreter(self) -> PyResult<crate::interpreter::PySubinterpreter> {
+        crate::interpreter::PySubinterpreter::new(self)
+    }
 }
 
 impl<'unbound> Python<'unbound> {
@@ -1,7 +1,25 @@
 //! Helper to convert Rust panics to Python exceptions.
+//!
+//! When Rust code called from Python panics, PyO3 raises a [`PanicException`] by default. What
+//! happens instead can be configured for each module with a [`PanicPolicy`], using the
+//! `panic_policy` option of [`#[pymodule]`](crate::pymodule).
+//!
+//! When backtraces are enabled with the `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` environment
+//! variables, the `PanicException` carries the Rust backtrace of the panic in its `backtrace`
+//! attribute and in a note, so that it is shown in the Python traceback.
+//!
+//! To capture the backtrace, PyO3 replaces the panic hook of the process when the first
+//! `#[pymodule]` is initialized with backtraces enabled. The new hook captures the backtrace and
+//! then calls the previous hook, so panics are still reported as before, but a hook set later
+//! with [`std::panic::set_hook`] replaces it. Applications embedding Python which manage the
+//! panic hook themselves can prevent this with [`disable_backtrace_hook`].
 use crate::exceptions::PyBaseException;
-use crate::PyErr;
+use crate::types::PyAnyMethods;
+use crate::{PyErr, Python};
 use std::any::Any;
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(rustc_has_backtrace)]
+use std::{backtrace::Backtrace, cell::RefCell};
 
 pyo3_exception!(
     "
@@ -10,17 +28,124 @@ The exception raised when Rust code called from Python panics.
 Like SystemExit, this exception is derived from BaseException so that
 it will typically propagate all the way through the stack and cause the
 Python interpreter to exit.
+
+The `backtrace` attribute of the exception is the Rust backtrace of the
+panic, or `None` when backtraces are not enabled.
 ",
     PanicException,
     PyBaseException
 );
 
+/// What PyO3 does when Rust code called from Python panics.
+///
+/// The policy of a module is set with the `panic_policy` option of
+/// [`#[pymodule]`](crate::pymodule). It applies to the panics of the functions of the module, of
+/// the coroutines they return, and, on CPython 3.9 and up (3.10 and up with the `abi3` feature),
+/// of the methods of the classes added to the module with
+/// [`add_class`](crate::types::PyModuleMethods::add_class), provided that the class was first
+/// added to that module. Submodules have their own policy.
+///
+/// Panics of other code, for example of functions which are not added to a module or of
+/// `Drop` implementations, are raised as [`PanicException`].
+///
+/// # Examples
+///
+/// ```rust
+/// # #![allow(dead_code)]
+/// use pyo3::panic::PanicPolicy;
+/// use pyo3::prelude::*;
+///
+/// fn log_panic(py: Python<'_>, err: PyErr) -> PyErr {
+///     eprintln!("panic in Rust code: {}", err.value(py));
+///     err
+/// }
+///
+/// #[pymodule(panic_policy = PanicPolicy::Custom(log_panic))]
+/// fn my_module(_m: &Bound<'_, PyModule>) -> PyResult<()> {
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+#[non_exhaustive]
+pub enum PanicPolicy {
+    /// Raise a [`PanicException`]. This is the default policy.
+    #[default]
+    Raise,
+    /// Abort the process, after the panic hook has reported the panic.
+    Abort,
+    /// Raise the error returned by the function, which is given the [`PanicException`] of the
+    /// panic. This can for example be used to log panics, or to convert them to another exception.
+    Custom(for<'py> fn(Python<'py>, PyErr) -> PyErr),
+}
+
+/// Whether [`disable_backtrace_hook`] has been called.
+static BACKTRACE_HOOK_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Prevents PyO3 from installing its panic hook, which captures the backtraces of panics.
+///
+/// This must be called before the first `#[pymodule]` is initialized, as the hook is installed
+/// then if backtraces are enabled; it has no effect on a hook which is already installed.
+/// `PanicException`s then have no backtrace.
+pub fn disable_backtrace_hook() {
+    BACKTRACE_HOOK_DISABLED.store(true, Ordering::Relaxed);
+}
+
+#[cfg(rustc_has_backtrace)]
+thread_local! {
+    /// Backtrace of the last panic of the thread, taken when the panic is converted.
+    static PANIC_BACKTRACE: RefCell<Option<Backtrace>> = const { RefCell::new(None) };
+}
+
+/// Installs a panic hook capturing the backtrace of panics, if backtraces are enabled by the
+/// environment and the hook has not been disabled. The previous panic hook is still called.
+pub(crate) fn install_backtrace_hook() {
+    #[cfg(rustc_has_backtrace)]
+    {
+        if BACKTRACE_HOOK_DISABLED.load(Ordering::Relaxed) {
+            return;
+        }
+        static INSTALL: std::sync::Once = std::sync::Once::new();
+        INSTALL.call_once(|| {
+            let enabled = std::env::var_os("RUST_LIB_BACKTRACE")
+                .or_else(|| std::env::var_os("RUST_BACKTRACE"))
+                .map_or(false, |value| value != "0");
+            if !enabled {
+                return;
+            }
+            let previous = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                let backtrace = Backtrace::force_capture();
+                let _ = PANIC_BACKTRACE.try_with(|cell| {
+                    if let Ok(mut slot) = cell.try_borrow_mut() {
+                        *slot = Some(backtrace);
+                    }
+                });
+                previous(info);
+            }));
+        });
+    }
+}
+
+/// Returns the backtrace captured for the last panic of the current thread, if any.
+fn take_backtrace() -> Option<String> {
+    #[cfg(rustc_has_backtrace)]
+    {
+        PANIC_BACKTRACE
+            .try_with(|cell| cell.try_borrow_mut().ok()?.take())
+            .ok()
+            .flatten()
+            .map(|backtrace| backtrace.to_string())
+    }
+    #[cfg(not(rustc_has_backtrace))]
+    {
+        None
+    }
+}
+
 impl PanicException {
-    /// Creates a new PanicException from a panic payload.
-    ///
-    /// Attempts to format the error in the same way panic does.
-    #[cold]
-    pub(crate) fn from_panic_payload(payload: Box<dyn Any + Send + 'static>) -> PyErr {
+    /// Creates a new PanicException with the message of a panic payload, without applying the
+    /// [`PanicPolicy`], for panics which are resumed after the exception is handled.
+    pub(crate) fn new_from_payload(payload: &(dyn Any + Send + 'static)) -> PyErr {
         if let Some(string) = payload.downcast_ref::<String>() {
             Self::new_err((string.clone(),))
         } else if let Some(s) = payload.downcast_ref::<&str>() {
@@ -29,4 +154,33 @@ impl PanicException {
             Self::new_err(("panic from Rust code",))
         }
     }
+
+    /// Creates a new PanicException from a panic payload, and applies `policy`, the
+    /// [`PanicPolicy`] of the module of the code which panicked.
+    ///
+    /// Attempts to format the error in the same way panic does.
+    #[cold]
+    pub(crate) fn from_panic_payload(
+        py: Python<'_>,
+        payload: Box<dyn Any + Send + 'static>,
+        policy: PanicPolicy,
+    ) -> PyErr {
+        if let PanicPolicy::Abort = policy {
+            eprintln!("Rust code called from Python panicked, aborting");
+            std::process::abort();
+        }
+        let err = Self::new_from_payload(&*payload);
+        // Failing to attach the backtrace should not hide the panic
+        let backtrace = take_backtrace();
+        let _ = err
+            .value(py)
+            .setattr(crate::intern!(py, "backtrace"), backtrace.as_deref());
+        if let Some(backtrace) = backtrace {
+            let _ = err.add_note(py, &format!("Rust backtrace:\n{}", backtrace));
+        }
+        match policy {
+            PanicPolicy::Custom(hook) => hook(py, err),
+            _ => err,
+        }
+    }
 }
@@ -9,7 +9,9 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use crate::impl_::pyclass::{
     PyClassBaseType, PyClassDict, PyClassImpl, PyClassThreadChecker, PyClassWeakRef,
 };
-use crate::internal::get_slot::TP_FREE;
+use crate::impl_::pyclass_init::native_base_of_python_class;
+use crate::internal::get_slot::{TP_BASE, TP_CLEAR, TP_DEALLOC, TP_FREE};
+use crate::internal_tricks::{ptr_from_mut, ptr_from_ref};
 use crate::type_object::{PyLayout, PySizedLayout};
 use crate::types::{PyType, PyTypeMethods};
 use crate::{ffi, PyClass, PyTypeInfo, Python};
@@ -183,13 +185,13 @@ pub trait GetBorrowChecker<T: PyClassImpl> {
 
 impl<T: PyClassImpl<PyClassMutability = Self>> GetBorrowChecker<T> for MutableClass {
     fn borrow_checker(class_object: &PyClassObject<T>) -> &BorrowChecker {
-        &class_object.contents.borrow_checker
+        &class_object.contents().borrow_checker
     }
 }
 
 impl<T: PyClassImpl<PyClassMutability = Self>> GetBorrowChecker<T> for ImmutableClass {
     fn borrow_checker(class_object: &PyClassObject<T>) -> &EmptySlot {
-        &class_object.contents.borrow_checker
+        &class_object.contents().borrow_checker
     }
 }
 
@@ -249,27 +251,19 @@ where
         }
 
         // More complex native types (e.g. `extends=PyDict`) require calling the base's dealloc.
-        #[cfg(not(Py_LIMITED_API))]
-        {
-            // FIXME: should this be using actual_type.tp_dealloc?
-            if let Some(dealloc) = (*type_ptr).tp_dealloc {
-                // Before CPython 3.11 BaseException_dealloc would use Py_GC_UNTRACK which
-                // assumes the exception is currently GC tracked, so we have to re-track
-                // before calling the dealloc so that it can safely call Py_GC_UNTRACK.
-                #[cfg(not(any(Py_3_11, PyPy)))]
-                if ffi::PyType_FastSubclass(type_ptr, ffi::Py_TPFLAGS_BASE_EXC_SUBCLASS) == 1 {
-                    ffi::PyObject_GC_Track(slf.cast());
-                }
-                dealloc(slf);
-            } else {
-                (*actual_type.as_type_ptr())
-                    .tp_free
-                    .expect("type missing tp_free")(slf.cast());
+        // FIXME: should this be using actual_type.tp_dealloc?
+        if let Some(dealloc) = type_obj.get_slot(TP_DEALLOC) {
+            // Before CPython 3.11 BaseException_dealloc would use Py_GC_UNTRACK which
+            // assumes the exception is currently GC tracked, so we have to re-track
+            // before calling the dealloc so that it can safely call Py_GC_UNTRACK.
+            #[cfg(not(any(Py_3_11, PyPy)))]
+            if ffi::PyType_FastSubclass(type_ptr, ffi::Py_TPFLAGS_BASE_EXC_SUBCLASS) == 1 {
+                ffi::PyObject_GC_Track(slf.cast());
             }
+            dealloc(slf);
+        } else {
+            actual_type.get_slot(TP_FREE).expect("type missing tp_free")(slf.cast());
         }
-
-        #[cfg(Py_LIMITED_API)]
-        unreachable!("subclassing native types is not possible with the `abi3` feature");
     }
 }
 
@@ -291,27 +285,70 @@ pub(crate) struct PyClassObjectContents<T: PyClassImpl> {
 
 impl<T: PyClassImpl> PyClassObject<T> {
     pub(crate) fn get_ptr(&self) -> *mut T {
-        self.contents.value.get()
+        self.contents().value.get()
+    }
+
+    /// Gets the offset of the contents from the start of the object in bytes.
+    ///
+    /// Classes extending a Python class place their contents after the size of the base, which
+    /// is only known once the type object has been created.
+    pub(crate) fn contents_offset() -> usize {
+        if T::DYNAMIC_LAYOUT {
+            // Safety: instances of `T` are only accessed with the GIL held
+            let py = unsafe { Python::assume_gil_acquired() };
+            T::lazy_type_object().dynamic_layout(py).contents_offset
+        } else {
+            memoffset::offset_of!(PyClassObject<T>, contents)
+        }
+    }
+
+    /// Gets the basic size of instances of the class in bytes.
+    pub(crate) fn basic_size() -> usize {
+        if T::DYNAMIC_LAYOUT {
+            // Safety: instances of `T` are only created with the GIL held
+            let py = unsafe { Python::assume_gil_acquired() };
+            T::lazy_type_object().dynamic_layout(py).basic_size
+        } else {
+            std::mem::size_of::<PyClassObject<T>>()
+        }
+    }
+
+    /// Gets a pointer to the contents of `obj`, which may not be initialized yet.
+    ///
+    /// # Safety
+    /// `obj` must point to an instance of `T` or a subclass.
+    pub(crate) unsafe fn contents_ptr(obj: *mut ffi::PyObject) -> *mut PyClassObjectContents<T> {
+        obj.cast::<u8>()
+            .add(Self::contents_offset())
+            .cast::<PyClassObjectContents<T>>()
+    }
+
+    pub(crate) fn contents(&self) -> &PyClassObjectContents<T> {
+        // Safety: `self` is an instance of `T` or a subclass.
+        unsafe { &*Self::contents_ptr(ptr_from_ref(self) as *mut ffi::PyObject) }
     }
 
-    /// Gets the offset of the dictionary from the start of the struct in bytes.
+    fn contents_mut(&mut self) -> &mut PyClassObjectContents<T> {
+        // Safety: `self` is an instance of `T` or a subclass.
+        unsafe { &mut *Self::contents_ptr(ptr_from_mut(self).cast()) }
+    }
+
+    /// Gets the offset of the dictionary from the start of the contents in bytes.
     pub(crate) fn dict_offset() -> ffi::Py_ssize_t {
         use memoffset::offset_of;
 
-        let offset =
-            offset_of!(PyClassObject<T>, contents) + offset_of!(PyClassObjectContents<T>, dict);
+        let offset = offset_of!(PyClassObjectContents<T>, dict);
 
         // Py_ssize_t may not be equal to isize on all platforms
         #[allow(clippy::useless_conversion)]
         offset.try_into().expect("offset should fit in Py_ssize_t")
     }
 
-    /// Gets the offset of the weakref list from the start of the struct in bytes.
+    /// Gets the offset of the weakref list from the start of the contents in bytes.
     pub(crate) fn weaklist_offset() -> ffi::Py_ssize_t {
         use memoffset::offset_of;
 
-        let offset =
-            offset_of!(PyClassObject<T>, contents) + offset_of!(PyClassObjectContents<T>, weakref);
+        let offset = offset_of!(PyClassObjectContents<T>, weakref);
 
         // Py_ssize_t may not be equal to isize on all platforms
         #[allow(clippy::useless_conversion)]
@@ -333,11 +370,11 @@ where
     <T::BaseType as PyClassBaseType>::LayoutAsBase: PyClassObjectLayout<T::BaseType>,
 {
     fn ensure_threadsafe(&self) {
-        self.contents.thread_checker.ensure();
+        self.contents().thread_checker.ensure();
         self.ob_base.ensure_threadsafe();
     }
     fn check_threadsafe(&self) -> Result<(), PyBorrowError> {
-        if !self.contents.thread_checker.check() {
+        if !self.contents().thread_checker.check() {
             return Err(PyBorrowError { _private: () });
         }
         self.ob_base.check_threadsafe()
@@ -345,13 +382,64 @@ where
     unsafe fn tp_dealloc(py: Python<'_>, slf: *mut ffi::PyObject) {
         // Safety: Python only calls tp_dealloc when no references to the object remain.
         let class_object = &mut *(slf.cast::<PyClassObject<T>>());
-        if class_object.contents.thread_checker.can_drop(py) {
-            ManuallyDrop::drop(&mut class_object.contents.value);
+        let contents = class_object.contents_mut();
+        if contents.thread_checker.can_drop(py) {
+            ManuallyDrop::drop(&mut contents.value);
         }
-        class_object.contents.dict.clear_dict(py);
-        class_object.contents.weakref.clear_weakrefs(slf, py);
-        <T::BaseType as PyClassBaseType>::LayoutAsBase::tp_dealloc(py, slf)
+        contents.dict.clear_dict(py);
+        contents.weakref.clear_weakrefs(slf, py);
+        if T::PYTHON_BASE.is_some() {
+            python_base_dealloc::<T>(py, slf)
+        } else {
+            <T::BaseType as PyClassBaseType>::LayoutAsBase::tp_dealloc(py, slf)
+        }
+    }
+}
+
+/// Deallocates the part of `slf` belonging to the Python class `T` extends, which
+/// `subtype_dealloc` can't do because it starts from the type of `slf`.
+///
+/// # Safety
+/// `slf` must be an instance of `T` whose contents have been dropped, and must not be used
+/// after this call.
+unsafe fn python_base_dealloc<T: PyClassImpl>(py: Python<'_>, slf: *mut ffi::PyObject) {
+    let type_obj = T::lazy_type_object()
+        .get(py)
+        .expect("type object should have been created");
+    let base = PyType::from_borrowed_type_ptr(py, type_obj.get_slot(TP_BASE));
+
+    if T::lazy_type_object()
+        .dynamic_layout(py)
+        .python_base_has_weaklist
+    {
+        ffi::PyObject_ClearWeakRefs(slf);
+    }
+
+    // Clears the `__slots__` and `__dict__` of the Python classes in the hierarchy.
+    if let Some(clear) = base.get_slot(TP_CLEAR) {
+        clear(slf);
+    }
+
+    let native = native_base_of_python_class(&base);
+    let native_ptr = native.as_type_ptr();
+    if native_ptr == std::ptr::addr_of_mut!(ffi::PyBaseObject_Type) {
+        let actual_type = PyType::from_borrowed_type_ptr(py, ffi::Py_TYPE(slf));
+        let tp_free = actual_type
+            .get_slot(TP_FREE)
+            .expect("PyBaseObject_Type should have tp_free");
+        return tp_free(slf.cast());
+    }
+
+    // The native dealloc may expect the object to still be tracked by the GC, as
+    // `subtype_dealloc` does.
+    #[cfg(not(PyPy))]
+    if ffi::PyType_IS_GC(native_ptr) == 1 {
+        ffi::PyObject_GC_Track(slf.cast());
     }
+    let dealloc = native
+        .get_slot(TP_DEALLOC)
+        .expect("native types should have tp_dealloc");
+    dealloc(slf)
 }
 
 #[cfg(test)]
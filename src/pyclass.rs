@@ -5,7 +5,12 @@ use std::{cmp::Ordering, os::raw::c_int};
 mod create_type_object;
 mod gc;
 
-pub(crate) use self::create_type_object::{create_type_object, PyClassTypeObject};
+#[cfg(all(
+    any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+    not(any(PyPy, GraalPy))
+))]
+pub(crate) use self::create_type_object::create_module_type_object;
+pub(crate) use self::create_type_object::{create_type_object, DynamicLayout, PyClassTypeObject};
 
 pub use self::gc::{PyTraverseError, PyVisit};
 
@@ -4,15 +4,17 @@ use crate::{
     impl_::{
         pycell::PyClassObject,
         pyclass::{
-            assign_sequence_item_from_mapping, get_sequence_item_from_mapping, tp_dealloc,
-            tp_dealloc_with_gc, MaybeRuntimePyMethodDef, PyClassItemsIter,
+            assign_sequence_item_from_mapping, get_sequence_item_from_mapping, import_type,
+            tp_dealloc, tp_dealloc_with_gc, MaybeRuntimePyMethodDef, PyClassItemsIter,
         },
-        pymethods::{Getter, PyGetterDef, PyMethodDefType, PySetterDef, Setter, _call_clear},
+        pyclass_init::native_base_of_python_class,
+        pymethods::{_call_clear, Getter, PyGetterDef, PyMethodDefType, PySetterDef, Setter},
         trampoline::trampoline,
     },
     internal_tricks::ptr_from_ref,
-    types::{typeobject::PyTypeMethods, PyType},
-    Py, PyClass, PyResult, PyTypeInfo, Python,
+    pycell::impl_::PyClassObjectContents,
+    types::{typeobject::PyTypeMethods, PyAnyMethods, PyType},
+    Bound, Py, PyClass, PyResult, PyTypeInfo, Python,
 };
 use std::{
     collections::HashMap,
@@ -23,11 +25,50 @@ use std::{
 
 pub(crate) struct PyClassTypeObject {
     pub type_object: Py<PyType>,
+    /// Layout of instances of a class with a dynamic layout, which depends on the base type
+    /// object of the interpreter.
+    pub dynamic_layout: Option<DynamicLayout>,
     #[allow(dead_code)] // This is purely a cache that must live as long as the type object
     getset_destructors: Vec<GetSetDefDestructor>,
 }
 
+/// Layout of instances of a class extending a Python class, or a native type with the `abi3`
+/// feature, which is only known at runtime.
+pub(crate) struct DynamicLayout {
+    /// Offset of the contents from the start of the object in bytes.
+    pub contents_offset: usize,
+    /// Basic size of instances in bytes.
+    pub basic_size: usize,
+    /// Whether the Python class extended by the class supports weak references.
+    pub python_base_has_weaklist: bool,
+}
+
 pub(crate) fn create_type_object<T>(py: Python<'_>) -> PyResult<PyClassTypeObject>
+where
+    T: PyClass,
+{
+    create_type_object_in_module::<T>(py, ptr::null_mut())
+}
+
+/// Creates a type object for `T` associated with `module`, see `PyType_FromModuleAndSpec`.
+#[cfg(all(
+    any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+    not(any(PyPy, GraalPy))
+))]
+pub(crate) fn create_module_type_object<T>(
+    module: &Bound<'_, crate::types::PyModule>,
+) -> PyResult<PyClassTypeObject>
+where
+    T: PyClass,
+{
+    create_type_object_in_module::<T>(module.py(), module.as_ptr())
+}
+
+/// Creates a type object for `T`, associated with `module` unless it is null.
+fn create_type_object_in_module<T>(
+    py: Python<'_>,
+    module: *mut ffi::PyObject,
+) -> PyResult<PyClassTypeObject>
 where
     T: PyClass,
 {
@@ -46,10 +87,19 @@ where
         is_basetype: bool,
         items_iter: PyClassItemsIter,
         name: &'static str,
-        module: Option<&'static str>,
+        module_name: Option<&'static str>,
+        module: *mut ffi::PyObject,
+        metaclass: Option<Bound<'_, PyType>>,
+        dynamic_layout: bool,
         size_of: usize,
+        #[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))] new_vectorcall: Option<
+            ffi::vectorcallfunc,
+        >,
+        #[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))] call_vectorcall: Option<
+            ffi::vectorcallfunc,
+        >,
     ) -> PyResult<PyClassTypeObject> {
-        PyTypeBuilder {
+        let builder = PyTypeBuilder {
             slots: Vec::new(),
             method_defs: Vec::new(),
             member_defs: Vec::new(),
@@ -73,29 +123,127 @@ where
         }
         .type_doc(doc)
         .offsets(dict_offset, weaklist_offset)
-        .set_is_basetype(is_basetype)
-        .class_items(items_iter)
-        .build(py, name, module, size_of)
+        .set_is_basetype(is_basetype);
+
+        #[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))]
+        let (builder, size_of) = builder.vectorcall(new_vectorcall, call_vectorcall, size_of);
+
+        let builder = builder.class_items(items_iter);
+        if dynamic_layout {
+            builder.check_dynamic_layout(name)?;
+        }
+        builder.build(py, name, module_name, module, metaclass, size_of)
     }
 
-    unsafe {
+    let base = match T::PYTHON_BASE {
+        Some(path) => import_type(py, path)?,
+        None => T::BaseType::type_object(py),
+    };
+    if T::PYTHON_BASE.is_some() {
+        check_python_base(T::NAME, &base)?;
+    }
+    let dynamic_layout = if T::DYNAMIC_LAYOUT {
+        if T::EXTENDS_PYTHON_CLASS && (T::dict_offset().is_some() || T::weaklist_offset().is_some())
+        {
+            return Err(PyTypeError::new_err(format!(
+                "`#[pyclass]` {} extends a Python class and can't use `dict` or `weakref`",
+                T::NAME
+            )));
+        }
+        Some(dynamic_layout::<T>(&base)?)
+    } else {
+        None
+    };
+    // the layout of the type object being created is not stored in its `LazyTypeObject` yet
+    let (contents_offset, basic_size) = match &dynamic_layout {
+        Some(layout) => (layout.contents_offset, layout.basic_size),
+        None => (
+            PyClassObject::<T>::contents_offset(),
+            PyClassObject::<T>::basic_size(),
+        ),
+    };
+    // Py_ssize_t may not be equal to isize on all platforms
+    #[allow(clippy::useless_conversion)]
+    let contents_offset: ffi::Py_ssize_t = contents_offset
+        .try_into()
+        .expect("offset should fit in Py_ssize_t");
+
+    let mut type_object = unsafe {
         inner(
             py,
-            T::BaseType::type_object_raw(py),
+            base.as_type_ptr(),
             tp_dealloc::<T>,
             tp_dealloc_with_gc::<T>,
             T::IS_MAPPING,
             T::IS_SEQUENCE,
             T::doc(py)?,
-            T::dict_offset(),
-            T::weaklist_offset(),
+            T::dict_offset().map(|offset| contents_offset + offset),
+            T::weaklist_offset().map(|offset| contents_offset + offset),
             T::IS_BASETYPE,
             T::items_iter(),
             T::NAME,
             T::MODULE,
-            std::mem::size_of::<PyClassObject<T>>(),
+            module,
+            T::metaclass(py)?,
+            T::DYNAMIC_LAYOUT,
+            basic_size,
+            #[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))]
+            T::new_vectorcall(),
+            #[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))]
+            T::call_vectorcall(),
         )
+    }?;
+    type_object.dynamic_layout = dynamic_layout;
+
+    #[cfg(feature = "experimental-inspect")]
+    crate::impl_::introspection::register_class::<T>(type_object.type_object.as_ptr().cast());
+
+    Ok(type_object)
+}
+
+/// Places the contents of `T` after the runtime size of `base`, which isn't known at compile
+/// time when `T` extends a Python class, or a native type with the `abi3` feature.
+fn dynamic_layout<T: PyClass>(base: &Bound<'_, PyType>) -> PyResult<DynamicLayout> {
+    let py = base.py();
+    let base_size: usize = base.getattr(intern!(py, "__basicsize__"))?.extract()?;
+    let align = std::mem::align_of::<PyClassObjectContents<T>>();
+    let contents_offset = (base_size + align - 1) / align * align;
+    let python_base_has_weaklist = T::PYTHON_BASE.is_some()
+        && base
+            .getattr(intern!(py, "__weakrefoffset__"))?
+            .extract::<ffi::Py_ssize_t>()?
+            != 0;
+    Ok(DynamicLayout {
+        contents_offset,
+        basic_size: contents_offset + std::mem::size_of::<PyClassObjectContents<T>>(),
+        python_base_has_weaklist,
+    })
+}
+
+/// Checks that instances of a class extending the Python class `base` can be created with the
+/// `tp_new` of its closest native ancestor.
+fn check_python_base(name: &str, base: &Bound<'_, PyType>) -> PyResult<()> {
+    let py = base.py();
+    let native = native_base_of_python_class(base);
+    if !base
+        .getattr(intern!(py, "__new__"))?
+        .is(&native.getattr(intern!(py, "__new__"))?)
+    {
+        return Err(PyTypeError::new_err(format!(
+            "`#[pyclass]` {} can't extend {}, which defines `__new__`",
+            name, base
+        )));
     }
+
+    #[cfg(all(Py_LIMITED_API, not(Py_3_12)))]
+    if !native.is(&crate::PyAny::type_object(py)) {
+        return Err(PyTypeError::new_err(format!(
+            "`#[pyclass]` {} can't extend {}, subclassing native types with the `abi3` feature requires Python 3.12 or greater",
+            name, base
+        )));
+    }
+
+    Ok(())
 }
 
 type PyTypeBuilderCleanup = Box<dyn Fn(&PyTypeBuilder, *mut ffi::PyTypeObject)>;
@@ -185,6 +333,7 @@ impl PyTypeBuilder {
                 .or_default()
                 .add_setter(setter),
             PyMethodDefType::Method(def)
+            | PyMethodDefType::AbstractMethod(def)
             | PyMethodDefType::Class(def)
             | PyMethodDefType::Static(def) => self.method_defs.push(def.as_method_def()),
             // These class attributes are added after the type gets created by LazyStaticType
@@ -238,20 +387,23 @@ impl PyTypeBuilder {
                     closure: *mut c_void,
                 ) -> *mut ffi::PyObject {
                     unsafe {
-                        trampoline(|_| {
-                            let dict_offset = closure as ffi::Py_ssize_t;
-                            // we don't support negative dict_offset here; PyO3 doesn't set it negative
-                            assert!(dict_offset > 0);
-                            // TODO: use `.byte_offset` on MSRV 1.75
-                            let dict_ptr = object
-                                .cast::<u8>()
-                                .offset(dict_offset)
-                                .cast::<*mut ffi::PyObject>();
-                            if (*dict_ptr).is_null() {
-                                std::ptr::write(dict_ptr, ffi::PyDict_New());
-                            }
-                            Ok(ffi::compat::Py_XNewRef(*dict_ptr))
-                        })
+                        trampoline(
+                            |_| {
+                                let dict_offset = closure as ffi::Py_ssize_t;
+                                // we don't support negative dict_offset here; PyO3 doesn't set it negative
+                                assert!(dict_offset > 0);
+                                // TODO: use `.byte_offset` on MSRV 1.75
+                                let dict_ptr = object
+                                    .cast::<u8>()
+                                    .offset(dict_offset)
+                                    .cast::<*mut ffi::PyObject>();
+                                if (*dict_ptr).is_null() {
+                                    std::ptr::write(dict_ptr, ffi::PyDict_New());
+                                }
+                                Ok(ffi::compat::Py_XNewRef(*dict_ptr))
+                            },
+                            object,
+                        )
                     }
                 }
 
@@ -412,11 +564,57 @@ impl PyTypeBuilder {
         self
     }
 
+    /// Sets the vectorcall implementations used to call the type object and its instances.
+    ///
+    /// Instances store their vectorcall pointer right after the class layout of size `size_of`,
+    /// which is written when the object is created. Returns the basic size of the type.
+    #[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))]
+    fn vectorcall(
+        mut self,
+        new_vectorcall: Option<ffi::vectorcallfunc>,
+        call_vectorcall: Option<ffi::vectorcallfunc>,
+        size_of: usize,
+    ) -> (Self, usize) {
+        if let Some(new_vectorcall) = new_vectorcall {
+            // There is no slot for `tp_vectorcall` until Python 3.14.
+            self.cleanup
+                .push(Box::new(move |_self, type_object| unsafe {
+                    (*type_object).tp_vectorcall = Some(new_vectorcall);
+                }));
+        }
+
+        if call_vectorcall.is_none() {
+            return (self, size_of);
+        }
+        self.class_flags |= ffi::Py_TPFLAGS_HAVE_VECTORCALL;
+        self.member_defs.push(ffi::PyMemberDef {
+            name: ffi::c_str!("__vectorcalloffset__").as_ptr().cast(),
+            type_code: ffi::Py_T_PYSSIZET,
+            offset: size_of as ffi::Py_ssize_t,
+            flags: ffi::Py_READONLY,
+            doc: std::ptr::null_mut(),
+        });
+        (self, size_of + std::mem::size_of::<ffi::vectorcallfunc>())
+    }
+
+    /// Rejects the features which rely on the layout of the base class being known.
+    fn check_dynamic_layout(&self, name: &str) -> PyResult<()> {
+        if self.has_traverse || self.has_clear {
+            return Err(PyTypeError::new_err(format!(
+                "`#[pyclass]` {} extends a Python class and can't implement __traverse__ or __clear__",
+                name
+            )));
+        }
+        Ok(())
+    }
+
     fn build(
         mut self,
         py: Python<'_>,
         name: &'static str,
         module_name: Option<&'static str>,
+        module: *mut ffi::PyObject,
+        metaclass: Option<Bound<'_, PyType>>,
         basicsize: usize,
     ) -> PyResult<PyClassTypeObject> {
         // `c_ulong` and `c_uint` have the same size
@@ -488,9 +686,27 @@ impl PyTypeBuilder {
             slots: self.slots.as_mut_ptr(),
         };
 
-        // Safety: We've correctly setup the PyType_Spec at this point
-        let type_object: Py<PyType> =
-            unsafe { Py::from_owned_ptr_or_err(py, ffi::PyType_FromSpec(&mut spec))? };
+        let type_object = match metaclass {
+            // Safety: We've correctly setup the PyType_Spec at this point
+            None => unsafe { type_from_module_and_spec(module, &mut spec) },
+            #[cfg(Py_3_12)]
+            Some(metaclass) => unsafe {
+                ffi::PyType_FromMetaclass(
+                    metaclass.as_type_ptr(),
+                    module,
+                    &mut spec,
+                    ptr::null_mut(),
+                )
+            },
+            #[cfg(not(Py_3_12))]
+            Some(_) => {
+                return Err(PyTypeError::new_err(format!(
+                    "`#[pyclass]` {} requires Python 3.12 or later to use a metaclass",
+                    name
+                )))
+            }
+        };
+        let type_object: Py<PyType> = unsafe { Py::from_owned_ptr_or_err(py, type_object)? };
 
         #[cfg(not(Py_3_11))]
         bpo_45315_workaround(py, class_name);
@@ -501,11 +717,28 @@ impl PyTypeBuilder {
 
         Ok(PyClassTypeObject {
             type_object,
+            dynamic_layout: None,
             getset_destructors,
         })
     }
 }
 
+/// `PyType_FromModuleAndSpec`, or `PyType_FromSpec` when `module` is null.
+unsafe fn type_from_module_and_spec(
+    module: *mut ffi::PyObject,
+    spec: *mut ffi::PyType_Spec,
+) -> *mut ffi::PyObject {
+    #[cfg(all(
+        any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+        not(any(PyPy, GraalPy))
+    ))]
+    if !module.is_null() {
+        return ffi::PyType_FromModuleAndSpec(module, spec, ptr::null_mut());
+    }
+    debug_assert!(module.is_null());
+    ffi::PyType_FromSpec(spec)
+}
+
 fn py_class_qualified_name(module_name: Option<&str>, class_name: &str) -> PyResult<CString> {
     Ok(CString::new(format!(
         "{}.{}",
@@ -545,16 +778,19 @@ unsafe extern "C" fn no_constructor_defined(
     _args: *mut ffi::PyObject,
     _kwds: *mut ffi::PyObject,
 ) -> *mut ffi::PyObject {
-    trampoline(|py| {
-        let tpobj = PyType::from_borrowed_type_ptr(py, subtype);
-        let name = tpobj
-            .name()
-            .map_or_else(|_| "<unknown>".into(), |name| name.to_string());
-        Err(crate::exceptions::PyTypeError::new_err(format!(
-            "No constructor defined for {}",
-            name
-        )))
-    })
+    trampoline(
+        |py| {
+            let tpobj = PyType::from_borrowed_type_ptr(py, subtype);
+            let name = tpobj
+                .name()
+                .map_or_else(|_| "<unknown>".into(), |name| name.to_string());
+            Err(crate::exceptions::PyTypeError::new_err(format!(
+                "No constructor defined for {}",
+                name
+            )))
+        },
+        subtype.cast(),
+    )
 }
 
 unsafe extern "C" fn call_super_clear(slf: *mut ffi::PyObject) -> c_int {
@@ -644,7 +880,7 @@ impl GetSetDefType {
                     ) -> *mut ffi::PyObject {
                         // Safety: PyO3 sets the closure when constructing the ffi getter so this cast should always be valid
                         let getter: Getter = std::mem::transmute(closure);
-                        trampoline(|py| getter(py, slf))
+                        trampoline(|py| getter(py, slf), slf)
                     }
                     (Some(getter), None, closure as Getter as _)
                 }
@@ -656,7 +892,7 @@ impl GetSetDefType {
                     ) -> c_int {
                         // Safety: PyO3 sets the closure when constructing the ffi setter so this cast should always be valid
                         let setter: Setter = std::mem::transmute(closure);
-                        trampoline(|py| setter(py, slf, value))
+                        trampoline(|py| setter(py, slf, value), slf)
                     }
                     (None, Some(setter), closure as Setter as _)
                 }
@@ -666,7 +902,7 @@ impl GetSetDefType {
                         closure: *mut c_void,
                     ) -> *mut ffi::PyObject {
                         let getset: &GetterAndSetter = &*closure.cast();
-                        trampoline(|py| (getset.getter)(py, slf))
+                        trampoline(|py| (getset.getter)(py, slf), slf)
                     }
 
                     unsafe extern "C" fn getset_setter(
@@ -675,7 +911,7 @@ impl GetSetDefType {
                         closure: *mut c_void,
                     ) -> c_int {
                         let getset: &GetterAndSetter = &*closure.cast();
-                        trampoline(|py| (getset.setter)(py, slf, value))
+                        trampoline(|py| (getset.setter)(py, slf, value), slf)
                     }
                     (
                         Some(getset_getter),
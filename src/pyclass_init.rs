@@ -7,13 +7,11 @@ use crate::types::PyAnyMethods;
 use crate::{ffi, Bound, Py, PyClass, PyResult, Python};
 use crate::{
     ffi::PyTypeObject,
-    pycell::impl_::{PyClassBorrowChecker, PyClassMutability, PyClassObjectContents},
-};
-use std::{
-    cell::UnsafeCell,
-    marker::PhantomData,
-    mem::{ManuallyDrop, MaybeUninit},
+    pycell::impl_::{
+        PyClassBorrowChecker, PyClassMutability, PyClassObject, PyClassObjectContents,
+    },
 };
+use std::{cell::UnsafeCell, marker::PhantomData, mem::ManuallyDrop};
 
 /// Initializer for our `#[pyclass]` system.
 ///
@@ -165,24 +163,22 @@ impl<T: PyClass> PyClassInitializer<T> {
     where
         T: PyClass,
     {
-        /// Layout of a PyClassObject after base new has been called, but the contents have not yet been
-        /// written.
-        #[repr(C)]
-        struct PartiallyInitializedClassObject<T: PyClass> {
-            _ob_base: <T::BaseType as PyClassBaseType>::LayoutAsBase,
-            contents: MaybeUninit<PyClassObjectContents<T>>,
-        }
-
         let (init, super_init) = match self.0 {
             PyClassInitializerImpl::Existing(value) => return Ok(value.into_bound(py)),
             PyClassInitializerImpl::New { init, super_init } => (init, super_init),
         };
 
-        let obj = super_init.into_new_object(py, target_type)?;
+        let obj = if T::PYTHON_BASE.is_some() {
+            // `super_init` only initializes `PyAny`, which stands in for the Python base in Rust
+            let type_object = T::lazy_type_object().get_or_init(py);
+            crate::impl_::pyclass_init::python_base_new(type_object, target_type)?
+        } else {
+            super_init.into_new_object(py, target_type)?
+        };
 
-        let part_init: *mut PartiallyInitializedClassObject<T> = obj.cast();
+        // The contents have not been written yet after base new has been called.
         std::ptr::write(
-            (*part_init).contents.as_mut_ptr(),
+            PyClassObject::<T>::contents_ptr(obj),
             PyClassObjectContents {
                 value: ManuallyDrop::new(UnsafeCell::new(init)),
                 borrow_checker: <T::PyClassMutability as PyClassMutability>::Storage::new(),
@@ -192,6 +188,18 @@ impl<T: PyClass> PyClassInitializer<T> {
             },
         );
 
+        // Classes which aren't subclassable store the vectorcall implementation of `__call__`
+        // after their layout, see `create_type_object`.
+        #[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))]
+        if let Some(vectorcall) = T::call_vectorcall() {
+            std::ptr::write(
+                obj.cast::<u8>()
+                    .add(PyClassObject::<T>::basic_size())
+                    .cast::<Option<crate::ffi::vectorcallfunc>>(),
+                Some(vectorcall),
+            );
+        }
+
         // Safety: obj is a valid pointer to an object of type `target_type`, which` is a known
         // subclass of `T`
         Ok(obj.assume_owned(py).downcast_into_unchecked())
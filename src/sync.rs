@@ -14,7 +14,7 @@ use std::{
     cell::UnsafeCell,
     marker::PhantomData,
     mem::MaybeUninit,
-    sync::{Once, OnceState},
+    sync::{Once, OnceState, PoisonError, RwLock},
 };
 
 #[cfg(not(Py_GIL_DISABLED))]
@@ -94,6 +94,10 @@ unsafe impl<T> Sync for GILProtected<T> where T: Send {}
 /// more likely since there is no GIL to prevent races. In the future, PyO3 may change
 /// the semantics of GILOnceCell to behave more like the GIL build in the future.
 ///
+/// The value is shared by all Python interpreters of the process. Python objects stored by
+/// modules which can be loaded in [subinterpreters](crate::interpreter) should use
+/// [`InterpreterOnceCell<T>`] instead.
+///
 /// # Re-entrant initialization
 ///
 /// [`get_or_init`][GILOnceCell::get_or_init] and
@@ -369,6 +373,165 @@ impl<T> Drop for GILOnceCell<T> {
     }
 }
 
+/// A write-once cell like [`GILOnceCell<T>`], which holds a separate value for each Python
+/// interpreter.
+///
+/// Python objects belong to the interpreter they were created in, so static storage of Python
+/// objects should use `InterpreterOnceCell` in modules which can be loaded in
+/// [subinterpreters](crate::interpreter). When a single interpreter is used, it behaves
+/// like [`GILOnceCell<T>`].
+///
+/// The values of subinterpreters are never dropped: as Python does not notify extensions when an
+/// interpreter ends, a value stays in the cell after its interpreter has ended, and is leaked
+/// when the cell is dropped. Interpreter IDs are not reused, so these values are not accessed
+/// again. Each subinterpreter thus leaks the values stored for it, including the type objects
+/// of `#[pyclass]` types and the strings created by [`intern!`](crate::intern), which should be
+/// kept in mind by applications creating many subinterpreters.
+///
+/// # Examples
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use pyo3::sync::InterpreterOnceCell;
+/// use pyo3::types::PyType;
+///
+/// static ORDERED_DICT: InterpreterOnceCell<Py<PyType>> = InterpreterOnceCell::new();
+///
+/// # Python::with_gil(|py| -> PyResult<()> {
+/// let ordered_dict = ORDERED_DICT.import(py, "collections", "OrderedDict")?;
+/// assert_eq!(ordered_dict.name()?, "OrderedDict");
+/// # Ok(())
+/// # }).unwrap();
+/// ```
+pub struct InterpreterOnceCell<T> {
+    /// Value of the main interpreter.
+    main: GILOnceCell<T>,
+    /// Values of subinterpreters, by interpreter ID. The cells are boxed so that references to
+    /// them remain valid when the vector grows, and are never removed while `self` is alive.
+    subinterpreters: RwLock<Vec<(i64, Box<GILOnceCell<T>>)>>,
+}
+
+impl<T> Default for InterpreterOnceCell<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> InterpreterOnceCell<T> {
+    /// Create an `InterpreterOnceCell` which does not yet contain a value.
+    pub const fn new() -> Self {
+        Self {
+            main: GILOnceCell::new(),
+            subinterpreters: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Returns the cell of the current interpreter.
+    #[inline]
+    fn cell(&self, py: Python<'_>) -> &GILOnceCell<T> {
+        let id = crate::interpreter::current_interpreter_id(py);
+        if id == crate::interpreter::MAIN_INTERPRETER_ID {
+            &self.main
+        } else {
+            self.subinterpreter_cell(id)
+        }
+    }
+
+    #[cold]
+    fn subinterpreter_cell(&self, id: i64) -> &GILOnceCell<T> {
+        fn find<T>(cells: &[(i64, Box<GILOnceCell<T>>)], id: i64) -> Option<*const GILOnceCell<T>> {
+            cells
+                .iter()
+                .find(|(cell_id, _)| *cell_id == id)
+                .map(|(_, cell)| &**cell as *const GILOnceCell<T>)
+        }
+
+        let existing = find(&self.subinterpreters.read().unwrap(), id);
+        let cell = match existing {
+            Some(cell) => cell,
+            None => {
+                let mut cells = self.subinterpreters.write().unwrap();
+                match find(&cells, id) {
+                    Some(cell) => cell,
+                    None => {
+                        cells.push((id, Box::new(GILOnceCell::new())));
+                        &*cells.last().unwrap().1 as *const GILOnceCell<T>
+                    }
+                }
+            }
+        };
+        // SAFETY: the boxed cells are only dropped with `self`
+        unsafe { &*cell }
+    }
+
+    /// Get a reference to the value of the current interpreter, or `None` if it has not yet
+    /// been written.
+    #[inline]
+    pub fn get(&self, py: Python<'_>) -> Option<&T> {
+        self.cell(py).get(py)
+    }
+
+    /// Get a reference to the value of the current interpreter, initializing it if needed using
+    /// the provided closure.
+    ///
+    /// See [`GILOnceCell::get_or_init`] for detail on re-entrancy and concurrent initialization.
+    #[inline]
+    pub fn get_or_init<F>(&self, py: Python<'_>, f: F) -> &T
+    where
+        F: FnOnce() -> T,
+    {
+        self.cell(py).get_or_init(py, f)
+    }
+
+    /// Like `get_or_init`, but accepts a fallible initialization function. If it fails, the cell
+    /// is left uninitialized.
+    #[inline]
+    pub fn get_or_try_init<F, E>(&self, py: Python<'_>, f: F) -> Result<&T, E>
+    where
+        F: FnOnce() -> Result<T, E>,
+    {
+        self.cell(py).get_or_try_init(py, f)
+    }
+
+    /// Set the value of the current interpreter.
+    ///
+    /// If it has already been written, `Err(value)` will be returned containing the new value
+    /// which was not written.
+    pub fn set(&self, py: Python<'_>, value: T) -> Result<(), T> {
+        self.cell(py).set(py, value)
+    }
+}
+
+impl<T> InterpreterOnceCell<Py<T>>
+where
+    T: PyTypeCheck,
+{
+    /// Get a reference to the contained Python type of the current interpreter, importing it if
+    /// needed.
+    ///
+    /// See [`GILOnceCell::import`].
+    pub fn import<'py>(
+        &self,
+        py: Python<'py>,
+        module_name: &str,
+        attr_name: &str,
+    ) -> PyResult<&Bound<'py, T>> {
+        self.cell(py).import(py, module_name, attr_name)
+    }
+}
+
+impl<T> Drop for InterpreterOnceCell<T> {
+    fn drop(&mut self) {
+        let cells = self
+            .subinterpreters
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        for (_, cell) in cells.drain(..) {
+            std::mem::forget(cell);
+        }
+    }
+}
+
 /// Interns `text` as a Python string and stores a reference to it in static storage.
 ///
 /// A reference to the same Python string is returned on each invocation.
@@ -416,12 +579,12 @@ macro_rules! intern {
 
 /// Implementation detail for `intern!` macro.
 #[doc(hidden)]
-pub struct Interned(&'static str, GILOnceCell<Py<PyString>>);
+pub struct Interned(&'static str, InterpreterOnceCell<Py<PyString>>);
 
 impl Interned {
     /// Creates an empty holder for an interned `str`.
     pub const fn new(value: &'static str) -> Self {
-        Interned(value, GILOnceCell::new())
+        Interned(value, InterpreterOnceCell::new())
     }
 
     /// Gets or creates the interned `str` value.
@@ -1,21 +1,30 @@
 use crate::call::PyCallArgs;
 use crate::class::basic::CompareOp;
 use crate::conversion::{AsPyPointer, FromPyObjectBound, IntoPyObject};
+#[cfg(feature = "experimental-async")]
+use crate::coroutine::AsyncWithContext;
 use crate::err::{DowncastError, DowncastIntoError, PyErr, PyResult};
-use crate::exceptions::{PyAttributeError, PyTypeError};
+use crate::exceptions::{PyAttributeError, PyBaseException, PyTypeError};
 use crate::ffi_ptr_ext::FfiPtrExt;
 use crate::instance::Bound;
 use crate::internal::get_slot::TP_DESCR_GET;
 use crate::internal_tricks::ptr_from_ref;
+use crate::panic::PanicException;
 use crate::py_result_ext::PyResultExt;
 use crate::type_object::{PyTypeCheck, PyTypeInfo};
+use crate::types::typeobject::PyTypeMethods;
 #[cfg(not(any(PyPy, GraalPy)))]
 use crate::types::PySuper;
-use crate::types::{PyDict, PyIterator, PyList, PyString, PyType};
+use crate::types::{PyDict, PyIterator, PyList, PyString, PyTraceback, PyType};
+#[cfg(feature = "experimental-async")]
+use crate::PyObject;
 use crate::{err, ffi, Borrowed, BoundObject, IntoPyObjectExt, Python};
 use std::cell::UnsafeCell;
 use std::cmp::Ordering;
+#[cfg(feature = "experimental-async")]
+use std::future::Future;
 use std::os::raw::c_int;
+use std::panic;
 
 /// Represents any Python object.
 ///
@@ -904,6 +913,81 @@ pub trait PyAnyMethods<'py>: crate::sealed::Sealed {
     /// This is equivalent to the Python expression `super()`
     #[cfg(not(any(PyPy, GraalPy)))]
     fn py_super(&self) -> PyResult<Bound<'py, PySuper>>;
+
+    /// Runs `f` in this context manager.
+    ///
+    /// This is equivalent to the Python statement `with self as value: f(value)`: `f` receives
+    /// the result of `__enter__`, and `__exit__` is called when `f` returns, with the exception
+    /// of the error returned by `f`, if any. If `__exit__` returns a true value, the error is
+    /// suppressed and `Ok(None)` is returned. An exception raised by `__exit__` replaces the error
+    /// of `f`, which becomes its `__context__`.
+    ///
+    /// If `f` panics, `__exit__` is called with a `PanicException` before the panic is resumed.
+    ///
+    /// See [`async_with_context`](PyAnyMethods::async_with_context) for asynchronous context
+    /// managers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use pyo3::prelude::*;
+    ///
+    /// # fn main() -> PyResult<()> {
+    /// Python::with_gil(|py| {
+    ///     let lock = py.import("threading")?.call_method0("Lock")?;
+    ///     let locked = lock.with_context(|_| lock.call_method0("locked")?.extract::<bool>())?;
+    ///     assert_eq!(locked, Some(true));
+    ///     assert!(!lock.call_method0("locked")?.extract::<bool>()?);
+    ///     Ok(())
+    /// })
+    /// # }
+    /// ```
+    fn with_context<F, R>(&self, f: F) -> PyResult<Option<R>>
+    where
+        F: FnOnce(&Bound<'py, PyAny>) -> PyResult<R>;
+
+    /// Runs the future returned by `f` in this asynchronous context manager.
+    ///
+    /// This is the asynchronous counterpart of [`with_context`](PyAnyMethods::with_context),
+    /// equivalent to the Python statement `async with self as value: await f(value)`. The
+    /// awaitables returned by `__aenter__` and `__aexit__` are awaited by the
+    /// [`Coroutine`](crate::coroutine::Coroutine) running the returned future, which yields their
+    /// values to the event loop as `await` would, so this works with any
+    /// [`AsyncLibrary`](crate::coroutine::AsyncLibrary), but it fails with a `RuntimeError` if
+    /// the future is not run by a coroutine, e.g. an `async fn` wrapped by `#[pyfunction]`.
+    ///
+    /// If the future returned by `f` panics, `__aexit__` is awaited with a `PanicException`
+    /// before the panic is resumed.
+    ///
+    /// An exception thrown into the coroutine while the future returned by `f` runs, e.g. a
+    /// cancellation, is raised in it like in the body of `async with`: the future is dropped, and
+    /// `__aexit__` is awaited with the exception, which is raised again unless `__aexit__`
+    /// suppresses it. Such exceptions are then not seen by the
+    /// [`CancelHandle`](crate::coroutine::CancelHandle) of the coroutine.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #![allow(dead_code)]
+    /// use pyo3::prelude::*;
+    ///
+    /// #[pyfunction]
+    /// async fn acquire(lock: PyObject) -> PyResult<bool> {
+    ///     let locked = Python::with_gil(|py| {
+    ///         let locked = lock.clone_ref(py);
+    ///         lock.bind(py).async_with_context(|_| async move {
+    ///             Python::with_gil(|py| locked.call_method0(py, "locked")?.extract::<bool>(py))
+    ///         })
+    ///     })
+    ///     .await?;
+    ///     Ok(locked == Some(true))
+    /// }
+    /// ```
+    #[cfg(feature = "experimental-async")]
+    fn async_with_context<F, Fut, R>(&self, f: F) -> AsyncWithContext<F, Fut, R>
+    where
+        F: FnOnce(PyObject) -> Fut,
+        Fut: Future<Output = PyResult<R>>;
 }
 
 macro_rules! implement_binop {
@@ -1530,6 +1614,46 @@ impl<'py> PyAnyMethods<'py> for Bound<'py, PyAny> {
     fn py_super(&self) -> PyResult<Bound<'py, PySuper>> {
         PySuper::new(&self.get_type(), self)
     }
+
+    fn with_context<F, R>(&self, f: F) -> PyResult<Option<R>>
+    where
+        F: FnOnce(&Bound<'py, PyAny>) -> PyResult<R>,
+    {
+        let py = self.py();
+        let (enter, exit) = self.context_manager_methods(
+            intern!(py, "__enter__"),
+            intern!(py, "__exit__"),
+            "context manager",
+        )?;
+        let value = enter.call0()?;
+        match panic::catch_unwind(panic::AssertUnwindSafe(|| f(&value))) {
+            Ok(Ok(result)) => {
+                exit.call1((py.None(), py.None(), py.None()))?;
+                Ok(Some(result))
+            }
+            Ok(Err(err)) => match exit.call1(exit_args(py, &err)) {
+                Ok(suppress) if suppress.is_truthy()? => Ok(None),
+                Ok(_) => Err(err),
+                Err(exit_err) => Err(chain_exit_error(py, exit_err, err)),
+            },
+            Err(payload) => {
+                let err = PanicException::new_from_payload(&*payload);
+                if let Err(exit_err) = exit.call1(exit_args(py, &err)) {
+                    exit_err.write_unraisable(py, Some(self));
+                }
+                panic::resume_unwind(payload)
+            }
+        }
+    }
+
+    #[cfg(feature = "experimental-async")]
+    fn async_with_context<F, Fut, R>(&self, f: F) -> AsyncWithContext<F, Fut, R>
+    where
+        F: FnOnce(PyObject) -> Fut,
+        Fut: Future<Output = PyResult<R>>,
+    {
+        AsyncWithContext::new(self.clone().unbind(), f)
+    }
 }
 
 impl<'py> Bound<'py, PyAny> {
@@ -1568,6 +1692,46 @@ impl<'py> Bound<'py, PyAny> {
             Ok(Some(attr))
         }
     }
+
+    /// Looks up the enter and exit methods of a context manager, like the `with` statement.
+    pub(crate) fn context_manager_methods(
+        &self,
+        enter: &Bound<'py, PyString>,
+        exit: &Bound<'py, PyString>,
+        protocol: &str,
+    ) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+        match (self.lookup_special(enter)?, self.lookup_special(exit)?) {
+            (Some(enter), Some(exit)) => Ok((enter, exit)),
+            _ => Err(PyTypeError::new_err(format!(
+                "'{}' object does not support the {} protocol",
+                self.get_type().qualname()?,
+                protocol
+            ))),
+        }
+    }
+}
+
+/// Arguments of `__exit__` and `__aexit__` when the body of a context manager fails with `err`.
+pub(crate) fn exit_args<'py>(
+    py: Python<'py>,
+    err: &PyErr,
+) -> (
+    Bound<'py, PyType>,
+    Bound<'py, PyBaseException>,
+    Option<Bound<'py, PyTraceback>>,
+) {
+    (err.get_type(py), err.value(py).clone(), err.traceback(py))
+}
+
+/// Sets `err` as the `__context__` of the exception raised by `__exit__` or `__aexit__`, as
+/// the `with` statement does, unless it is the same exception.
+pub(crate) fn chain_exit_error(py: Python<'_>, exit_err: PyErr, err: PyErr) -> PyErr {
+    let value = exit_err.value(py);
+    if !value.is(err.value(py)) {
+        // `PyException_SetContext` steals a reference to the context
+        unsafe { ffi::PyException_SetContext(value.as_ptr(), err.into_value(py).into_ptr()) };
+    }
+    exit_err
 }
 
 #[cfg(test)]
@@ -1577,7 +1741,7 @@ mod tests {
         ffi,
         tests::common::generate_unique_module_name,
         types::{IntoPyDict, PyAny, PyAnyMethods, PyBool, PyInt, PyList, PyModule, PyTypeMethods},
-        Bound, BoundObject, IntoPyObject, PyTypeInfo, Python,
+        Bound, BoundObject, IntoPyObject, PyResult, PyTypeInfo, Python,
     };
     use pyo3_ffi::c_str;
     use std::fmt::Debug;
@@ -2001,6 +2165,91 @@ class SimpleClass:
         });
     }
 
+    #[test]
+    fn test_with_context() {
+        use crate::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+        use crate::PyErr;
+
+        Python::with_gil(|py| {
+            let module = PyModule::from_code(
+                py,
+                c_str!(
+                    r#"
+class Manager:
+    def __init__(self, suppress=False, fail=False):
+        self.suppress = suppress
+        self.fail = fail
+        self.exc_type = "not exited"
+
+    def __enter__(self):
+        return "value"
+
+    def __exit__(self, exc_type, exc, tb):
+        self.exc_type = exc_type
+        if self.fail:
+            raise RuntimeError("exit failed")
+        return self.suppress
+"#
+                ),
+                c_str!("test.py"),
+                &generate_unique_module_name("test"),
+            )
+            .unwrap();
+            let manager_type = module.getattr("Manager").unwrap();
+            let new_manager = |suppress: bool, fail: bool| {
+                let kwargs = [("suppress", suppress), ("fail", fail)]
+                    .into_py_dict(py)
+                    .unwrap();
+                manager_type.call((), Some(&kwargs)).unwrap()
+            };
+            fn exc_type<'py>(manager: &Bound<'py, PyAny>) -> Bound<'py, PyAny> {
+                manager.getattr("exc_type").unwrap()
+            }
+
+            let manager = new_manager(false, false);
+            let result = manager.with_context(|value| value.extract::<String>());
+            assert_eq!(result.unwrap().as_deref(), Some("value"));
+            assert!(exc_type(&manager).is_none());
+
+            let manager = new_manager(false, false);
+            let result: PyResult<Option<()>> =
+                manager.with_context(|_| Err(PyValueError::new_err("body failed")));
+            assert!(result.unwrap_err().is_instance_of::<PyValueError>(py));
+            assert!(exc_type(&manager).is(&py.get_type::<PyValueError>()));
+
+            let manager = new_manager(true, false);
+            let result: PyResult<Option<()>> =
+                manager.with_context(|_| Err(PyValueError::new_err("body failed")));
+            assert!(result.unwrap().is_none());
+
+            let manager = new_manager(false, true);
+            let result: PyResult<Option<()>> =
+                manager.with_context(|_| Err(PyValueError::new_err("body failed")));
+            let err = result.unwrap_err();
+            assert!(err.is_instance_of::<PyRuntimeError>(py));
+            let context = err.value(py).getattr("__context__").unwrap();
+            assert!(PyErr::from_value(context).is_instance_of::<PyValueError>(py));
+
+            let manager = new_manager(false, false);
+            let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                let _ = manager.with_context(|_| -> PyResult<()> { panic!("body panicked") });
+            }));
+            assert!(panic.is_err());
+            assert!(exc_type(&manager).is(&py.get_type::<crate::panic::PanicException>()));
+
+            let err = 1i32
+                .into_pyobject(py)
+                .unwrap()
+                .with_context(|_| Ok(()))
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyTypeError>(py));
+            assert_eq!(
+                err.value(py).to_string(),
+                "'int' object does not support the context manager protocol"
+            );
+        });
+    }
+
     #[cfg(feature = "macros")]
     #[test]
     #[allow(unknown_lints, non_local_definitions)]
@@ -160,6 +160,10 @@ impl PyCFunction {
             (std::ptr::null_mut(), None)
         };
         let def = method_def.as_method_def();
+        #[cfg(feature = "experimental-inspect")]
+        if let Some(introspection) = method_def.introspection {
+            crate::impl_::introspection::register_function(&def, introspection);
+        }
 
         // FIXME: stop leaking the def
         let def = Box::into_raw(Box::new(def));
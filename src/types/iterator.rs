@@ -1,7 +1,13 @@
+use crate::exceptions::PyStopIteration;
 use crate::ffi_ptr_ext::FfiPtrExt;
 use crate::instance::Borrowed;
 use crate::py_result_ext::PyResultExt;
-use crate::{ffi, Bound, PyAny, PyErr, PyResult, PyTypeCheck};
+use crate::types::any::PyAnyMethods;
+#[cfg(feature = "macros")]
+use crate::PyObject;
+use crate::{
+    ffi, Bound, IntoPyObject, IntoPyObjectExt, PyAny, PyErr, PyResult, PyTypeCheck, Python,
+};
 
 /// A Python iterator object.
 ///
@@ -50,6 +56,230 @@ impl PyIterator {
     pub fn from_bound_object<'py>(obj: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyIterator>> {
         Self::from_object(obj)
     }
+
+    /// Wraps a Rust iterator into a Python iterator.
+    ///
+    /// Items are converted to Python objects lazily, as the iterator is advanced from Python.
+    /// Items of type `Result<T, E>` raise their error instead of being yielded, see
+    /// [`IntoPyIteratorItem`], and the lower bound of [`Iterator::size_hint`] is exposed to Python
+    /// as `__length_hint__`.
+    ///
+    /// This requires the `macros` feature, as the Python iterator is a `#[pyclass]`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use pyo3::prelude::*;
+    /// use pyo3::types::PyIterator;
+    ///
+    /// #[pyfunction]
+    /// fn squares(py: Python<'_>, n: u64) -> PyResult<Bound<'_, PyIterator>> {
+    ///     PyIterator::from_rust(py, (0..n).map(|i| i * i))
+    /// }
+    ///
+    /// # Python::with_gil(|py| {
+    /// #     let squares = wrap_pyfunction!(squares, py).unwrap();
+    /// #     pyo3::py_run!(py, squares, "assert list(squares(4)) == [0, 1, 4, 9]");
+    /// # });
+    /// ```
+    #[cfg(feature = "macros")]
+    pub fn from_rust<'py, I>(py: Python<'py>, iter: I) -> PyResult<Bound<'py, PyIterator>>
+    where
+        I: IntoIterator,
+        I::IntoIter: Send + 'static,
+        I::Item: for<'a> IntoPyIteratorItem<'a>,
+    {
+        let iter = RustIterator(Some(Box::new(iter.into_iter())));
+        // Safety: `RustIterator` implements `__next__`
+        unsafe {
+            Bound::new(py, iter)
+                .map(Bound::into_any)
+                .downcast_into_unchecked()
+        }
+    }
+}
+
+/// Conversion of the items of the Rust iterators wrapped by [`PyIterator::from_rust`].
+///
+/// It is implemented for the types which implement [`IntoPyObject`], which are yielded, and for
+/// `Result`s of them, whose error is raised by the Python iterator.
+pub trait IntoPyIteratorItem<'py> {
+    /// Converts the item to the object yielded by the Python iterator.
+    fn into_py_item(self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>>;
+}
+
+impl<'py, T> IntoPyIteratorItem<'py> for T
+where
+    T: IntoPyObject<'py>,
+{
+    #[inline]
+    fn into_py_item(self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.into_bound_py_any(py)
+    }
+}
+
+impl<'py, T, E> IntoPyIteratorItem<'py> for Result<T, E>
+where
+    T: IntoPyObject<'py>,
+    E: Into<PyErr>,
+{
+    #[inline]
+    fn into_py_item(self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.map_err(Into::into)?.into_py_item(py)
+    }
+}
+
+/// Object-safe interface of the Rust iterators wrapped by [`PyIterator::from_rust`].
+#[cfg(feature = "macros")]
+trait DynIterator: Send {
+    fn next(&mut self, py: Python<'_>) -> Option<PyResult<PyObject>>;
+    fn size_hint(&self) -> (usize, Option<usize>);
+}
+
+#[cfg(feature = "macros")]
+impl<I> DynIterator for I
+where
+    I: Iterator + Send,
+    I::Item: for<'a> IntoPyIteratorItem<'a>,
+{
+    fn next(&mut self, py: Python<'_>) -> Option<PyResult<PyObject>> {
+        let item = Iterator::next(self)?;
+        Some(item.into_py_item(py).map(Bound::unbind))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        Iterator::size_hint(self)
+    }
+}
+
+/// Python iterator created by [`PyIterator::from_rust`]; the Rust iterator is dropped once
+/// exhausted.
+#[cfg(feature = "macros")]
+#[crate::pyclass(crate = "crate")]
+struct RustIterator(Option<Box<dyn DynIterator>>);
+
+// Safety: `RustIterator` is allowed to be `Sync` even though the iterator is not,
+// because the iterator is only accessed with `&mut self` receivers, including for its size hint
+#[cfg(feature = "macros")]
+unsafe impl Sync for RustIterator {}
+
+#[cfg(feature = "macros")]
+#[crate::pymethods(crate = "crate")]
+impl RustIterator {
+    fn __iter__(slf: crate::PyRef<'_, Self>) -> crate::PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        let next = self.0.as_mut().and_then(|iter| iter.next(py));
+        if next.is_none() {
+            self.0 = None;
+        }
+        next.transpose()
+    }
+
+    fn __length_hint__(&mut self) -> usize {
+        self.0.as_ref().map_or(0, |iter| iter.size_hint().0)
+    }
+}
+
+/// Outcome of [`PyIteratorMethods::send`] and [`PyIteratorMethods::throw`].
+#[derive(Debug)]
+pub enum PySendResult<'py> {
+    /// The iterator yielded a value.
+    Next(Bound<'py, PyAny>),
+    /// The iterator is exhausted, and returned a value, e.g. the value of a `return` statement
+    /// in a generator, or `None`.
+    Return(Bound<'py, PyAny>),
+}
+
+/// Implementation of functionality for [`PyIterator`].
+///
+/// These methods are defined for the `Bound<'py, PyIterator>` smart pointer, so to use method call
+/// syntax these methods are separated into a trait, because stable Rust does not yet support
+/// `arbitrary_self_types`.
+#[doc(alias = "PyIterator")]
+pub trait PyIteratorMethods<'py>: crate::sealed::Sealed {
+    /// Sends a value into the iterator; the equivalent of `gen.send(value)` in Python.
+    ///
+    /// Generators and coroutines must be started by sending `None`. Sending `None` into other
+    /// iterators advances them like [`Iterator::next`]. Unlike [`Iterator::next`], the value
+    /// returned by an exhausted generator is available as [`PySendResult::Return`].
+    fn send(&self, value: &Bound<'py, PyAny>) -> PyResult<PySendResult<'py>>;
+
+    /// Raises an exception in the iterator at the point where it is suspended; the equivalent of
+    /// `gen.throw(err)` in Python.
+    ///
+    /// Fails with `AttributeError` if the iterator is not a generator or a coroutine, and with
+    /// `err` itself if it is not handled.
+    fn throw(&self, err: PyErr) -> PyResult<PySendResult<'py>>;
+
+    /// Closes the iterator; the equivalent of `gen.close()` in Python.
+    ///
+    /// This raises `GeneratorExit` in a suspended generator or coroutine, so that it can clean up.
+    /// Iterators without a `close` method are left untouched.
+    fn close(&self) -> PyResult<()>;
+}
+
+impl<'py> PyIteratorMethods<'py> for Bound<'py, PyIterator> {
+    fn send(&self, value: &Bound<'py, PyAny>) -> PyResult<PySendResult<'py>> {
+        #[cfg(all(not(PyPy), Py_3_10))]
+        {
+            let py = self.py();
+            let mut result = std::ptr::null_mut();
+            match unsafe { ffi::PyIter_Send(self.as_ptr(), value.as_ptr(), &mut result) } {
+                ffi::PySendResult::PYGEN_ERROR => Err(PyErr::fetch(py)),
+                ffi::PySendResult::PYGEN_RETURN => Ok(PySendResult::Return(unsafe {
+                    result.assume_owned_unchecked(py)
+                })),
+                ffi::PySendResult::PYGEN_NEXT => Ok(PySendResult::Next(unsafe {
+                    result.assume_owned_unchecked(py)
+                })),
+            }
+        }
+        #[cfg(any(PyPy, not(Py_3_10)))]
+        {
+            let py = self.py();
+            // `PyIter_Send` calls `tp_iternext` when sending `None`
+            let result = if value.is_none() {
+                self.call_method0(crate::intern!(py, "__next__"))
+            } else {
+                self.call_method1(crate::intern!(py, "send"), (value,))
+            };
+            send_result(py, result)
+        }
+    }
+
+    fn throw(&self, err: PyErr) -> PyResult<PySendResult<'py>> {
+        let py = self.py();
+        send_result(
+            py,
+            self.call_method1(crate::intern!(py, "throw"), (err.into_value(py),)),
+        )
+    }
+
+    fn close(&self) -> PyResult<()> {
+        let py = self.py();
+        let close = crate::intern!(py, "close");
+        if self.hasattr(close)? {
+            self.call_method0(close)?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts the `StopIteration` raised by an exhausted iterator to [`PySendResult::Return`].
+fn send_result<'py>(
+    py: Python<'py>,
+    result: PyResult<Bound<'py, PyAny>>,
+) -> PyResult<PySendResult<'py>> {
+    match result {
+        Ok(value) => Ok(PySendResult::Next(value)),
+        Err(err) if err.is_instance_of::<PyStopIteration>(py) => Ok(PySendResult::Return(
+            err.value(py).getattr(crate::intern!(py, "value"))?,
+        )),
+        Err(err) => Err(err),
+    }
 }
 
 impl<'py> Iterator for Bound<'py, PyIterator> {
@@ -105,8 +335,8 @@ impl PyTypeCheck for PyIterator {
 
 #[cfg(test)]
 mod tests {
-    use super::PyIterator;
-    use crate::exceptions::PyTypeError;
+    use super::{PyIterator, PyIteratorMethods, PySendResult};
+    use crate::exceptions::{PyTypeError, PyValueError};
     use crate::types::{PyAnyMethods, PyDict, PyList, PyListMethods};
     use crate::{ffi, IntoPyObject, Python};
 
@@ -317,6 +547,163 @@ def fibonacci(target):
         });
     }
 
+    #[test]
+    #[cfg(feature = "macros")]
+    fn from_rust() {
+        Python::with_gil(|py| {
+            let numbers = PyIterator::from_rust(py, vec![1, 2, 3]).unwrap();
+            crate::py_run!(
+                py,
+                numbers,
+                r#"
+                    import operator
+                    assert iter(numbers) is numbers
+                    assert operator.length_hint(numbers) == 3
+                    assert next(numbers) == 1
+                    assert operator.length_hint(numbers) == 2
+                    assert list(numbers) == [2, 3]
+                    assert operator.length_hint(numbers) == 0
+                    assert list(numbers) == []
+                "#
+            );
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "macros")]
+    fn from_rust_is_lazy() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        Python::with_gil(|py| {
+            let advanced = Arc::new(AtomicUsize::new(0));
+            let counter = advanced.clone();
+            let mut iter = PyIterator::from_rust(
+                py,
+                (0..).inspect(move |_| {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }),
+            )
+            .unwrap();
+            assert_eq!(advanced.load(Ordering::Relaxed), 0);
+            let item = iter.next().unwrap().unwrap();
+            assert_eq!(item.extract::<i32>().unwrap(), 0);
+            assert_eq!(advanced.load(Ordering::Relaxed), 1);
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "macros")]
+    fn from_rust_result_items() {
+        use crate::exceptions::PyValueError;
+        use crate::PyErr;
+
+        Python::with_gil(|py| {
+            let items: Vec<Result<i32, PyErr>> = vec![Ok(1), Err(PyValueError::new_err("bad"))];
+            let mut iter = PyIterator::from_rust(py, items).unwrap();
+            assert_eq!(iter.next().unwrap().unwrap().extract::<i32>().unwrap(), 1);
+            let err = iter.next().unwrap().unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert!(iter.next().is_none());
+        });
+    }
+
+    #[test]
+    fn send_into_generator() {
+        Python::with_gil(|py| {
+            let globals = PyDict::new(py);
+            py.run(
+                ffi::c_str!(
+                    r#"
+def gen():
+    total = 0
+    value = yield total
+    while value is not None:
+        total += value
+        value = yield total
+    return total
+"#
+                ),
+                Some(&globals),
+                None,
+            )
+            .unwrap();
+            let generator = globals.get_item("gen").unwrap().call0().unwrap();
+            let generator = generator.downcast::<PyIterator>().unwrap();
+            let send = |value: Option<i32>| {
+                let value = value.into_pyobject(py).unwrap();
+                match generator.send(&value).unwrap() {
+                    PySendResult::Next(value) => Ok(value.extract::<i32>().unwrap()),
+                    PySendResult::Return(value) => Err(value.extract::<i32>().unwrap()),
+                }
+            };
+            assert_eq!(send(None), Ok(0));
+            assert_eq!(send(Some(2)), Ok(2));
+            assert_eq!(send(Some(3)), Ok(5));
+            assert_eq!(send(None), Err(5));
+        });
+    }
+
+    #[test]
+    fn send_into_iterator() {
+        Python::with_gil(|py| {
+            let iter = vec![1].into_pyobject(py).unwrap().try_iter().unwrap();
+            let none = py.None().into_bound(py);
+            assert!(
+                matches!(iter.send(&none).unwrap(), PySendResult::Next(value) if value.extract::<i32>().unwrap() == 1)
+            );
+            assert!(
+                matches!(iter.send(&none).unwrap(), PySendResult::Return(value) if value.is_none())
+            );
+        });
+    }
+
+    #[test]
+    fn throw_and_close_generator() {
+        Python::with_gil(|py| {
+            let globals = PyDict::new(py);
+            py.run(
+                ffi::c_str!(
+                    r#"
+closed = []
+def gen():
+    try:
+        yield 1
+    except ValueError:
+        yield 2
+    finally:
+        closed.append(True)
+"#
+                ),
+                Some(&globals),
+                None,
+            )
+            .unwrap();
+            let gen = globals.get_item("gen").unwrap();
+
+            let mut generator = gen.call0().unwrap().try_iter().unwrap();
+            generator.next().unwrap().unwrap();
+            let result = generator.throw(PyValueError::new_err("thrown")).unwrap();
+            assert!(
+                matches!(result, PySendResult::Next(value) if value.extract::<i32>().unwrap() == 2)
+            );
+            let err = generator.throw(PyTypeError::new_err("thrown")).unwrap_err();
+            assert!(err.is_instance_of::<PyTypeError>(py));
+
+            let mut generator = gen.call0().unwrap().try_iter().unwrap();
+            generator.next().unwrap().unwrap();
+            generator.close().unwrap();
+            let closed = globals.get_item("closed").unwrap();
+            assert_eq!(closed.len().unwrap(), 2);
+
+            // iterators which are not generators
+            let iter = vec![1].into_pyobject(py).unwrap().try_iter().unwrap();
+            iter.close().unwrap();
+            let err = iter.throw(PyValueError::new_err("thrown")).unwrap_err();
+            assert!(err.is_instance_of::<crate::exceptions::PyAttributeError>(py));
+        });
+    }
+
     #[test]
     #[cfg(not(Py_LIMITED_API))]
     fn length_hint_becomes_size_hint_lower_bound() {
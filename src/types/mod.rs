@@ -25,12 +25,12 @@ pub use self::frozenset::{PyFrozenSet, PyFrozenSetBuilder, PyFrozenSetMethods};
 pub use self::function::PyCFunction;
 #[cfg(all(not(Py_LIMITED_API), not(all(PyPy, not(Py_3_8)))))]
 pub use self::function::PyFunction;
-pub use self::iterator::PyIterator;
+pub use self::iterator::{IntoPyIteratorItem, PyIterator, PyIteratorMethods, PySendResult};
 pub use self::list::{PyList, PyListMethods};
 pub use self::mapping::{PyMapping, PyMappingMethods};
 pub use self::mappingproxy::PyMappingProxy;
 pub use self::memoryview::PyMemoryView;
-pub use self::module::{PyModule, PyModuleMethods};
+pub use self::module::{PyModule, PyModuleMethods, PyModuleState};
 pub use self::none::PyNone;
 pub use self::notimplemented::PyNotImplemented;
 #[allow(deprecated)]
@@ -204,6 +204,17 @@ macro_rules! pyobject_subclassable_native_type {
             type Initializer = $crate::impl_::pyclass_init::PyNativeTypeInitializer<Self>;
             type PyClassMutability = $crate::pycell::impl_::ImmutableClass;
         }
+
+        // The layout of native types isn't part of the limited API, so subclasses place their
+        // contents after the size of the base read at runtime.
+        #[cfg(all(Py_LIMITED_API, Py_3_12))]
+        impl<$($generics,)*> $crate::impl_::pyclass::PyClassBaseType for $name {
+            type LayoutAsBase = $crate::impl_::pycell::PyClassObjectBase<$crate::ffi::PyObject>;
+            type BaseNativeType = $name;
+            type Initializer = $crate::impl_::pyclass_init::PyNativeTypeInitializer<Self>;
+            type PyClassMutability = $crate::pycell::impl_::ImmutableClass;
+            const DYNAMIC_LAYOUT: bool = true;
+        }
     }
 }
 
@@ -226,6 +237,9 @@ macro_rules! pyobject_native_type {
         // To prevent inheriting native types with ABI3
         #[cfg(not(Py_LIMITED_API))]
         $crate::pyobject_native_type_sized!($name, $layout $(;$generics)*);
+        // With ABI3 only the object header is known
+        #[cfg(all(Py_LIMITED_API, Py_3_12))]
+        $crate::pyobject_native_type_sized!($name, $crate::ffi::PyObject $(;$generics)*);
     };
 }
 
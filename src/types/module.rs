@@ -2,7 +2,7 @@ use crate::err::{PyErr, PyResult};
 use crate::ffi_ptr_ext::FfiPtrExt;
 use crate::impl_::callback::IntoPyCallbackOutput;
 use crate::py_result_ext::PyResultExt;
-use crate::pyclass::PyClass;
+use crate::pyclass::{PyClass, PyTraverseError, PyVisit};
 use crate::types::{
     any::PyAnyMethods, list::PyListMethods, PyAny, PyCFunction, PyDict, PyList, PyString,
 };
@@ -423,6 +423,60 @@ pub trait PyModuleMethods<'py>: crate::sealed::Sealed {
     ///
     /// This is a no-op on the GIL-enabled build.
     fn gil_used(&self, gil_used: bool) -> PyResult<()>;
+
+    /// Returns the state of this module.
+    ///
+    /// Fails if the module was not defined with [`#[pymodule(state = T)]`](crate::pymodule), or
+    /// if its state has already been dropped because the module is being cleared.
+    ///
+    /// See [`PyModuleState`] for an example.
+    fn state<T: PyModuleState>(&self) -> PyResult<&T>;
+}
+
+/// State of a module defined with [`#[pymodule(state = ...)]`](crate::pymodule).
+///
+/// Each module object created from the definition owns a separate state, created with
+/// [`Default::default`] when the module is executed and dropped when the module is cleared
+/// or deallocated. As the state is not shared between module objects, it is preferable to
+/// global `static`s when the module may be imported several times.
+///
+/// The state is accessed with [`PyModuleMethods::state`]. Classes access the state of their
+/// module through `PyTypeMethods::module_with_state`.
+///
+/// # Examples
+///
+/// ```rust
+/// use pyo3::prelude::*;
+/// use pyo3::types::PyModuleState;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// #[derive(Default)]
+/// struct Counter {
+///     calls: AtomicUsize,
+/// }
+///
+/// impl PyModuleState for Counter {}
+///
+/// #[pyfunction(pass_module)]
+/// fn count(module: &Bound<'_, PyModule>) -> PyResult<usize> {
+///     let state = module.state::<Counter>()?;
+///     Ok(state.calls.fetch_add(1, Ordering::Relaxed) + 1)
+/// }
+///
+/// #[pymodule(state = Counter)]
+/// fn my_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+///     m.add_function(wrap_pyfunction!(count, m)?)
+/// }
+/// ```
+pub trait PyModuleState: Default + Send + Sync + 'static {
+    /// Visits the Python objects owned by the state, to allow the garbage collector to break
+    /// reference cycles going through the module state.
+    ///
+    /// As for `__traverse__` of classes, this must not acquire the GIL.
+    fn __traverse__(&self, visit: PyVisit<'_>) -> Result<(), PyTraverseError> {
+        let _ = visit;
+        Ok(())
+    }
 }
 
 impl<'py> PyModuleMethods<'py> for Bound<'py, PyModule> {
@@ -519,8 +573,26 @@ impl<'py> PyModuleMethods<'py> for Bound<'py, PyModule> {
     where
         T: PyClass,
     {
-        let py = self.py();
-        self.add(T::NAME, T::lazy_type_object().get_or_try_init(py)?)
+        // The type object is associated with the first module with a state or a panic policy
+        // it is added to, so that the methods of the class can access the state, see
+        // `PyTypeMethods::module_with_state`, and use the panic policy of the module.
+        #[cfg(all(
+            any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+            not(any(PyPy, GraalPy))
+        ))]
+        let type_object = if crate::impl_::pymodule::associates_classes(self) {
+            T::lazy_type_object().get_or_try_init_in_module(self)?
+        } else {
+            T::lazy_type_object().get_or_try_init(self.py())?
+        };
+        #[cfg(not(all(
+            any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+            not(any(PyPy, GraalPy))
+        )))]
+        let type_object = T::lazy_type_object().get_or_try_init(self.py())?;
+        self.add(T::NAME, type_object)?;
+        // pickle looks up the reconstructor of a `#[pyclass(pickle)]` next to the class
+        crate::impl_::pyclass::add_pickle_reconstructor::<T>(self)
     }
 
     fn add_wrapped<T>(&self, wrapper: &impl Fn(Python<'py>) -> T) -> PyResult<()>
@@ -562,6 +634,10 @@ impl<'py> PyModuleMethods<'py> for Bound<'py, PyModule> {
         #[cfg(any(Py_LIMITED_API, not(Py_GIL_DISABLED)))]
         Ok(())
     }
+
+    fn state<T: PyModuleState>(&self) -> PyResult<&T> {
+        crate::impl_::pymodule::module_state(self)
+    }
 }
 
 fn __all__(py: Python<'_>) -> &Bound<'_, PyString> {
@@ -4,6 +4,11 @@ use crate::instance::Borrowed;
 use crate::pybacked::PyBackedStr;
 use crate::types::any::PyAnyMethods;
 use crate::types::PyTuple;
+#[cfg(all(
+    any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+    not(any(PyPy, GraalPy))
+))]
+use crate::types::{PyModule, PyModuleState};
 use crate::{ffi, Bound, PyAny, PyTypeInfo, Python};
 
 use super::PyString;
@@ -97,6 +102,48 @@ pub trait PyTypeMethods<'py>: crate::sealed::Sealed {
     ///
     /// Equivalent to the Python expression `self.__bases__`.
     fn bases(&self) -> Bound<'py, PyTuple>;
+
+    /// Returns the module with a state of type `S` associated with this type, or with the first
+    /// of its bases which has one.
+    ///
+    /// A `#[pyclass]` has a single type object in each interpreter. It is associated with the
+    /// first module which has a [`PyModuleState`] the class is added to with
+    /// [`PyModuleMethods::add_class`](crate::types::PyModuleMethods::add_class), provided that
+    /// its type object has not been created before, so that its methods can access the state of
+    /// their module. The module is looked up with `PyType_GetModuleByDef`, which also finds it
+    /// for the subclasses of the class defined in Python.
+    ///
+    /// The class remains associated with that module when it is added to other modules, for
+    /// example when its module is imported again.
+    ///
+    /// ```rust
+    /// use pyo3::prelude::*;
+    /// use pyo3::types::PyModuleState;
+    ///
+    /// #[derive(Default)]
+    /// struct State {
+    ///     greeting: String,
+    /// }
+    ///
+    /// impl PyModuleState for State {}
+    ///
+    /// #[pyclass]
+    /// struct Greeter;
+    ///
+    /// #[pymethods]
+    /// impl Greeter {
+    /// #   #[cfg(all(any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))), not(any(PyPy, GraalPy))))]
+    ///     fn greet(slf: &Bound<'_, Self>) -> PyResult<String> {
+    ///         let module = slf.get_type().module_with_state::<State>()?;
+    ///         Ok(module.state::<State>()?.greeting.clone())
+    ///     }
+    /// }
+    /// ```
+    #[cfg(all(
+        any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+        not(any(PyPy, GraalPy))
+    ))]
+    fn module_with_state<S: PyModuleState>(&self) -> PyResult<Bound<'py, PyModule>>;
 }
 
 impl<'py> PyTypeMethods<'py> for Bound<'py, PyType> {
@@ -246,6 +293,14 @@ impl<'py> PyTypeMethods<'py> for Bound<'py, PyType> {
 
         bases
     }
+
+    #[cfg(all(
+        any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+        not(any(PyPy, GraalPy))
+    ))]
+    fn module_with_state<S: PyModuleState>(&self) -> PyResult<Bound<'py, PyModule>> {
+        crate::impl_::pymodule::module_with_state::<S>(self)
+    }
 }
 
 #[cfg(test)]
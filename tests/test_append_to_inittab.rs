@@ -49,6 +49,32 @@ assert module_fn_with_functions.foo() == 123
                 r#"
 import module_mod_with_functions
 assert module_mod_with_functions.foo() == 123
+"#
+            ),
+            None,
+            None,
+        )
+        .map_err(|e| e.display(py))
+        .unwrap();
+    });
+
+    // re-importing needs the interpreter ID, see `ModuleDef::make_module`
+    #[cfg(all(
+        not(any(PyPy, GraalPy)),
+        Py_3_9,
+        not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+    ))]
+    Python::with_gil(|py| {
+        py.run(
+            ffi::c_str!(
+                r#"
+import sys
+import module_fn_with_functions
+first = module_fn_with_functions
+del sys.modules["module_fn_with_functions"]
+import module_fn_with_functions
+assert module_fn_with_functions is not first
+assert module_fn_with_functions.foo() == 123
 "#
             ),
             None,
@@ -0,0 +1,259 @@
+#![cfg(feature = "macros")]
+
+use pyo3::prelude::*;
+use pyo3::py_run;
+use pyo3::types::PyDict;
+
+#[path = "../src/tests/common.rs"]
+mod common;
+
+#[pyclass(abstract)]
+struct Shape {
+    #[pyo3(get)]
+    name: String,
+}
+
+#[pymethods]
+impl Shape {
+    #[new]
+    fn new(name: String) -> Self {
+        Shape { name }
+    }
+
+    #[pyo3(abstractmethod)]
+    fn area(&self) -> f64 {
+        0.0
+    }
+
+    #[pyo3(abstractmethod)]
+    fn perimeter(&self) -> f64 {
+        0.0
+    }
+
+    fn describe(slf: &Bound<'_, Self>) -> PyResult<String> {
+        let area: f64 = slf.call_method0("area")?.extract()?;
+        Ok(format!("{} with area {}", slf.borrow().name, area))
+    }
+}
+
+#[pyclass(extends = Shape)]
+struct Square {
+    side: f64,
+}
+
+#[pymethods]
+impl Square {
+    #[new]
+    fn new(side: f64) -> (Self, Shape) {
+        (Square { side }, Shape::new("square".into()))
+    }
+
+    fn area(&self) -> f64 {
+        self.side * self.side
+    }
+
+    fn perimeter(&self) -> f64 {
+        4.0 * self.side
+    }
+}
+
+#[pyclass(extends = Shape, abstract)]
+struct Polygon {}
+
+#[pymethods]
+impl Polygon {
+    fn perimeter(&self) -> f64 {
+        1.0
+    }
+}
+
+#[pyclass(register = "collections.abc.Sized")]
+struct Bag {
+    items: Vec<u32>,
+}
+
+#[pymethods]
+impl Bag {
+    fn __len__(&self) -> usize {
+        self.items.len()
+    }
+}
+
+fn abc_globals(py: Python<'_>) -> Bound<'_, PyDict> {
+    let globals = PyDict::new(py);
+    globals.set_item("Shape", py.get_type::<Shape>()).unwrap();
+    globals.set_item("Square", py.get_type::<Square>()).unwrap();
+    globals
+        .set_item("Polygon", py.get_type::<Polygon>())
+        .unwrap();
+    globals.set_item("Bag", py.get_type::<Bag>()).unwrap();
+    globals
+}
+
+#[test]
+fn abstract_class_cannot_be_instantiated() {
+    Python::with_gil(|py| {
+        let globals = abc_globals(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            import inspect
+
+            assert inspect.isabstract(Shape)
+            assert Shape.__abstractmethods__ == frozenset({"area", "perimeter"})
+            assert Shape.area.__isabstractmethod__
+            assert not hasattr(Shape.describe, "__isabstractmethod__")
+
+            try:
+                Shape("circle")
+            except TypeError as e:
+                assert str(e) == (
+                    "Can't instantiate abstract class Shape without an implementation "
+                    "for abstract methods 'area', 'perimeter'"
+                ), str(e)
+            else:
+                assert False, "expected a TypeError"
+            "#
+        );
+    });
+}
+
+#[test]
+fn python_subclass() {
+    Python::with_gil(|py| {
+        let globals = abc_globals(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            import inspect
+
+            class Circle(Shape):
+                def __new__(cls, name, radius):
+                    self = super().__new__(cls, name)
+                    self.radius = radius
+                    return self
+
+                def area(self):
+                    return 3.0 * self.radius ** 2
+
+                def perimeter(self):
+                    return 6.0 * self.radius
+
+            assert not inspect.isabstract(Circle)
+            c = Circle("circle", 2)
+            assert c.area() == 12.0
+            assert c.describe() == "circle with area 12"
+            assert isinstance(c, Shape)
+
+            class Partial(Shape):
+                def area(self):
+                    return super().area() + 1
+
+            assert Partial.__abstractmethods__ == frozenset({"perimeter"})
+            try:
+                Partial("partial")
+            except TypeError as e:
+                assert "abstract method 'perimeter'" in str(e), str(e)
+            else:
+                assert False, "expected a TypeError"
+
+            class Complete(Partial):
+                def perimeter(self):
+                    return 0.5
+
+            assert Complete("complete").area() == 1.0
+            "#
+        );
+    });
+}
+
+#[test]
+fn rust_subclass() {
+    Python::with_gil(|py| {
+        let globals = abc_globals(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            import inspect
+
+            assert not inspect.isabstract(Square)
+            s = Square(2.0)
+            assert s.area() == 4.0
+            assert s.perimeter() == 8.0
+            assert s.describe() == "square with area 4"
+
+            assert Polygon.__abstractmethods__ == frozenset({"area"})
+            try:
+                Polygon("polygon")
+            except TypeError:
+                pass
+            else:
+                assert False, "expected a TypeError"
+            "#
+        );
+    });
+}
+
+#[test]
+fn abc_metaclass() {
+    Python::with_gil(|py| {
+        let globals = abc_globals(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            import abc
+
+            def extra(self): ...
+            Mixin = abc.ABCMeta("Mixin", (abc.ABC,), {"extra": abc.abstractmethod(extra)})
+
+            class Both(Shape, Mixin):
+                def area(self):
+                    return 1.0
+
+                def perimeter(self):
+                    return 2.0
+
+            assert type(Both) is abc.ABCMeta
+            assert Both.__abstractmethods__ == frozenset({"extra"})
+
+            class Concrete(Both):
+                def extra(self):
+                    return 3.0
+
+            c = Concrete("concrete")
+            assert isinstance(c, Mixin)
+            assert c.extra() == 3.0
+
+            class Registered(Shape, metaclass=abc.ABCMeta):
+                def area(self):
+                    return 1.0
+
+            assert Registered.__abstractmethods__ == frozenset({"perimeter"})
+            "#
+        );
+    });
+}
+
+#[test]
+fn register_abc() {
+    Python::with_gil(|py| {
+        let globals = abc_globals(py);
+        let bag = Bound::new(py, Bag { items: vec![1, 2] }).unwrap();
+        globals.set_item("bag", bag).unwrap();
+        py_run!(
+            py,
+            *globals,
+            r#"
+            import collections.abc
+
+            assert issubclass(Bag, collections.abc.Sized)
+            assert isinstance(bag, collections.abc.Sized)
+            assert not issubclass(Bag, collections.abc.Iterable)
+            "#
+        );
+    });
+}
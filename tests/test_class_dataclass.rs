@@ -0,0 +1,203 @@
+#![cfg(feature = "macros")]
+
+use pyo3::prelude::*;
+use pyo3::py_run;
+
+#[path = "../src/tests/common.rs"]
+mod common;
+
+#[pyclass(dataclass)]
+#[derive(PartialEq)]
+struct Point {
+    x: i64,
+    #[pyo3(default)]
+    y: i64,
+}
+
+#[test]
+fn dataclass_constructor() {
+    Python::with_gil(|py| {
+        let point = py.get_type::<Point>();
+        py_run!(
+            py,
+            point,
+            r#"
+            assert point(1, 2).x == 1
+            assert point(1, 2).y == 2
+            assert point(x=3).y == 0
+            assert point(4, y=5).y == 5
+            assert point.__text_signature__ == "(x, y=...)"
+            "#
+        );
+        py_expect_exception!(py, point, "point()", PyTypeError);
+        py_expect_exception!(py, point, "point(1, z=2)", PyTypeError);
+        py_expect_exception!(py, point, "point('1')", PyTypeError);
+    });
+}
+
+#[test]
+fn dataclass_repr_eq_match_args() {
+    Python::with_gil(|py| {
+        let point = py.get_type::<Point>();
+        py_run!(
+            py,
+            point,
+            r#"
+            assert repr(point(1, 2)) == "Point(x=1, y=2)"
+            assert point(1, 2) == point(1, 2)
+            assert point(1, 2) != point(2, 1)
+            assert point(1, 2) != (1, 2)
+            assert point.__match_args__ == ("x", "y")
+            "#
+        );
+    });
+}
+
+#[pyclass(dataclass, subclass)]
+#[derive(PartialEq)]
+struct Base {
+    value: i64,
+}
+
+#[test]
+fn dataclass_python_subclass() {
+    Python::with_gil(|py| {
+        let base = py.get_type::<Base>();
+        py_run!(
+            py,
+            base,
+            r#"
+            class Derived(base):
+                pass
+
+            d = Derived(1)
+            assert repr(d) == "Derived(value=1)"
+            assert type(d.__replace__(value=2)) is Derived
+            "#
+        );
+    });
+}
+
+#[cfg(Py_3_10)]
+#[test]
+fn dataclass_pattern_matching() {
+    Python::with_gil(|py| {
+        let point = py.get_type::<Point>();
+        py_run!(
+            py,
+            point,
+            r#"
+            match point(0, 5):
+                case point(0, y):
+                    assert y == 5
+                case _:
+                    assert False, "expected a match"
+            "#
+        );
+    });
+}
+
+#[test]
+fn dataclass_replace() {
+    Python::with_gil(|py| {
+        let point = py.get_type::<Point>();
+        py_run!(
+            py,
+            point,
+            r#"
+            p = point(1, 2)
+            assert p.__replace__() == p
+            assert p.__replace__() is not p
+            assert p.__replace__(y=3) == point(1, 3)
+
+            import sys
+            if sys.version_info >= (3, 13):
+                import copy
+                assert copy.replace(p, x=0) == point(0, 2)
+            "#
+        );
+        py_expect_exception!(py, point, "point(1).__replace__(z=3)", PyTypeError);
+    });
+}
+
+#[pyclass(dataclass, frozen, hash, rename_all = "camelCase")]
+#[derive(PartialEq, Hash)]
+struct Config {
+    file_name: String,
+    #[pyo3(name = "level", default = 3)]
+    compression_level: u8,
+    #[pyo3(default = vec!["txt".to_owned()])]
+    extensions: Vec<String>,
+    #[pyo3(default)]
+    comment: Option<String>,
+}
+
+#[test]
+fn dataclass_renamed_fields_and_defaults() {
+    Python::with_gil(|py| {
+        let config = py.get_type::<Config>();
+        py_run!(
+            py,
+            config,
+            r#"
+            c = config("a.txt", comment="hello")
+            assert c.fileName == "a.txt"
+            assert c.level == 3
+            assert c.extensions == ["txt"]
+            assert c.comment == "hello"
+            assert repr(c) == "Config(fileName='a.txt', level=3, extensions=['txt'], comment='hello')"
+            assert config.__match_args__ == ("fileName", "level", "extensions", "comment")
+            assert config.__text_signature__ == "(fileName, level=3, extensions=..., comment=...)"
+            assert c.__replace__(level=9, comment=None) == config(fileName="a.txt", level=9)
+            assert hash(c) == hash(config("a.txt", 3, ["txt"], "hello"))
+            "#
+        );
+    });
+}
+
+#[pyclass(dataclass)]
+#[derive(PartialEq)]
+struct CustomRepr {
+    value: i64,
+}
+
+#[pymethods]
+impl CustomRepr {
+    fn __repr__(&self) -> String {
+        format!("<CustomRepr {}>", self.value)
+    }
+}
+
+#[test]
+fn dataclass_user_defined_repr() {
+    Python::with_gil(|py| {
+        let custom_repr = py.get_type::<CustomRepr>();
+        py_assert!(py, custom_repr, "repr(custom_repr(1)) == '<CustomRepr 1>'");
+    });
+}
+
+#[test]
+fn dataclass_from_rust() {
+    Python::with_gil(|py| {
+        let point = Bound::new(py, Point { x: 7, y: 8 }).unwrap();
+        assert_eq!(point.repr().unwrap().to_cow().unwrap(), "Point(x=7, y=8)");
+        let replaced = point.call_method1("__replace__", ()).unwrap();
+        assert!(replaced
+            .downcast::<Point>()
+            .unwrap()
+            .borrow()
+            .eq(&point.borrow()));
+    });
+}
+
+#[test]
+fn dataclass_mutably_borrowed() {
+    Python::with_gil(|py| {
+        let point = Bound::new(py, Point { x: 7, y: 8 }).unwrap();
+        let _guard = point.borrow_mut();
+        let err = point.repr().unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyRuntimeError>(py));
+        let err = point.call_method1("__replace__", ()).unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyRuntimeError>(py));
+    });
+}
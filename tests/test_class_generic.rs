@@ -0,0 +1,167 @@
+#![cfg(feature = "macros")]
+
+use pyo3::prelude::*;
+use pyo3::py_run;
+use pyo3::types::{PyFloat, PyInt, PyString};
+
+#[path = "../src/tests/common.rs"]
+mod common;
+
+// generic `#[pymethods]` can be implemented wherever the class is in scope, even before it
+mod container_methods {
+    use super::Container;
+    use pyo3::prelude::*;
+
+    #[pymethods]
+    impl<E: Clone> Container<E> {
+        #[new]
+        fn new(items: Vec<E>) -> Self {
+            Container { items }
+        }
+
+        pub fn push(&mut self, item: E) {
+            self.items.push(item);
+        }
+
+        fn first(&self) -> Option<E> {
+            self.items.first().cloned()
+        }
+
+        fn __len__(&self) -> usize {
+            self.items.len()
+        }
+    }
+}
+
+#[pyclass(generic(
+    IntContainer = Container<i64> as PyInt,
+    FloatContainer = Container<f64> as PyFloat,
+    StrContainer = Container<String> as PyString,
+))]
+struct Container<T> {
+    #[pyo3(get)]
+    items: Vec<T>,
+}
+
+#[test]
+fn generic_class_instantiations() {
+    Python::with_gil(|py| {
+        let int_container = py.get_type::<IntContainer>();
+        let float_container = py.get_type::<FloatContainer>();
+        let generic = py.get_type::<ContainerGeneric>();
+        py_run!(
+            py,
+            int_container float_container generic,
+            r#"
+            assert int_container.__name__ == "IntContainer"
+            assert float_container.__name__ == "FloatContainer"
+            assert generic.__name__ == "Container"
+
+            assert issubclass(int_container, generic)
+            assert issubclass(float_container, generic)
+
+            c = int_container([1, 2])
+            assert isinstance(c, generic)
+            assert not isinstance(c, float_container)
+            c.push(3)
+            assert len(c) == 3
+            assert c.first() == 1
+            assert c.items == [1, 2, 3]
+
+            f = float_container([])
+            assert f.first() is None
+            f.push(0.5)
+            assert f.first() == 0.5
+
+            try:
+                c.push("not an int")
+            except TypeError:
+                pass
+            else:
+                assert False, "expected a TypeError"
+            "#
+        );
+    });
+}
+
+#[test]
+fn generic_class_getitem() {
+    Python::with_gil(|py| {
+        let int_container = py.get_type::<IntContainer>();
+        let str_container = py.get_type::<StrContainer>();
+        let generic = py.get_type::<ContainerGeneric>();
+        py_run!(
+            py,
+            int_container str_container generic,
+            r#"
+            assert generic[int] is int_container
+            assert generic[str] is str_container
+            assert generic[str](["a"]).first() == "a"
+            "#
+        );
+        py_expect_exception!(py, generic, "generic[bytes]", PyTypeError);
+    });
+}
+
+#[pyclass(generic(
+    Pair = Array<2>,
+    Triple = Array<3> as (PyInt, PyInt, PyInt),
+))]
+struct Array<const N: usize> {
+    values: [i64; N],
+}
+
+#[pymethods]
+impl<const N: usize> Array<N> {
+    #[new]
+    fn new(values: [i64; N]) -> Self {
+        Array { values }
+    }
+
+    #[classattr]
+    fn size() -> usize {
+        N
+    }
+
+    fn sum(&self) -> i64 {
+        self.values.iter().sum()
+    }
+}
+
+#[test]
+fn generic_class_const_parameter() {
+    Python::with_gil(|py| {
+        let pair = py.get_type::<Pair>();
+        let triple = py.get_type::<Triple>();
+        let generic = py.get_type::<ArrayGeneric>();
+        py_run!(
+            py,
+            pair triple generic,
+            r#"
+            assert pair.size == 2
+            assert triple.size == 3
+            assert pair([1, 2]).sum() == 3
+            assert triple([1, 2, 3]).sum() == 6
+            assert generic[int, int, int] is triple
+            "#
+        );
+        py_expect_exception!(py, pair, "pair([1, 2, 3])", PyValueError);
+    });
+}
+
+#[test]
+fn generic_class_from_rust() {
+    Python::with_gil(|py| {
+        let container = Bound::new(py, Container::<i64> { items: vec![1] }).unwrap();
+        assert!(container.is_instance_of::<IntContainer>());
+        assert!(container.is_instance_of::<ContainerGeneric>());
+        container.borrow_mut().push(2);
+        assert_eq!(container.borrow().items, [1, 2]);
+
+        let container = Container::<String> { items: vec![] }
+            .into_pyobject(py)
+            .unwrap();
+        assert!(container.is_instance_of::<StrContainer>());
+        assert!(container.is_instance_of::<ContainerGeneric>());
+    });
+}
@@ -0,0 +1,205 @@
+#![cfg(feature = "macros")]
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::py_run;
+use pyo3::types::{PyDict, PyTuple};
+
+#[path = "../src/tests/common.rs"]
+mod common;
+
+#[pyclass(subclass)]
+struct Counter {
+    #[pyo3(get)]
+    value: i64,
+    #[pyo3(get)]
+    inits: u32,
+}
+
+#[pymethods]
+impl Counter {
+    #[new]
+    #[pyo3(signature = (*_args, **_kwargs))]
+    fn new(_args: &Bound<'_, PyTuple>, _kwargs: Option<&Bound<'_, PyDict>>) -> Self {
+        Counter {
+            value: -1,
+            inits: 0,
+        }
+    }
+
+    #[init]
+    #[pyo3(signature = (value, *, step = 1))]
+    fn init(&mut self, value: i64, step: i64) -> PyResult<()> {
+        if step == 0 {
+            return Err(PyValueError::new_err("step must not be zero"));
+        }
+        self.value = value * step;
+        self.inits += 1;
+        Ok(())
+    }
+}
+
+#[pyclass(extends = Counter)]
+struct InheritsInit {}
+
+#[pymethods]
+impl InheritsInit {
+    #[new]
+    #[pyo3(signature = (*args, **kwargs))]
+    fn new(args: &Bound<'_, PyTuple>, kwargs: Option<&Bound<'_, PyDict>>) -> (Self, Counter) {
+        (InheritsInit {}, Counter::new(args, kwargs))
+    }
+}
+
+#[pyclass(extends = Counter, subclass)]
+struct OverridesInit {
+    #[pyo3(get)]
+    label: String,
+}
+
+#[pymethods]
+impl OverridesInit {
+    #[new]
+    #[pyo3(signature = (*args, **kwargs))]
+    fn new(args: &Bound<'_, PyTuple>, kwargs: Option<&Bound<'_, PyDict>>) -> (Self, Counter) {
+        (
+            OverridesInit {
+                label: String::new(),
+            },
+            Counter::new(args, kwargs),
+        )
+    }
+
+    #[init]
+    fn init(mut slf: PyRefMut<'_, Self>, label: String, value: i64) -> PyResult<()> {
+        slf.label = label;
+        slf.as_super().init(value, 10)
+    }
+}
+
+fn init_globals(py: Python<'_>) -> Bound<'_, PyDict> {
+    let globals = PyDict::new(py);
+    globals
+        .set_item("Counter", py.get_type::<Counter>())
+        .unwrap();
+    globals
+        .set_item("InheritsInit", py.get_type::<InheritsInit>())
+        .unwrap();
+    globals
+        .set_item("OverridesInit", py.get_type::<OverridesInit>())
+        .unwrap();
+    globals
+}
+
+#[test]
+fn init_runs_after_new() {
+    Python::with_gil(|py| {
+        let globals = init_globals(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            c = Counter(3)
+            assert c.value == 3
+            assert c.inits == 1
+
+            c = Counter(3, step=2)
+            assert c.value == 6
+
+            # `__init__` can be called again on an existing instance
+            c.__init__(5)
+            assert c.value == 5
+            assert c.inits == 2
+
+            try:
+                Counter(3, step=0)
+            except ValueError as e:
+                assert str(e) == "step must not be zero"
+            else:
+                assert False, "expected a ValueError"
+
+            try:
+                Counter()
+            except TypeError as e:
+                assert "missing 1 required positional argument: 'value'" in str(e), str(e)
+            else:
+                assert False, "expected a TypeError"
+            "#
+        );
+    });
+}
+
+#[test]
+fn init_not_run_from_rust() {
+    Python::with_gil(|py| {
+        let args = PyTuple::empty(py);
+        let counter = Bound::new(py, Counter::new(&args, None)).unwrap();
+        assert_eq!(counter.borrow().value, -1);
+        assert_eq!(counter.borrow().inits, 0);
+    });
+}
+
+#[test]
+fn python_subclass_init() {
+    Python::with_gil(|py| {
+        let globals = init_globals(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            class CallsSuper(Counter):
+                def __init__(self, value, extra):
+                    super().__init__(value + 1, step=2)
+                    self.extra = extra
+
+            c = CallsSuper(1, "x")
+            assert c.value == 4
+            assert c.inits == 1
+            assert c.extra == "x"
+
+            class SkipsSuper(Counter):
+                def __init__(self, value):
+                    self.skipped = value
+
+            c = SkipsSuper(1)
+            assert c.value == -1
+            assert c.inits == 0
+
+            class InheritsFromPython(Counter):
+                pass
+
+            c = InheritsFromPython(7)
+            assert c.value == 7
+            "#
+        );
+    });
+}
+
+#[test]
+fn rust_subclass_init() {
+    Python::with_gil(|py| {
+        let globals = init_globals(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            c = InheritsInit(2, step=3)
+            assert c.value == 6
+            assert c.inits == 1
+
+            c = OverridesInit("ten", 4)
+            assert c.label == "ten"
+            assert c.value == 40
+            assert c.inits == 1
+
+            class Child(OverridesInit):
+                def __init__(self):
+                    super().__init__("child", 1)
+
+            c = Child()
+            assert c.label == "child"
+            assert c.value == 10
+            "#
+        );
+    });
+}
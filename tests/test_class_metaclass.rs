@@ -0,0 +1,150 @@
+#![cfg(all(feature = "macros", Py_3_12))]
+
+use pyo3::prelude::*;
+use pyo3::py_run;
+use pyo3::sync::OnceExt;
+use pyo3::types::{PyDict, PyType};
+use std::ffi::CString;
+use std::sync::Once;
+
+#[path = "../src/tests/common.rs"]
+mod common;
+
+const META_MODULE: &str = r#"
+registry = []
+
+class Meta(type):
+    def __init__(cls, name, bases, namespace, **kwargs):
+        super().__init__(name, bases, namespace, **kwargs)
+        registry.append(name)
+
+    def __getitem__(cls, key):
+        return f"{cls.__name__}[{key}]"
+
+    @property
+    def label(cls):
+        return cls.__name__.lower()
+
+class MetaWithNew(type):
+    def __new__(mcls, name, bases, namespace):
+        return super().__new__(mcls, name, bases, namespace)
+"#;
+
+/// Makes the metaclasses importable before the type objects using them are created.
+fn import_metaclasses(py: Python<'_>) {
+    static IMPORTED: Once = Once::new();
+    IMPORTED.call_once_py_attached(py, || {
+        PyModule::from_code(
+            py,
+            &CString::new(META_MODULE).unwrap(),
+            pyo3::ffi::c_str!("test_class_metaclass.py"),
+            pyo3::ffi::c_str!("test_class_metaclass"),
+        )
+        .unwrap();
+    });
+}
+
+#[pyclass(metaclass = "test_class_metaclass.Meta", subclass)]
+struct Model {
+    #[pyo3(get)]
+    value: i32,
+}
+
+#[pymethods]
+impl Model {
+    #[new]
+    fn new(value: i32) -> Self {
+        Model { value }
+    }
+}
+
+#[pyclass(extends = Model)]
+struct Derived {}
+
+#[pymethods]
+impl Derived {
+    #[new]
+    fn new(value: i32) -> (Self, Model) {
+        (Derived {}, Model::new(value))
+    }
+}
+
+#[pyclass(metaclass = PyType)]
+struct PlainType {}
+
+#[pyclass(metaclass = "test_class_metaclass.MetaWithNew")]
+struct UnsupportedMetaclass {}
+
+fn metaclass_globals(py: Python<'_>) -> Bound<'_, PyDict> {
+    import_metaclasses(py);
+    let globals = PyDict::new(py);
+    globals.set_item("Model", py.get_type::<Model>()).unwrap();
+    globals
+        .set_item("Derived", py.get_type::<Derived>())
+        .unwrap();
+    globals
+        .set_item("meta", py.import("test_class_metaclass").unwrap())
+        .unwrap();
+    globals
+}
+
+#[test]
+fn python_metaclass() {
+    Python::with_gil(|py| {
+        let globals = metaclass_globals(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            assert type(Model) is meta.Meta
+            assert isinstance(Model, meta.Meta)
+            assert Model["x"] == "Model[x]"
+            assert Model.label == "model"
+            assert Model(3).value == 3
+            "#
+        );
+    });
+}
+
+#[test]
+fn metaclass_subclasses() {
+    Python::with_gil(|py| {
+        let globals = metaclass_globals(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            assert type(Derived) is meta.Meta
+            assert Derived.label == "derived"
+            assert Derived(4).value == 4
+
+            class Child(Model):
+                pass
+
+            assert type(Child) is meta.Meta
+            assert "Child" in meta.registry
+            assert Child[1] == "Child[1]"
+            assert Child(5).value == 5
+            "#
+        );
+    });
+}
+
+#[test]
+fn rust_metaclass() {
+    Python::with_gil(|py| {
+        let cls = py.get_type::<PlainType>();
+        assert!(cls.get_type().is(&py.get_type::<PyType>()));
+    });
+}
+
+#[test]
+fn metaclass_with_new_is_rejected() {
+    Python::with_gil(|py| {
+        import_metaclasses(py);
+        let module = PyModule::new(py, "test_module").unwrap();
+        let err = module.add_class::<UnsupportedMetaclass>().unwrap_err();
+        let cause = err.cause(py).unwrap();
+        assert!(cause.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+    });
+}
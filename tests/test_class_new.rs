@@ -323,3 +323,128 @@ fn test_new_class_method() {
         pyo3::py_run!(py, cls, "assert cls().cls is cls");
     });
 }
+
+#[pyclass]
+struct NewWithSignature {
+    #[pyo3(get)]
+    args: (i32, i32, Vec<i32>),
+    #[pyo3(get)]
+    kwargs: Option<PyObject>,
+}
+
+#[pymethods]
+impl NewWithSignature {
+    #[new]
+    #[pyo3(signature = (a, b = 2, *args, **kwargs))]
+    fn new(a: i32, b: i32, args: Vec<i32>, kwargs: Option<Bound<'_, PyAny>>) -> Self {
+        Self {
+            args: (a, b, args),
+            kwargs: kwargs.map(Bound::unbind),
+        }
+    }
+}
+
+#[test]
+fn new_with_signature() {
+    Python::with_gil(|py| {
+        let cls = py.get_type::<NewWithSignature>();
+        pyo3::py_run!(
+            py,
+            cls,
+            r#"
+            assert cls(1).args == (1, 2, [])
+            assert cls(1, b=3).args == (1, 3, [])
+            assert cls(1, 3, 4, 5).args == (1, 3, [4, 5])
+            assert cls(1, c=3).kwargs == {"c": 3}
+            assert cls(*[1], **{"b": 4}).args == (1, 4, [])
+            try:
+                cls()
+            except TypeError as e:
+                assert "missing 1 required positional argument: 'a'" in str(e)
+            else:
+                assert False, "expected a TypeError"
+            "#
+        );
+    });
+}
+
+#[test]
+#[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))]
+fn new_uses_vectorcall() {
+    use pyo3::types::PyTypeMethods;
+
+    Python::with_gil(|py| {
+        let cls = py.get_type::<NewWithSignature>();
+        assert!(unsafe { (*cls.as_type_ptr()).tp_vectorcall.is_some() });
+        let class_method = py.get_type::<NewClassMethod>();
+        assert!(unsafe { (*class_method.as_type_ptr()).tp_vectorcall.is_some() });
+    });
+}
+
+#[cfg(not(Py_LIMITED_API))]
+#[pyclass(extends = pyo3::exceptions::PyException)]
+struct NewException {
+    #[pyo3(get)]
+    code: i32,
+}
+
+#[cfg(not(Py_LIMITED_API))]
+#[pymethods]
+impl NewException {
+    #[new]
+    #[pyo3(signature = (code, *_args))]
+    fn new(code: i32, _args: &Bound<'_, pyo3::types::PyTuple>) -> Self {
+        Self { code }
+    }
+}
+
+#[test]
+#[cfg(not(Py_LIMITED_API))]
+fn new_runs_base_init() {
+    Python::with_gil(|py| {
+        let cls = py.get_type::<NewException>();
+        pyo3::py_run!(
+            py,
+            cls,
+            r#"
+            e = cls(1, "message")
+            assert e.code == 1
+            assert e.args == (1, "message")
+            "#
+        );
+    });
+}
+
+#[pyclass(subclass)]
+struct NewSubclassable {
+    #[pyo3(get)]
+    value: i32,
+}
+
+#[pymethods]
+impl NewSubclassable {
+    #[new]
+    fn new(value: i32) -> Self {
+        Self { value }
+    }
+}
+
+#[test]
+fn new_python_subclass_init() {
+    Python::with_gil(|py| {
+        let cls = py.get_type::<NewSubclassable>();
+        pyo3::py_run!(
+            py,
+            cls,
+            r#"
+            class Sub(cls):
+                def __init__(self, value):
+                    self.double = value * 2
+
+            s = Sub(3)
+            assert s.value == 3
+            assert s.double == 6
+            "#
+        );
+    });
+}
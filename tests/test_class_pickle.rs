@@ -0,0 +1,232 @@
+#![cfg(feature = "macros")]
+
+use pyo3::prelude::*;
+use pyo3::py_run;
+use pyo3::types::{PyDict, PyModule};
+
+#[path = "../src/tests/common.rs"]
+mod common;
+
+#[pyclass(pickle, eq, module = "test_class_pickle")]
+#[derive(PartialEq)]
+struct Point {
+    x: i64,
+    y: i64,
+    label: Option<String>,
+}
+
+#[pymethods]
+impl Point {
+    #[new]
+    #[pyo3(signature = (x, y, label = None))]
+    fn new(x: i64, y: i64, label: Option<String>) -> Self {
+        Point { x, y, label }
+    }
+}
+
+#[pyclass(pickle, frozen, eq, module = "test_class_pickle")]
+#[derive(PartialEq)]
+struct Pair(i64, String);
+
+#[pymethods]
+impl Pair {
+    #[new]
+    fn new(first: i64, second: String) -> Self {
+        Pair(first, second)
+    }
+}
+
+/// A class which cannot be created from Python except by unpickling.
+#[pyclass(pickle, module = "test_class_pickle")]
+struct Handle {
+    #[pyo3(get)]
+    id: u32,
+}
+
+#[pyclass(pickle, subclass, module = "test_class_pickle")]
+struct Base {
+    #[pyo3(get)]
+    base_value: i64,
+}
+
+#[pymethods]
+impl Base {
+    #[new]
+    fn new(base_value: i64) -> Self {
+        Base { base_value }
+    }
+}
+
+#[pyclass(pickle, extends = Base, module = "test_class_pickle")]
+struct Child {
+    #[pyo3(get)]
+    child_value: Vec<String>,
+}
+
+#[pymethods]
+impl Child {
+    #[new]
+    fn new(base_value: i64, child_value: Vec<String>) -> (Self, Base) {
+        (Child { child_value }, Base { base_value })
+    }
+}
+
+/// Registers the classes in a `test_class_pickle` module, where pickle looks them up.
+fn pickle_module(py: Python<'_>) -> Bound<'_, PyDict> {
+    let module = PyModule::new(py, "test_class_pickle").unwrap();
+    module.add_class::<Point>().unwrap();
+    module.add_class::<Pair>().unwrap();
+    module.add_class::<Handle>().unwrap();
+    module.add_class::<Base>().unwrap();
+    module.add_class::<Child>().unwrap();
+    py.import("sys")
+        .unwrap()
+        .getattr("modules")
+        .unwrap()
+        .set_item("test_class_pickle", &module)
+        .unwrap();
+    let globals = PyDict::new(py);
+    globals.set_item("m", module).unwrap();
+    globals
+}
+
+#[test]
+fn pickle_round_trip() {
+    Python::with_gil(|py| {
+        let globals = pickle_module(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            import copy, pickle
+
+            p = m.Point(1, 2, "a")
+            for protocol in range(pickle.HIGHEST_PROTOCOL + 1):
+                assert pickle.loads(pickle.dumps(p, protocol)) == p
+            assert copy.copy(p) == p
+            assert copy.copy(p) is not p
+            assert copy.deepcopy(m.Point(3, 4)) == m.Point(3, 4)
+
+            pair = m.Pair(5, "five")
+            assert pickle.loads(pickle.dumps(pair)) == pair
+            "#
+        );
+    });
+}
+
+#[test]
+fn pickle_state() {
+    Python::with_gil(|py| {
+        let globals = pickle_module(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            reconstructor, args, state = m.Point(1, 2).__reduce__()
+            assert reconstructor is m._pyo3_reconstruct_Point
+            assert args == (m.Point, (None, {"x": 1, "y": 2, "label": None}))
+            assert state is None
+            assert m.Pair(1, "a").__reduce__()[1][1][1] == {"0": 1, "1": "a"}
+            "#
+        );
+    });
+}
+
+#[test]
+fn pickle_without_constructor() {
+    Python::with_gil(|py| {
+        let globals = pickle_module(py);
+        let handle = Bound::new(py, Handle { id: 7 }).unwrap();
+        globals.set_item("handle", handle).unwrap();
+        py_run!(
+            py,
+            *globals,
+            r#"
+            import pickle
+            assert pickle.loads(pickle.dumps(handle)).id == 7
+            "#
+        );
+        let handle_type = py.get_type::<Handle>();
+        py_expect_exception!(py, handle_type, "handle_type()", PyTypeError);
+    });
+}
+
+#[test]
+fn pickle_invalid_state() {
+    Python::with_gil(|py| {
+        let globals = pickle_module(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            try:
+                m._pyo3_reconstruct_Point(m.Point, (None, {"x": 1}))
+            except KeyError as e:
+                assert e.args == ("y",)
+            else:
+                assert False, "expected a KeyError"
+
+            try:
+                m._pyo3_reconstruct_Point(m.Pair, (None, {"0": 1, "1": "a"}))
+            except TypeError as e:
+                assert str(e) == "Pair is not a subtype of Point"
+            else:
+                assert False, "expected a TypeError"
+
+            try:
+                m.Point(__pyo3_state__=(None, {"x": 1, "y": 2, "label": None}))
+            except TypeError:
+                pass
+            else:
+                assert False, "expected a TypeError"
+            "#
+        );
+    });
+}
+
+#[test]
+fn pickle_python_subclass() {
+    Python::with_gil(|py| {
+        let globals = pickle_module(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            import copy, pickle
+
+            class Derived(m.Base):
+                pass
+
+            Derived.__module__ = "test_class_pickle"
+            m.Derived = Derived
+
+            d = Derived(3)
+            d.extra = [1, 2]
+            for restored in [pickle.loads(pickle.dumps(d)), copy.deepcopy(d)]:
+                assert type(restored) is Derived
+                assert restored.base_value == 3
+                assert restored.extra == [1, 2]
+            "#
+        );
+    });
+}
+
+#[test]
+fn pickle_extends() {
+    Python::with_gil(|py| {
+        let globals = pickle_module(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            import pickle
+
+            c = m.Child(1, ["a", "b"])
+            restored = pickle.loads(pickle.dumps(c))
+            assert type(restored) is m.Child
+            assert restored.base_value == 1
+            assert restored.child_value == ["a", "b"]
+            "#
+        );
+    });
+}
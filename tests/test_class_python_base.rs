@@ -0,0 +1,358 @@
+#![cfg(feature = "macros")]
+
+use pyo3::prelude::*;
+use pyo3::py_run;
+use pyo3::sync::OnceExt;
+use pyo3::types::{PyDict, PyString};
+use pyo3::{PyClass, PyTraverseError, PyVisit};
+use std::ffi::CString;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Once;
+
+#[path = "../src/tests/common.rs"]
+mod common;
+
+const BASE_MODULE: &str = r#"
+class Base:
+    def __init__(self, *args, **kwargs):
+        self.init_args = args
+
+    def greet(self):
+        return f"hello from {type(self).__name__}"
+
+    def describe(self):
+        return f"{self.label} of size {self.size}"
+
+class SlottedBase:
+    __slots__ = ("x", "y")
+
+    def total(self):
+        return self.x + self.y
+
+class Registry(dict):
+    def names(self):
+        return sorted(self)
+
+class WithNew:
+    def __new__(cls):
+        return super().__new__(cls)
+"#;
+
+/// Makes the base classes importable before the type objects extending them are created.
+fn import_bases(py: Python<'_>) {
+    static IMPORTED: Once = Once::new();
+    IMPORTED.call_once_py_attached(py, || {
+        PyModule::from_code(
+            py,
+            &CString::new(BASE_MODULE).unwrap(),
+            pyo3::ffi::c_str!("test_class_python_base.py"),
+            pyo3::ffi::c_str!("test_class_python_base"),
+        )
+        .unwrap();
+    });
+}
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+#[pyclass(extends = "test_class_python_base.Base", subclass)]
+struct Widget {
+    #[pyo3(get, set)]
+    size: u32,
+    #[pyo3(get)]
+    label: Py<PyString>,
+}
+
+#[pymethods]
+impl Widget {
+    #[new]
+    #[pyo3(signature = (size, *_args))]
+    fn new(py: Python<'_>, size: u32, _args: &Bound<'_, PyAny>) -> Self {
+        Widget {
+            size,
+            label: PyString::new(py, "widget").unbind(),
+        }
+    }
+
+    fn area(&self) -> u32 {
+        self.size * self.size
+    }
+
+    fn base_greeting(slf: &Bound<'_, Self>) -> PyResult<String> {
+        slf.call_method0("greet")?.extract()
+    }
+}
+
+#[pyclass(extends = "test_class_python_base.Base")]
+struct Tracked {}
+
+#[pymethods]
+impl Tracked {
+    #[new]
+    fn new() -> Self {
+        Tracked {}
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[pyclass(extends = Widget)]
+struct Button {
+    #[pyo3(get)]
+    clicks: u64,
+}
+
+#[pymethods]
+impl Button {
+    #[new]
+    fn new(py: Python<'_>) -> (Self, Widget) {
+        let widget = Widget {
+            size: 2,
+            label: PyString::new(py, "button").unbind(),
+        };
+        (Button { clicks: 0 }, widget)
+    }
+
+    fn click(mut slf: PyRefMut<'_, Self>) -> u32 {
+        slf.clicks += 1;
+        slf.as_super().size
+    }
+}
+
+#[pyclass(extends = "test_class_python_base.SlottedBase")]
+struct Point {
+    #[pyo3(get)]
+    z: i64,
+}
+
+#[pymethods]
+impl Point {
+    #[new]
+    fn new(z: i64) -> Self {
+        Point { z }
+    }
+}
+
+#[cfg(any(not(Py_LIMITED_API), Py_3_12))]
+#[pyclass(extends = "test_class_python_base.Registry")]
+struct Catalog {
+    #[pyo3(get)]
+    owner: String,
+}
+
+#[cfg(any(not(Py_LIMITED_API), Py_3_12))]
+#[pymethods]
+impl Catalog {
+    #[new]
+    #[pyo3(signature = (*_args))]
+    fn new(_args: &Bound<'_, PyAny>) -> Self {
+        Catalog { owner: "me".into() }
+    }
+}
+
+#[cfg(any(Py_3_9, not(Py_LIMITED_API)))]
+#[pyclass(extends = Widget, dict)]
+struct WidgetWithDict {}
+
+#[pyclass(extends = "test_class_python_base.Base")]
+struct Traversed {
+    value: Option<PyObject>,
+}
+
+#[pymethods]
+impl Traversed {
+    fn __traverse__(&self, visit: PyVisit<'_>) -> Result<(), PyTraverseError> {
+        visit.call(&self.value)
+    }
+}
+
+#[pyclass(extends = "test_class_python_base.WithNew")]
+struct OverridesNew {}
+
+fn python_base_globals(py: Python<'_>) -> Bound<'_, PyDict> {
+    import_bases(py);
+    let globals = PyDict::new(py);
+    globals.set_item("Widget", py.get_type::<Widget>()).unwrap();
+    globals.set_item("Button", py.get_type::<Button>()).unwrap();
+    globals.set_item("Point", py.get_type::<Point>()).unwrap();
+    globals
+        .set_item("bases", py.import("test_class_python_base").unwrap())
+        .unwrap();
+    globals
+}
+
+#[test]
+fn extends_python_class() {
+    Python::with_gil(|py| {
+        let globals = python_base_globals(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            assert Widget.__base__ is bases.Base
+            assert issubclass(Widget, bases.Base)
+
+            w = Widget(3, "extra")
+            assert isinstance(w, bases.Base)
+            assert w.init_args == (3, "extra")
+            assert w.size == 3
+            assert w.label == "widget"
+            assert w.area() == 9
+            assert w.greet() == "hello from Widget"
+            assert w.base_greeting() == "hello from Widget"
+            assert w.describe() == "widget of size 3"
+
+            w.size = 4
+            w.color = "red"
+            assert w.area() == 16
+            assert w.__dict__ == {"init_args": (3, "extra"), "color": "red"}
+            "#
+        );
+    });
+}
+
+#[test]
+fn rust_subclass() {
+    Python::with_gil(|py| {
+        let globals = python_base_globals(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            assert Button.__mro__ == (Button, Widget, bases.Base, object)
+
+            b = Button()
+            assert b.click() == 2
+            assert b.click() == 2
+            assert b.clicks == 2
+            assert b.describe() == "button of size 2"
+            assert b.greet() == "hello from Button"
+            "#
+        );
+    });
+}
+
+#[test]
+fn python_subclass() {
+    Python::with_gil(|py| {
+        let globals = python_base_globals(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            class Custom(Widget):
+                __slots__ = ("extra",)
+
+                def __init__(self, size):
+                    super().__init__(size)
+                    self.extra = size + 1
+
+                def greet(self):
+                    return "custom " + super().greet()
+
+            c = Custom(5)
+            assert c.extra == 6
+            assert c.area() == 25
+            assert c.init_args == (5,)
+            assert c.base_greeting() == "custom hello from Custom"
+            "#
+        );
+    });
+}
+
+#[test]
+fn slotted_python_base() {
+    Python::with_gil(|py| {
+        let globals = python_base_globals(py);
+        py_run!(
+            py,
+            *globals,
+            r#"
+            p = Point(3)
+            p.x = 1
+            p.y = 2
+            assert p.total() == 3
+            assert p.z == 3
+            assert not hasattr(p, "__dict__")
+            "#
+        );
+    });
+}
+
+#[test]
+#[cfg(any(not(Py_LIMITED_API), Py_3_12))]
+fn python_base_extending_native_type() {
+    Python::with_gil(|py| {
+        let globals = python_base_globals(py);
+        globals
+            .set_item("Catalog", py.get_type::<Catalog>())
+            .unwrap();
+        py_run!(
+            py,
+            *globals,
+            r#"
+            # the arguments are also passed to `dict.__init__`
+            c = Catalog({"b": 1})
+            assert isinstance(c, dict)
+            c["a"] = [c]
+            assert c.names() == ["a", "b"]
+            assert len(c) == 2
+            assert c.owner == "me"
+            del c
+            "#
+        );
+    });
+}
+
+#[test]
+fn drop_and_collect() {
+    Python::with_gil(|py| {
+        let globals = python_base_globals(py);
+        globals
+            .set_item("Tracked", py.get_type::<Tracked>())
+            .unwrap();
+        py_run!(
+            py,
+            *globals,
+            r#"
+            import gc
+            import weakref
+
+            t = Tracked()
+            ref = weakref.ref(t)
+            del t
+            assert ref() is None
+
+            # a cycle through the `__dict__` of the Python base
+            t = Tracked()
+            t.me = t
+            ref = weakref.ref(t)
+            del t
+            gc.collect()
+            assert ref() is None
+            "#
+        );
+        assert_eq!(DROPPED.load(Ordering::SeqCst), 2);
+    });
+}
+
+#[test]
+fn unsupported_python_base_classes() {
+    fn assert_type_error<T: PyClass>(py: Python<'_>) {
+        let module = PyModule::new(py, "test_module").unwrap();
+        let err = module.add_class::<T>().unwrap_err();
+        let cause = err.cause(py).unwrap();
+        assert!(cause.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+    }
+
+    Python::with_gil(|py| {
+        import_bases(py);
+        #[cfg(any(Py_3_9, not(Py_LIMITED_API)))]
+        assert_type_error::<WidgetWithDict>(py);
+        assert_type_error::<Traversed>(py);
+        assert_type_error::<OverridesNew>(py);
+    });
+}
@@ -9,7 +9,9 @@ fn test_compile_errors() {
     t.compile_fail("tests/ui/invalid_proto_pymethods.rs");
     t.compile_fail("tests/ui/invalid_pyclass_args.rs");
     t.compile_fail("tests/ui/invalid_pyclass_enum.rs");
+    t.compile_fail("tests/ui/invalid_pyclass_init.rs");
     t.compile_fail("tests/ui/invalid_pyclass_item.rs");
+    t.compile_fail("tests/ui/invalid_dataclass_base.rs");
     t.compile_fail("tests/ui/invalid_pyfunction_signatures.rs");
     t.compile_fail("tests/ui/invalid_pyfunction_definition.rs");
     #[cfg(any(not(Py_LIMITED_API), Py_3_11))]
@@ -33,7 +35,7 @@ fn test_compile_errors() {
     #[cfg(not(any(feature = "hashbrown", feature = "indexmap")))]
     t.compile_fail("tests/ui/invalid_pymethods.rs");
     // output changes with async feature
-    #[cfg(all(Py_LIMITED_API, feature = "experimental-async"))]
+    #[cfg(all(Py_LIMITED_API, not(Py_3_12), feature = "experimental-async"))]
     t.compile_fail("tests/ui/abi3_nativetype_inheritance.rs");
     t.compile_fail("tests/ui/invalid_intern_arg.rs");
     t.compile_fail("tests/ui/invalid_frozen_pyclass_borrow.rs");
@@ -57,7 +59,7 @@ fn test_compile_errors() {
     t.pass("tests/ui/pymodule_missing_docs.rs");
     #[cfg(not(Py_LIMITED_API))]
     t.pass("tests/ui/forbid_unsafe.rs");
-    #[cfg(all(Py_LIMITED_API, not(feature = "experimental-async")))]
+    #[cfg(all(Py_LIMITED_API, not(Py_3_12), not(feature = "experimental-async")))]
     // output changes with async feature
     t.compile_fail("tests/ui/abi3_inheritance.rs");
     #[cfg(all(Py_LIMITED_API, not(Py_3_9)))]
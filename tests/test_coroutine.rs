@@ -1,16 +1,22 @@
 #![cfg(feature = "experimental-async")]
 #![cfg(not(target_arch = "wasm32"))]
-use std::{ffi::CString, task::Poll, thread, time::Duration};
+use std::{
+    cell::Cell, ffi::CString, future::Future, pin::Pin, rc::Rc, task::Poll, thread, time::Duration,
+};
 
-use futures::{channel::oneshot, future::poll_fn, FutureExt};
+use futures::{channel::oneshot, future::poll_fn, stream, FutureExt, Stream, StreamExt};
 #[cfg(not(target_has_atomic = "64"))]
 use portable_atomic::{AtomicBool, Ordering};
 use pyo3::{
-    coroutine::CancelHandle,
+    coroutine::{
+        future_into_py, register_async_library, set_runtime, AsyncIterator, AsyncLibrary,
+        AsyncioWaker, CancelHandle, CoroutineWaker, PyFuture, Runtime,
+    },
     prelude::*,
     py_run,
     types::{IntoPyDict, PyType},
 };
+use std::sync::atomic::AtomicUsize;
 #[cfg(target_has_atomic = "64")]
 use std::sync::atomic::{AtomicBool, Ordering};
 
@@ -357,3 +363,642 @@ fn test_async_method_receiver_with_other_args() {
         py_run!(gil, *locals, test);
     });
 }
+
+#[test]
+fn await_python_awaitable() {
+    #[pyfunction]
+    async fn call_async(callback: PyObject) -> PyResult<PyObject> {
+        let future = Python::with_gil(|py| PyFuture::new(&callback.bind(py).call0()?))?;
+        future.await
+    }
+    Python::with_gil(|gil| {
+        let call_async = wrap_pyfunction!(call_async, gil).unwrap();
+        let test = r#"
+        import asyncio
+
+        async def answer():
+            import asyncio
+            await asyncio.sleep(0.01)
+            return 42
+
+        async def fail():
+            raise ValueError("failed")
+
+        assert asyncio.run(call_async(answer)) == 42
+        try:
+            asyncio.run(call_async(fail))
+        except ValueError as err:
+            assert str(err) == "failed"
+        else:
+            assert False
+        "#;
+        py_run!(gil, call_async, &handle_windows(test));
+    })
+}
+
+#[test]
+fn future_on_runtime() {
+    struct ThreadRuntime;
+
+    impl Runtime for ThreadRuntime {
+        fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send + 'static>>) {
+            thread::spawn(move || futures::executor::block_on(future));
+        }
+    }
+
+    #[pyfunction]
+    fn add_on_runtime<'py>(
+        py: Python<'py>,
+        callback: PyObject,
+        value: i32,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+        let event_loop = event_loop.unbind();
+        future_into_py(py, async move {
+            // not in the event loop thread, so the awaitable is sent to the event loop
+            let future = Python::with_gil(|py| {
+                PyFuture::with_event_loop(&callback.bind(py).call0()?, event_loop.bind(py))
+            })?;
+            let other = future.await?;
+            Python::with_gil(|py| Ok(value + other.extract::<i32>(py)?))
+        })
+    }
+
+    async fn panic() -> PyResult<()> {
+        panic!("test panic")
+    }
+
+    #[pyfunction]
+    fn panic_on_runtime(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        future_into_py(py, panic())
+    }
+
+    set_runtime(ThreadRuntime);
+    Python::with_gil(|gil| {
+        let locals = [
+            (
+                "add_on_runtime",
+                wrap_pyfunction!(add_on_runtime, gil).unwrap(),
+            ),
+            (
+                "panic_on_runtime",
+                wrap_pyfunction!(panic_on_runtime, gil).unwrap(),
+            ),
+        ]
+        .into_py_dict(gil)
+        .unwrap();
+        let test = r#"
+        import asyncio
+
+        async def answer():
+            import asyncio
+            await asyncio.sleep(0.01)
+            return 40
+
+        async def run(function, *args):
+            return await function(*args)
+
+        assert asyncio.run(run(add_on_runtime, answer, 2)) == 42
+        try:
+            asyncio.run(run(panic_on_runtime))
+        except BaseException as err:
+            assert type(err).__name__ == "PanicException"
+            assert str(err) == "test panic"
+        else:
+            assert False
+        "#;
+        py_run!(gil, *locals, &handle_windows(test));
+    })
+}
+
+#[test]
+fn stream_async_iterator() {
+    #[pyfunction]
+    fn count(n: u32) -> AsyncIterator {
+        AsyncIterator::new(stream::iter(0..n).then(|i| async move {
+            if i == 3 {
+                Err(pyo3::exceptions::PyValueError::new_err("three"))
+            } else {
+                Ok(i)
+            }
+        }))
+    }
+
+    #[pyfunction]
+    fn never() -> AsyncIterator {
+        AsyncIterator::new(stream::pending::<PyResult<u32>>())
+    }
+
+    Python::with_gil(|gil| {
+        let locals = [
+            ("count", wrap_pyfunction!(count, gil).unwrap()),
+            ("never", wrap_pyfunction!(never, gil).unwrap()),
+        ]
+        .into_py_dict(gil)
+        .unwrap();
+        let test = r#"
+        import asyncio
+
+        async def collect(iterator):
+            return [item async for item in iterator]
+
+        assert asyncio.run(collect(count(3))) == [0, 1, 2]
+        try:
+            asyncio.run(collect(count(5)))
+        except ValueError as err:
+            assert str(err) == "three"
+        else:
+            assert False
+
+        async def cancel_next(iterator):
+            import asyncio
+            try:
+                await asyncio.wait_for(iterator.__anext__(), 0.01)
+            except asyncio.TimeoutError:
+                pass
+            else:
+                assert False
+            try:
+                await iterator.__anext__()
+            except StopAsyncIteration:
+                pass
+            else:
+                assert False
+
+        asyncio.run(cancel_next(never()))
+
+        async def close(iterator):
+            assert await iterator.__anext__() == 0
+            await iterator.aclose()
+            return [item async for item in iterator]
+
+        assert asyncio.run(close(count(3))) == []
+
+        async def concurrent_next(iterator):
+            first = asyncio.ensure_future(iterator.__anext__())
+            await asyncio.sleep(0)
+            try:
+                await iterator.__anext__()
+            except RuntimeError as err:
+                assert str(err) == "anext(): asynchronous generator is already running"
+            else:
+                assert False
+            first.cancel()
+            try:
+                await first
+            except asyncio.CancelledError:
+                pass
+
+        asyncio.run(concurrent_next(never()))
+
+        async def sequential_next(iterator):
+            assert await iterator.__anext__() == 0
+            assert await iterator.__anext__() == 1
+
+        asyncio.run(sequential_next(count(3)))
+        "#;
+        py_run!(gil, *locals, &handle_windows(test));
+    })
+}
+
+#[test]
+fn stream_return_type() {
+    #[pyfunction]
+    fn count(n: u32) -> impl Stream<Item = PyResult<u32>> + Send + 'static {
+        stream::iter((0..n).map(Ok))
+    }
+
+    #[pyfunction]
+    fn checked_count(n: i32) -> PyResult<impl Stream<Item = PyResult<i32>> + Send + 'static> {
+        if n < 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err("negative count"));
+        }
+        Ok(stream::iter((0..n).map(Ok)))
+    }
+
+    #[pyclass]
+    struct Repeater {
+        value: u32,
+    }
+
+    #[pymethods]
+    impl Repeater {
+        #[new]
+        fn new(value: u32) -> Self {
+            Self { value }
+        }
+
+        fn repeat(&self, n: usize) -> impl Stream<Item = PyResult<u32>> + Send + 'static {
+            stream::repeat(self.value).take(n).map(Ok)
+        }
+    }
+
+    Python::with_gil(|gil| {
+        let locals = [
+            ("count", wrap_pyfunction!(count, gil).unwrap().into_any()),
+            (
+                "checked_count",
+                wrap_pyfunction!(checked_count, gil).unwrap().into_any(),
+            ),
+            ("Repeater", gil.get_type::<Repeater>().into_any()),
+        ]
+        .into_py_dict(gil)
+        .unwrap();
+        let test = r#"
+        import asyncio
+
+        async def collect(iterator):
+            return [item async for item in iterator]
+
+        assert asyncio.run(collect(count(3))) == [0, 1, 2]
+        assert asyncio.run(collect(checked_count(2))) == [0, 1]
+        try:
+            checked_count(-1)
+        except ValueError as err:
+            assert str(err) == "negative count"
+        else:
+            assert False
+        assert asyncio.run(collect(Repeater(7).repeat(2))) == [7, 7]
+        "#;
+        py_run!(gil, *locals, &handle_windows(test));
+    })
+}
+
+#[test]
+fn local_coroutine() {
+    async fn yield_now() {
+        let mut yielded = false;
+        poll_fn(|cx| {
+            if yielded {
+                return Poll::Ready(());
+            }
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        })
+        .await
+    }
+
+    #[pyfunction]
+    #[pyo3(local)]
+    async fn count_local(n: usize) -> usize {
+        // `Rc` is not `Send`, and is held across `await`
+        let counter = Rc::new(Cell::new(0));
+        for _ in 0..n {
+            yield_now().await;
+            counter.set(counter.get() + 1);
+        }
+        counter.get()
+    }
+
+    #[pyclass(unsendable)]
+    struct Counter(Cell<usize>);
+
+    #[pymethods]
+    impl Counter {
+        #[new]
+        fn new() -> Self {
+            Self(Cell::new(0))
+        }
+
+        #[pyo3(local)]
+        async fn increment(&self) -> usize {
+            yield_now().await;
+            self.0.set(self.0.get() + 1);
+            self.0.get()
+        }
+
+        #[pyo3(local)]
+        async fn reset(&mut self) {
+            yield_now().await;
+            self.0.set(0);
+        }
+    }
+
+    #[pyclass(unsendable)]
+    struct Value(usize);
+
+    #[pymethods]
+    impl Value {
+        #[new]
+        fn new() -> Self {
+            Self(42)
+        }
+
+        async fn get(&self) -> usize {
+            self.0
+        }
+    }
+
+    Python::with_gil(|gil| {
+        let locals = [
+            (
+                "count_local",
+                wrap_pyfunction!(count_local, gil).unwrap().into_any(),
+            ),
+            ("Counter", gil.get_type::<Counter>().into_any()),
+            ("Value", gil.get_type::<Value>().into_any()),
+        ]
+        .into_py_dict(gil)
+        .unwrap();
+        let test = r#"
+        import asyncio, sys, threading
+
+        assert asyncio.run(count_local(3)) == 3
+        counter = Counter()
+        assert asyncio.run(counter.increment()) == 1
+        assert asyncio.run(counter.increment()) == 2
+
+        unraisable = []
+        default_hook, sys.unraisablehook = sys.unraisablehook, unraisable.append
+        errors = []
+        def run(coro, errors=errors):
+            import asyncio
+            try:
+                asyncio.run(coro)
+            except RuntimeError as err:
+                errors.append(str(err))
+        for coro in [count_local(1), counter.reset()]:
+            thread = threading.Thread(target=run, args=(coro,))
+            thread.start()
+            thread.join()
+            del coro
+        sys.unraisablehook = default_hook
+        assert errors == ["local coroutine can only be awaited on the thread which created it"] * 2
+        assert len(unraisable) == 2
+        for hook_args in unraisable:
+            assert str(hook_args.exc_value) == "local coroutine is being dropped on another thread than the one which created it"
+        # the borrow of the leaked coroutine has been released
+        assert asyncio.run(counter.increment()) == 3
+
+        # unsendable instances cannot be borrowed by coroutines of another thread
+        value = Value()
+        def get(value=value, errors=errors):
+            try:
+                value.get()
+            except BaseException as err:
+                errors.append(str(err))
+        thread = threading.Thread(target=get)
+        thread.start()
+        thread.join()
+        assert "is unsendable, but sent to another thread" in errors[-1]
+        "#;
+        py_run!(gil, *locals, &handle_windows(test));
+    })
+}
+
+#[test]
+fn trio_coroutine() {
+    #[pyfunction]
+    async fn sleep_0() -> usize {
+        let mut waken = false;
+        poll_fn(|cx| {
+            if !waken {
+                cx.waker().wake_by_ref();
+                waken = true;
+                return Poll::Pending;
+            }
+            Poll::Ready(42)
+        })
+        .await
+    }
+
+    #[pyfunction]
+    async fn cancellable_sleep(
+        seconds: f64,
+        #[pyo3(cancel_handle)] mut cancel: CancelHandle,
+    ) -> usize {
+        futures::select! {
+            _ = sleep(seconds).fuse() => 42,
+            _ = cancel.cancelled().fuse() => 0,
+        }
+    }
+
+    Python::with_gil(|gil| {
+        if gil.import("trio").is_err() {
+            return;
+        }
+        let locals = [
+            ("sleep", wrap_pyfunction!(sleep, gil).unwrap()),
+            ("sleep_0", wrap_pyfunction!(sleep_0, gil).unwrap()),
+            (
+                "cancellable_sleep",
+                wrap_pyfunction!(cancellable_sleep, gil).unwrap(),
+            ),
+        ]
+        .into_py_dict(gil)
+        .unwrap();
+        let test = r#"
+        import trio
+
+        async def main(sleep=sleep, sleep_0=sleep_0, cancellable_sleep=cancellable_sleep):
+            import trio
+            assert await sleep(0.01) == 42
+            assert await sleep_0() == 42
+            with trio.move_on_after(0.01) as scope:
+                await sleep(999)
+            assert scope.cancelled_caught
+            with trio.move_on_after(0.01):
+                assert await cancellable_sleep(999) == 0
+            return "done"
+
+        assert trio.run(main) == "done"
+        "#;
+        py_run!(gil, *locals, test);
+    })
+}
+
+#[test]
+fn async_context_manager() {
+    #[pyfunction]
+    async fn run_in_context(manager: PyObject, fail: bool) -> PyResult<Option<String>> {
+        Python::with_gil(|py| {
+            manager.bind(py).async_with_context(|value| async move {
+                sleep(0.01).await;
+                if fail {
+                    return Err(pyo3::exceptions::PyValueError::new_err("body failed"));
+                }
+                Python::with_gil(|py| value.extract::<String>(py))
+            })
+        })
+        .await
+    }
+
+    Python::with_gil(|gil| {
+        let run_in_context = wrap_pyfunction!(run_in_context, gil).unwrap();
+        let test = r#"
+        import asyncio
+
+        class Manager:
+            def __init__(self, suppress=False):
+                self.suppress = suppress
+                self.events = []
+
+            async def __aenter__(self):
+                import asyncio
+                await asyncio.sleep(0)
+                self.events.append("enter")
+                return "value"
+
+            async def __aexit__(self, exc_type, exc, tb):
+                import asyncio
+                await asyncio.sleep(0)
+                self.events.append(exc_type)
+                return self.suppress
+
+        manager = Manager()
+        assert asyncio.run(run_in_context(manager, False)) == "value"
+        assert manager.events == ["enter", None]
+
+        manager = Manager(suppress=True)
+        assert asyncio.run(run_in_context(manager, True)) is None
+        assert manager.events == ["enter", ValueError]
+
+        manager = Manager()
+        try:
+            asyncio.run(run_in_context(manager, True))
+        except ValueError as err:
+            assert str(err) == "body failed"
+        else:
+            assert False
+        assert manager.events == ["enter", ValueError]
+
+        try:
+            asyncio.run(run_in_context(object(), False))
+        except TypeError as err:
+            assert str(err) == "'object' object does not support the asynchronous context manager protocol"
+        else:
+            assert False
+
+        # cancelling the body awaits `__aexit__` with the cancellation, which is then reraised
+        async def cancel_body(manager, run_in_context=run_in_context):
+            import asyncio
+            task = asyncio.ensure_future(run_in_context(manager, False))
+            await asyncio.sleep(0.001)
+            assert manager.events == ["enter"]
+            task.cancel()
+            try:
+                return await task
+            except asyncio.CancelledError:
+                return "cancelled"
+
+        manager = Manager()
+        assert asyncio.run(cancel_body(manager)) == "cancelled"
+        assert manager.events == ["enter", asyncio.CancelledError]
+
+        manager = Manager(suppress=True)
+        assert asyncio.run(cancel_body(manager)) is None
+        assert manager.events == ["enter", asyncio.CancelledError]
+        "#;
+        py_run!(gil, run_in_context, &handle_windows(test));
+    })
+}
+
+#[test]
+fn trio_async_context_manager() {
+    #[pyfunction]
+    async fn run_in_context(manager: PyObject) -> PyResult<Option<String>> {
+        Python::with_gil(|py| {
+            manager.bind(py).async_with_context(|value| async move {
+                sleep(0.01).await;
+                Python::with_gil(|py| value.extract::<String>(py))
+            })
+        })
+        .await
+    }
+
+    Python::with_gil(|gil| {
+        if gil.import("trio").is_err() {
+            return;
+        }
+        let run_in_context = wrap_pyfunction!(run_in_context, gil).unwrap();
+        let test = r#"
+        import trio
+
+        class Manager:
+            def __init__(self):
+                self.events = []
+
+            async def __aenter__(self):
+                import trio
+                await trio.sleep(0)
+                self.events.append("enter")
+                return "value"
+
+            async def __aexit__(self, exc_type, exc, tb):
+                import trio
+                self.events.append(exc_type)
+                await trio.sleep(0)
+
+        async def main(run_in_context=run_in_context):
+            import trio
+            manager = Manager()
+            assert await run_in_context(manager) == "value"
+            assert manager.events == ["enter", None]
+
+            # the cancellation of the body is seen by `__aexit__`
+            manager = Manager()
+            with trio.move_on_after(0.001) as scope:
+                await run_in_context(manager)
+            assert scope.cancelled_caught
+            assert manager.events == ["enter", trio.Cancelled]
+            return "done"
+
+        assert trio.run(main) == "done"
+        "#;
+        py_run!(gil, run_in_context, test);
+    })
+}
+
+#[test]
+fn custom_async_library() {
+    thread_local! {
+        static RUNNING: Cell<bool> = const { Cell::new(false) };
+    }
+    static WAKES: AtomicUsize = AtomicUsize::new(0);
+
+    struct CustomLibrary;
+
+    impl AsyncLibrary for CustomLibrary {
+        fn is_running(&self, _py: Python<'_>) -> bool {
+            RUNNING.with(Cell::get)
+        }
+
+        fn waker(&self, _py: Python<'_>) -> PyResult<Box<dyn CoroutineWaker>> {
+            Ok(Box::new(CountingWaker(AsyncioWaker::new())))
+        }
+    }
+
+    struct CountingWaker(AsyncioWaker);
+
+    impl CoroutineWaker for CountingWaker {
+        fn reset(&mut self) {
+            self.0.reset()
+        }
+
+        fn suspend(&self, py: Python<'_>) -> PyResult<PyObject> {
+            self.0.suspend(py)
+        }
+
+        fn wake(&self) {
+            WAKES.fetch_add(1, Ordering::Relaxed);
+            self.0.wake()
+        }
+    }
+
+    register_async_library(CustomLibrary);
+    Python::with_gil(|gil| {
+        let sleep = wrap_pyfunction!(sleep, gil).unwrap();
+        let test = r#"
+        import asyncio
+        assert asyncio.run(sleep(0.01)) == 42
+        "#;
+        // the library is only detected in this thread, while the flag is set
+        py_run!(gil, sleep, &handle_windows(test));
+        assert_eq!(WAKES.load(Ordering::Relaxed), 0);
+        RUNNING.with(|running| running.set(true));
+        py_run!(gil, sleep, &handle_windows(test));
+        RUNNING.with(|running| running.set(false));
+        assert_eq!(WAKES.load(Ordering::Relaxed), 1);
+    })
+}
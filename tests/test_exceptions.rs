@@ -83,6 +83,44 @@ fn test_custom_error() {
     });
 }
 
+#[cfg(Py_3_11)]
+#[pyfunction]
+fn validate(values: Vec<i64>) -> Result<(), Vec<PyErr>> {
+    let errors: Vec<PyErr> = values
+        .into_iter()
+        .filter(|value| *value < 0)
+        .map(|value| exceptions::PyValueError::new_err(format!("{} is negative", value)))
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(Py_3_11)]
+#[test]
+fn test_return_exception_group() {
+    Python::with_gil(|py| {
+        let validate = wrap_pyfunction!(validate)(py).unwrap();
+
+        py_run!(
+            py,
+            validate,
+            r#"
+        validate([1, 2])
+        try:
+            validate([-1, 2, -3])
+        except* ValueError as eg:
+            assert isinstance(eg, ExceptionGroup)
+            assert [str(e) for e in eg.exceptions] == ["-1 is negative", "-3 is negative"]
+        else:
+            assert False
+        "#
+        );
+    });
+}
+
 #[test]
 fn test_exception_nosegfault() {
     use std::net::TcpListener;
@@ -98,6 +136,93 @@ fn test_exception_nosegfault() {
     assert!(parse_int().is_err());
 }
 
+#[cfg(not(any(Py_LIMITED_API, PyPy, GraalPy)))]
+#[pyfunction]
+#[pyo3(traceback)]
+fn traced_call(callback: &Bound<'_, PyAny>) -> PyResult<()> {
+    callback.call0()?;
+    Err(exceptions::PyValueError::new_err("failed in Rust"))
+}
+
+#[cfg(not(any(Py_LIMITED_API, PyPy, GraalPy)))]
+#[pyclass]
+struct Traced;
+
+#[cfg(not(any(Py_LIMITED_API, PyPy, GraalPy)))]
+#[pymethods]
+impl Traced {
+    #[new]
+    #[pyo3(traceback)]
+    fn new(fail: bool) -> PyResult<Self> {
+        if fail {
+            Err(exceptions::PyValueError::new_err("failed in #[new]"))
+        } else {
+            Ok(Traced)
+        }
+    }
+
+    #[pyo3(traceback)]
+    fn panics(&self) {
+        panic!("panicked in Rust");
+    }
+
+    #[pyo3(traceback)]
+    fn __call__(&self) -> PyResult<()> {
+        Err(exceptions::PyValueError::new_err("failed in __call__"))
+    }
+}
+
+#[test]
+#[cfg(not(any(Py_LIMITED_API, PyPy, GraalPy)))]
+fn test_rust_traceback_frames() {
+    Python::with_gil(|py| {
+        let traced_call = wrap_pyfunction!(traced_call)(py).unwrap();
+        let traced = py.get_type::<Traced>();
+        let file = file!();
+
+        py_run!(
+            py,
+            traced_call traced file,
+            r#"
+        def frames(callable, *args):
+            import traceback
+            try:
+                callable(*args)
+            except BaseException as e:
+                return [(f.name, f.filename) for f in traceback.extract_tb(e.__traceback__)][1:]
+            assert False
+
+        def ok():
+            pass
+
+        def fail():
+            raise KeyError("failed in Python")
+
+        assert frames(traced_call, ok) == [("traced_call", file)]
+        assert frames(traced_call, fail) == [("traced_call", file), ("fail", "<string>")]
+        assert frames(traced, True) == [("Traced::new", file)]
+        assert frames(traced(False).panics) == [("Traced::panics", file)]
+        assert frames(traced(False)) == [("Traced::__call__", file)]
+        "#
+        );
+    });
+}
+
+#[test]
+#[cfg(not(any(Py_LIMITED_API, PyPy, GraalPy)))]
+fn test_with_rust_frame() {
+    Python::with_gil(|py| {
+        let err =
+            exceptions::PyValueError::new_err("error").with_rust_frame(py, "inner", "inner.rs", 12);
+        let err = err.with_rust_frame(py, "outer", "outer.rs", 34);
+        let tb = err.traceback(py).unwrap();
+        assert_eq!(
+            tb.format().unwrap(),
+            "Traceback (most recent call last):\n  File \"outer.rs\", line 34, in outer\n  File \"inner.rs\", line 12, in inner\n"
+        );
+    });
+}
+
 #[test]
 #[cfg(all(Py_3_8, not(Py_GIL_DISABLED)))]
 fn test_write_unraisable() {
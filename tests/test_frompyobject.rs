@@ -538,6 +538,45 @@ TypeError: failed to extract enum Bar ('str | uint | int')
     });
 }
 
+#[cfg(Py_3_11)]
+#[test]
+fn test_enum_error_group() {
+    use pyo3::exceptions::{PyBaseExceptionGroup, PyTypeError};
+
+    Python::with_gil(|py| {
+        let dict = PyDict::new(py);
+        let err = dict.extract::<Bar>().unwrap_err();
+        assert!(err.is_instance_of::<PyTypeError>(py));
+
+        let group = err.cause(py).unwrap();
+        assert!(group.is_instance_of::<PyBaseExceptionGroup>(py));
+        let group = group.value(py);
+        assert_eq!(
+            group.getattr("message").unwrap().to_string(),
+            "failed to extract enum Bar"
+        );
+
+        let exceptions = group.getattr("exceptions").unwrap();
+        let notes = exceptions
+            .try_iter()
+            .unwrap()
+            .map(|e| {
+                let e = e.unwrap();
+                assert!(e.is_instance_of::<PyTypeError>());
+                e.getattr("__notes__").unwrap().extract::<Vec<String>>().unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            notes,
+            [
+                ["variant A (str)"],
+                ["variant B (uint)"],
+                ["variant C (int)"]
+            ]
+        );
+    });
+}
+
 #[derive(Debug, FromPyObject)]
 pub struct Zap {
     #[pyo3(item)]
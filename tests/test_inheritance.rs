@@ -175,8 +175,8 @@ except Exception as e:
     });
 }
 
-// Subclassing builtin types is not allowed in the LIMITED API.
-#[cfg(not(Py_LIMITED_API))]
+// Subclassing builtin types is not allowed in the LIMITED API before Python 3.12.
+#[cfg(any(not(Py_LIMITED_API), Py_3_12))]
 mod inheriting_native_type {
     use super::*;
     use pyo3::exceptions::PyException;
@@ -258,6 +258,37 @@ mod inheriting_native_type {
         })
     }
 
+    #[pyclass(extends=PyDict, dict, weakref)]
+    struct DictWithAttributes {}
+
+    #[pymethods]
+    impl DictWithAttributes {
+        #[new]
+        fn new() -> Self {
+            DictWithAttributes {}
+        }
+    }
+
+    #[test]
+    fn inherit_dict_with_dict_and_weakref() {
+        Python::with_gil(|py| {
+            let dict_sub = pyo3::Py::new(py, DictWithAttributes::new()).unwrap();
+            py_run!(
+                py,
+                dict_sub,
+                r#"
+                import weakref
+
+                dict_sub["key"] = 1
+                dict_sub.attr = 2
+                assert dict_sub == {"key": 1}
+                assert dict_sub.__dict__ == {"attr": 2}
+                assert weakref.ref(dict_sub)() is dict_sub
+                "#
+            );
+        });
+    }
+
     #[pyclass(extends=PyException)]
     struct CustomException {
         #[pyo3(get)]
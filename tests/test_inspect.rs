@@ -0,0 +1,169 @@
+#![cfg(all(feature = "macros", feature = "experimental-inspect"))]
+
+use pyo3::inspect::interface::{FunctionKind, ModuleInfo, ParameterKind};
+use pyo3::inspect::stubs::module_stub;
+use pyo3::inspect::types::TypeInfo;
+use pyo3::prelude::*;
+use pyo3::wrap_pymodule;
+
+/// A counter.
+#[pyclass]
+struct Counter {
+    #[pyo3(get, set)]
+    count: u64,
+}
+
+#[pymethods]
+impl Counter {
+    #[new]
+    #[pyo3(signature = (start = 0))]
+    fn new(start: u64) -> Self {
+        Counter { count: start }
+    }
+
+    /// Increments the counter.
+    fn increment(&mut self, step: u64) -> u64 {
+        self.count += step;
+        self.count
+    }
+
+    #[staticmethod]
+    fn zero() -> Self {
+        Counter { count: 0 }
+    }
+
+    #[getter]
+    fn is_zero(&self) -> bool {
+        self.count == 0
+    }
+}
+
+/// Adds two numbers.
+#[pyfunction]
+#[pyo3(signature = (a, b = 1, *, strict = false))]
+#[allow(clippy::unnecessary_wraps)]
+fn add(a: i64, b: i64, strict: bool) -> PyResult<i64> {
+    let _ = strict;
+    Ok(a + b)
+}
+
+#[pyfunction]
+fn names(prefix: Option<String>) -> Vec<String> {
+    prefix.into_iter().collect()
+}
+
+#[pyfunction]
+fn make_counter() -> Counter {
+    Counter { count: 0 }
+}
+
+#[pyfunction]
+fn count_of(counter: &Counter) -> u64 {
+    counter.count
+}
+
+#[pymodule]
+fn inspected(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Counter>()?;
+    m.add_function(wrap_pyfunction!(add, m)?)?;
+    m.add_function(wrap_pyfunction!(names, m)?)?;
+    m.add_function(wrap_pyfunction!(make_counter, m)?)?;
+    m.add_function(wrap_pyfunction!(count_of, m)?)?;
+    m.add("VERSION", "1.0")?;
+    Ok(())
+}
+
+#[test]
+fn test_module_info() {
+    Python::with_gil(|py| {
+        let module = wrap_pymodule!(inspected)(py);
+        let info = ModuleInfo::from_module(module.bind(py).downcast().unwrap()).unwrap();
+        assert_eq!(info.name, "inspected");
+
+        let add = info.functions.iter().find(|f| f.name == "add").unwrap();
+        assert_eq!(add.kind, FunctionKind::Function);
+        assert_eq!(add.doc.as_deref(), Some("Adds two numbers."));
+        let kinds: Vec<_> = add.parameters.iter().map(|p| p.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                ParameterKind::PositionalOrKeyword,
+                ParameterKind::PositionalOrKeyword,
+                ParameterKind::KeywordOnly
+            ]
+        );
+        assert_eq!(add.parameters[1].default.as_deref(), Some("1"));
+        assert_eq!(add.parameters[2].default.as_deref(), Some("False"));
+        assert_eq!(add.return_type.to_string(), "int");
+
+        let counter = info.classes.iter().find(|c| c.name == "Counter").unwrap();
+        assert_eq!(counter.doc.as_deref(), Some("A counter."));
+        let new = counter
+            .methods
+            .iter()
+            .find(|m| m.kind == FunctionKind::Constructor)
+            .unwrap();
+        assert_eq!(new.parameters[0].name, "start");
+        let zero = counter.methods.iter().find(|m| m.name == "zero").unwrap();
+        assert_eq!(zero.kind, FunctionKind::StaticMethod);
+        assert_eq!(zero.return_type.to_string(), "Counter");
+        let count = counter
+            .properties
+            .iter()
+            .find(|p| p.name == "count")
+            .unwrap();
+        assert!(count.writable);
+        let is_zero = counter
+            .properties
+            .iter()
+            .find(|p| p.name == "is_zero")
+            .unwrap();
+        assert!(!is_zero.writable);
+        assert_eq!(is_zero.annotation, TypeInfo::builtin("bool"));
+
+        let version = info
+            .attributes
+            .iter()
+            .find(|a| a.name == "VERSION")
+            .unwrap();
+        assert_eq!(version.annotation, TypeInfo::builtin("str"));
+    });
+}
+
+#[test]
+fn test_module_stub() {
+    Python::with_gil(|py| {
+        let module = wrap_pymodule!(inspected)(py);
+        let info = ModuleInfo::from_module(module.bind(py).downcast().unwrap()).unwrap();
+        assert_eq!(
+            module_stub(&info),
+            r#"from typing import List, Optional
+
+VERSION: str
+
+def add(a: int, b: int = 1, *, strict: bool = False) -> int:
+    """Adds two numbers."""
+
+def names(prefix: Optional[str]) -> List[str]: ...
+
+def make_counter() -> Counter: ...
+
+def count_of(counter: Counter) -> int: ...
+
+class Counter:
+    """A counter."""
+    @property
+    def count(self) -> int: ...
+    @count.setter
+    def count(self, value: int) -> None: ...
+    @property
+    def is_zero(self) -> bool: ...
+    def __new__(cls, start: int = 0) -> Counter: ...
+    def increment(self, step: int) -> int:
+        """Increments the counter."""
+    @staticmethod
+    def zero() -> Counter: ...
+"#
+        );
+    });
+}
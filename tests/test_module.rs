@@ -2,11 +2,13 @@
 
 use pyo3::prelude::*;
 
+use pyo3::exceptions::PyTypeError;
 use pyo3::py_run;
 use pyo3::types::PyString;
-use pyo3::types::{IntoPyDict, PyDict, PyTuple};
+use pyo3::types::{IntoPyDict, PyDict, PyModuleState, PyTuple};
 use pyo3::BoundObject;
 use pyo3_ffi::c_str;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[path = "../src/tests/common.rs"]
 mod common;
@@ -476,6 +478,180 @@ fn test_module_functions_with_module() {
     });
 }
 
+#[derive(Debug, Default)]
+struct CounterState {
+    count: AtomicUsize,
+}
+
+impl PyModuleState for CounterState {}
+
+#[pyfunction]
+#[pyo3(pass_module)]
+fn increment(module: &Bound<'_, PyModule>) -> PyResult<usize> {
+    let state = module.state::<CounterState>()?;
+    Ok(state.count.fetch_add(1, Ordering::Relaxed) + 1)
+}
+
+#[pymodule(state = CounterState)]
+fn module_with_state(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(increment, m)?)
+}
+
+#[test]
+fn test_module_state() {
+    Python::with_gil(|py| {
+        let m = pyo3::wrap_pymodule!(module_with_state)(py);
+        py_assert!(py, m, "m.increment() == 1");
+        py_assert!(py, m, "m.increment() == 2");
+        assert_eq!(
+            m.bind(py)
+                .downcast::<PyModule>()
+                .unwrap()
+                .state::<CounterState>()
+                .unwrap()
+                .count
+                .load(Ordering::Relaxed),
+            2
+        );
+
+        // each module object has its own state
+        #[cfg(all(
+            not(any(PyPy, GraalPy)),
+            Py_3_9,
+            not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+        ))]
+        {
+            let other = pyo3::wrap_pymodule!(module_with_state)(py);
+            py_assert!(py, other, "other.increment() == 1");
+            py_assert!(py, m, "m.increment() == 3");
+        }
+    });
+}
+
+#[cfg(all(
+    any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+    not(any(PyPy, GraalPy))
+))]
+#[pyclass(subclass)]
+struct StateReader;
+
+#[cfg(all(
+    any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+    not(any(PyPy, GraalPy))
+))]
+#[pymethods]
+impl StateReader {
+    #[new]
+    fn new() -> Self {
+        StateReader
+    }
+
+    fn count(slf: &Bound<'_, Self>) -> PyResult<usize> {
+        let module = slf.get_type().module_with_state::<CounterState>()?;
+        Ok(module
+            .state::<CounterState>()?
+            .count
+            .load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(all(
+    any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+    not(any(PyPy, GraalPy))
+))]
+#[pymodule(state = CounterState)]
+fn module_with_state_class(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(increment, m)?)?;
+    m.add_class::<StateReader>()
+}
+
+#[cfg(all(
+    any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+    not(any(PyPy, GraalPy))
+))]
+#[test]
+fn test_module_state_class() {
+    #[derive(Debug, Default)]
+    struct OtherState;
+
+    impl PyModuleState for OtherState {}
+
+    Python::with_gil(|py| {
+        // the class is associated with the first module it is added to
+        let m = pyo3::wrap_pymodule!(module_with_state_class)(py);
+        let other = pyo3::wrap_pymodule!(module_with_state_class)(py);
+        py_run!(
+            py,
+            m other,
+            r#"
+            assert m.increment() == 1
+            assert m.increment() == 2
+            assert other.increment() == 1
+            assert m.StateReader is other.StateReader
+            assert m.StateReader().count() == 2
+            assert other.StateReader().count() == 2
+
+            class Sub(m.StateReader):
+                pass
+
+            assert Sub().count() == 2
+            "#
+        );
+
+        // instances created from Rust have the type of the module
+        let m = m.bind(py).downcast::<PyModule>().unwrap();
+        let reader = Bound::new(py, StateReader).unwrap();
+        assert!(reader.get_type().is(&m.getattr("StateReader").unwrap()));
+        assert!(reader
+            .get_type()
+            .module_with_state::<CounterState>()
+            .unwrap()
+            .is(m));
+        assert_eq!(
+            reader
+                .call_method0("count")
+                .unwrap()
+                .extract::<usize>()
+                .unwrap(),
+            2
+        );
+
+        let err = reader
+            .get_type()
+            .module_with_state::<OtherState>()
+            .unwrap_err();
+        assert!(err.is_instance_of::<PyTypeError>(py));
+        assert_eq!(
+            err.value(py).to_string(),
+            "type 'StateReader' is not associated with a module with state `test_module::test_module_state_class::OtherState`"
+        );
+    });
+}
+
+#[test]
+fn test_module_state_errors() {
+    #[derive(Debug, Default)]
+    struct OtherState;
+
+    impl PyModuleState for OtherState {}
+
+    Python::with_gil(|py| {
+        let m = pyo3::wrap_pymodule!(module_with_state)(py);
+        let m = m.bind(py).downcast::<PyModule>().unwrap();
+        let err = m.state::<OtherState>().unwrap_err();
+        assert!(err.is_instance_of::<PyTypeError>(py));
+
+        let m = pyo3::wrap_pymodule!(module_with_functions_with_module)(py);
+        let m = m.bind(py).downcast::<PyModule>().unwrap();
+        let err = m.state::<CounterState>().unwrap_err();
+        assert!(err.is_instance_of::<PyTypeError>(py));
+        assert_eq!(
+            err.value(py).to_string(),
+            "module 'module_with_functions_with_module' has no PyO3 module state"
+        );
+    });
+}
+
 #[test]
 fn test_module_doc_hidden() {
     #[doc(hidden)]
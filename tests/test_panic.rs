@@ -0,0 +1,130 @@
+#![cfg(feature = "macros")]
+
+//! The backtrace hook is installed for the whole process, so these tests are kept in their own
+//! test binary.
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::panic::{PanicException, PanicPolicy};
+use pyo3::prelude::*;
+use pyo3::{py_run, wrap_pymodule};
+
+#[pyfunction]
+fn do_panic() {
+    panic!("panic in do_panic");
+}
+
+fn to_runtime_error(py: Python<'_>, err: PyErr) -> PyErr {
+    let converted = PyRuntimeError::new_err("Rust code panicked");
+    converted.set_cause(py, Some(err));
+    converted
+}
+
+#[cfg(all(
+    any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+    not(any(PyPy, GraalPy))
+))]
+#[pyclass]
+struct Panicking;
+
+#[cfg(all(
+    any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+    not(any(PyPy, GraalPy))
+))]
+#[pymethods]
+impl Panicking {
+    #[new]
+    fn new() -> Self {
+        Self
+    }
+
+    fn do_panic(&self) {
+        panic!("panic in Panicking::do_panic");
+    }
+}
+
+#[pymodule(panic_policy = PanicPolicy::Custom(to_runtime_error))]
+fn panicking(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    #[cfg(all(
+        any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+        not(any(PyPy, GraalPy))
+    ))]
+    m.add_class::<Panicking>()?;
+    m.add_function(wrap_pyfunction!(do_panic, m)?)
+}
+
+#[pymodule]
+fn raising(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(do_panic, m)?)
+}
+
+#[test]
+fn test_panic_policy_and_backtrace() {
+    // Backtraces are captured if they are enabled when the module is initialized
+    std::env::set_var("RUST_BACKTRACE", "1");
+    Python::with_gil(|py| {
+        let module = wrap_pymodule!(panicking)(py).into_bound(py);
+
+        let err = module.call_method0("do_panic").unwrap_err();
+        assert!(err.is_instance_of::<PyRuntimeError>(py));
+        let panic = err.cause(py).unwrap();
+        assert!(panic.is_instance_of::<PanicException>(py));
+        assert!(panic.value(py).to_string().starts_with("panic in do_panic"));
+
+        let backtrace = panic.value(py).getattr("backtrace").unwrap();
+        #[cfg(rustc_has_backtrace)]
+        {
+            let backtrace: String = backtrace.extract().unwrap();
+            assert!(backtrace.contains("do_panic"));
+            let notes = panic.notes(py).unwrap();
+            assert_eq!(notes, [format!("Rust backtrace:\n{}", backtrace)]);
+        }
+        #[cfg(not(rustc_has_backtrace))]
+        assert!(backtrace.is_none());
+    });
+}
+
+#[test]
+fn test_panic_policy_is_per_module() {
+    Python::with_gil(|py| {
+        let panicking = wrap_pymodule!(panicking)(py).into_bound(py);
+        let raising = wrap_pymodule!(raising)(py).into_bound(py);
+
+        // fetching a `PanicException` in Rust resumes the panic, so it is caught in Python
+        let panic_exception = py.get_type::<PanicException>();
+        py_run!(
+            py,
+            raising panic_exception,
+            r#"
+try:
+    raising.do_panic()
+except BaseException as e:
+    assert type(e) is panic_exception
+else:
+    assert False
+"#
+        );
+
+        let err = panicking.call_method0("do_panic").unwrap_err();
+        assert!(err.is_instance_of::<PyRuntimeError>(py));
+    });
+}
+
+#[cfg(all(
+    any(Py_3_10, all(Py_3_9, not(Py_LIMITED_API))),
+    not(any(PyPy, GraalPy))
+))]
+#[test]
+fn test_panic_policy_of_class() {
+    Python::with_gil(|py| {
+        let module = wrap_pymodule!(panicking)(py).into_bound(py);
+        let instance = module.getattr("Panicking").unwrap().call0().unwrap();
+
+        let err = instance.call_method0("do_panic").unwrap_err();
+        assert!(err.is_instance_of::<PyRuntimeError>(py));
+        let panic = err.cause(py).unwrap();
+        assert!(panic
+            .value(py)
+            .to_string()
+            .starts_with("panic in Panicking::do_panic"));
+    });
+}
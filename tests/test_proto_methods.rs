@@ -416,6 +416,79 @@ fn callable() {
     });
 }
 
+#[pyclass(subclass)]
+struct CallableWithSignature {
+    factor: i32,
+}
+
+#[pymethods]
+impl CallableWithSignature {
+    #[pyo3(signature = (a, b = 1, *args, **kwargs))]
+    fn __call__(
+        &self,
+        a: i32,
+        b: i32,
+        args: Vec<i32>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> (i32, Vec<i32>, usize) {
+        (
+            a * b * self.factor,
+            args,
+            kwargs.map_or(0, |kwargs| kwargs.len()),
+        )
+    }
+}
+
+#[pyclass(extends = CallableWithSignature)]
+struct CallableSubclass;
+
+#[test]
+fn callable_with_signature() {
+    Python::with_gil(|py| {
+        let c = Py::new(py, Callable).unwrap();
+        let s = Py::new(py, CallableWithSignature { factor: 2 }).unwrap();
+        let sub = Py::new(
+            py,
+            PyClassInitializer::from(CallableWithSignature { factor: 3 })
+                .add_subclass(CallableSubclass),
+        )
+        .unwrap();
+        py_run!(
+            py,
+            c s sub,
+            r#"
+            assert c(arg=7) == 42
+            assert s(2) == (4, [], 0)
+            assert s(2, 3, 4, 5) == (12, [4, 5], 0)
+            assert s(2, b=3, c=4) == (12, [], 1)
+            assert sub(2, b=3, c=4) == (18, [], 1)
+            try:
+                c()
+            except TypeError as e:
+                assert "missing 1 required positional argument: 'arg'" in str(e)
+            else:
+                assert False, "expected a TypeError"
+            "#
+        );
+    });
+}
+
+#[test]
+#[cfg(all(Py_3_9, not(any(Py_LIMITED_API, PyPy, GraalPy))))]
+fn callable_uses_vectorcall() {
+    Python::with_gil(|py| {
+        let c = Py::new(py, Callable).unwrap();
+        assert!(unsafe { pyo3::ffi::PyVectorcall_Function(c.as_ptr()) }.is_some());
+
+        // subclassable classes keep using `tp_call`
+        let s = Py::new(py, CallableWithSignature { factor: 2 }).unwrap();
+        assert!(unsafe { pyo3::ffi::PyVectorcall_Function(s.as_ptr()) }.is_none());
+
+        let result = c.call1(py, (7,)).unwrap();
+        assert_eq!(result.extract::<i32>(py).unwrap(), 42);
+    });
+}
+
 #[pyclass]
 #[derive(Debug)]
 struct SetItem {
@@ -0,0 +1,148 @@
+#![cfg(all(
+    feature = "macros",
+    not(any(PyPy, GraalPy)),
+    Py_3_9,
+    not(all(windows, Py_LIMITED_API, not(Py_3_10)))
+))]
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyImportError};
+use pyo3::interpreter::PySubinterpreter;
+use pyo3::prelude::*;
+use pyo3::sync::InterpreterOnceCell;
+use pyo3::types::PyList;
+use pyo3::{ffi, wrap_pymodule};
+
+create_exception!(isolated, IsolatedError, PyException);
+
+#[pyclass]
+struct Point {
+    #[pyo3(get)]
+    x: i32,
+}
+
+#[pyfunction]
+fn make_point(x: i32) -> Point {
+    Point { x }
+}
+
+#[pyfunction]
+fn raise_isolated() -> PyResult<()> {
+    Err(IsolatedError::new_err("raised"))
+}
+
+#[pymodule(multiple_interpreters = true)]
+fn isolated(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Point>()?;
+    m.add_function(wrap_pyfunction!(make_point, m)?)?;
+    m.add_function(wrap_pyfunction!(raise_isolated, m)?)?;
+    m.add("IsolatedError", m.py().get_type::<IsolatedError>())
+}
+
+#[pymodule]
+#[allow(clippy::unnecessary_wraps)]
+fn not_isolated(_m: &Bound<'_, PyModule>) -> PyResult<()> {
+    Ok(())
+}
+
+// Creating a subinterpreter makes `PyGILState_Check` always return 1, so these tests are kept out
+// of the library unit tests.
+
+#[test]
+fn test_subinterpreter_is_isolated() {
+    Python::with_gil(|py| {
+        let sys = py.import("sys").unwrap();
+        sys.setattr("pyo3_test_attribute", 1).unwrap();
+        let interpreter = PySubinterpreter::new(py).unwrap();
+        // SAFETY: no Python object leaves the closure
+        let (id, has_attribute) = unsafe {
+            interpreter.with_gil(|py| {
+                let id = ffi::PyInterpreterState_GetID(ffi::PyInterpreterState_Get());
+                let sys = py.import("sys").unwrap();
+                (id, sys.hasattr("pyo3_test_attribute").unwrap())
+            })
+        };
+        assert_eq!(id, interpreter.id());
+        assert_ne!(id, 0);
+        assert!(!has_attribute);
+        sys.delattr("pyo3_test_attribute").unwrap();
+    });
+}
+
+#[test]
+fn test_interpreter_once_cell() {
+    static CELL: InterpreterOnceCell<Py<PyList>> = InterpreterOnceCell::new();
+
+    Python::with_gil(|py| {
+        let main = CELL.get_or_init(py, || PyList::empty(py).unbind()).as_ptr() as usize;
+        let interpreter = PySubinterpreter::new(py).unwrap();
+        // SAFETY: the objects of the subinterpreter are only stored in `CELL`
+        let (first, second) = unsafe {
+            interpreter.with_gil(|py| {
+                assert!(CELL.get(py).is_none());
+                (
+                    CELL.get_or_init(py, || PyList::empty(py).unbind()).as_ptr() as usize,
+                    CELL.get_or_init(py, || PyList::empty(py).unbind()).as_ptr() as usize,
+                )
+            })
+        };
+        assert_eq!(first, second);
+        assert_ne!(first, main);
+        assert_eq!(CELL.get(py).unwrap().as_ptr() as usize, main);
+    });
+}
+
+/// Returns the addresses of the `Point` and `IsolatedError` types of the current interpreter.
+fn type_addresses(module: &Bound<'_, PyAny>) -> (usize, usize) {
+    let point = module.getattr("Point").unwrap();
+    let error = module.getattr("IsolatedError").unwrap();
+    (point.as_ptr() as usize, error.as_ptr() as usize)
+}
+
+#[test]
+fn test_module_in_subinterpreter() {
+    Python::with_gil(|py| {
+        let main_module = wrap_pymodule!(isolated)(py).into_bound(py);
+        let main_types = type_addresses(&main_module);
+
+        let interpreter = PySubinterpreter::new(py).unwrap();
+        // SAFETY: no Python object leaves the closure
+        let sub_types = unsafe {
+            interpreter.with_gil(|py| {
+                let module = wrap_pymodule!(isolated)(py).into_bound(py);
+                let point = module.call_method1("make_point", (3,)).unwrap();
+                assert!(point
+                    .is_instance(&module.getattr("Point").unwrap())
+                    .unwrap());
+                assert_eq!(point.getattr("x").unwrap().extract::<i32>().unwrap(), 3);
+                let err = module.call_method0("raise_isolated").unwrap_err();
+                assert!(err.is_instance_of::<IsolatedError>(py));
+                assert!(err
+                    .get_type(py)
+                    .is(&module.getattr("IsolatedError").unwrap()));
+                type_addresses(&module)
+            })
+        };
+
+        assert_ne!(main_types.0, sub_types.0);
+        assert_ne!(main_types.1, sub_types.1);
+        assert_eq!(type_addresses(&main_module), main_types);
+    });
+}
+
+#[test]
+fn test_module_without_multiple_interpreters() {
+    Python::with_gil(|py| {
+        // the module is first loaded in the main interpreter
+        let _ = wrap_pymodule!(not_isolated)(py);
+
+        let interpreter = py.new_subinterpreter().unwrap();
+        // SAFETY: no Python object leaves the closure
+        unsafe {
+            interpreter.with_gil(|py| {
+                let err = not_isolated::_PYO3_DEF.make_module(py, true).unwrap_err();
+                assert!(err.is_instance_of::<PyImportError>(py));
+            })
+        };
+    });
+}
@@ -1,4 +1,4 @@
-//! With abi3, we cannot inherit native types.
+//! With abi3, we cannot inherit native types before Python 3.12.
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 
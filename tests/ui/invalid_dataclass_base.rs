@@ -0,0 +1,14 @@
+use pyo3::prelude::*;
+
+#[pyclass(subclass)]
+struct Base {
+    a: i32,
+}
+
+#[pyclass(dataclass, extends = Base)]
+#[derive(PartialEq)]
+struct DataclassExtendsPyClass {
+    b: i32,
+}
+
+fn main() {}
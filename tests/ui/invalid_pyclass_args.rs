@@ -181,4 +181,39 @@ impl Display for MyEnumInvalidStrFmt {
     }
 }
 
+#[pyclass(dataclass)]
+struct DataclassTupleStruct(i32);
+
+#[pyclass]
+struct DefaultWithoutDataclass {
+    #[pyo3(default)]
+    a: i32,
+}
+
+#[pyclass(dataclass)]
+#[derive(PartialEq)]
+struct DataclassFieldOrder {
+    #[pyo3(default = 1)]
+    a: i32,
+    b: i32,
+}
+
+#[pyclass(pickle(json))]
+struct PickleInvalidFormat {}
+
+#[pyclass(register = "Sized")]
+struct RegisterWithoutModule {}
+
+#[pyclass(abstract, abstract)]
+struct AbstractTwice {}
+
+#[pyclass(metaclass = "Meta")]
+struct MetaclassWithoutModule {}
+
+#[pyclass(extends = "mylib.Base", dict)]
+struct PythonBaseWithDict {}
+
+#[pyclass(extends = "mylib.Base", weakref)]
+struct PythonBaseWithWeakref {}
+
 fn main() {}
@@ -102,4 +102,19 @@ enum AllEnumVariantsDisabled {
     DisabledB,
 }
 
+#[pyclass(dataclass)]
+enum DataclassEnum {
+    Variant,
+}
+
+#[pyclass(pickle)]
+enum PickleEnum {
+    Variant,
+}
+
+#[pyclass(abstract)]
+enum AbstractEnum {
+    Variant,
+}
+
 fn main() {}
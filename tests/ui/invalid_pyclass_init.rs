@@ -0,0 +1,16 @@
+use pyo3::prelude::*;
+
+#[pyclass]
+struct InitWithoutNew {
+    value: i64,
+}
+
+#[pymethods]
+impl InitWithoutNew {
+    #[init]
+    fn init(&mut self, value: i64) {
+        self.value = value;
+    }
+}
+
+fn main() {}
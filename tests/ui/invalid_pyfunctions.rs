@@ -36,4 +36,12 @@ fn first_argument_not_module<'a, 'py>(
     module.name()
 }
 
+#[pyfunction]
+#[pyo3(local)]
+fn local_but_not_async() {}
+
+#[pyfunction]
+#[pyo3(abstractmethod)]
+fn abstract_function() {}
+
 fn main() {}
@@ -197,4 +197,69 @@ impl MyClass {
     macro_invocation!();
 }
 
+#[pymethods]
+impl MyClass {
+    #[getter(x)]
+    #[pyo3(traceback)]
+    fn traceback_on_getter(&self) {}
+}
+
+#[pymethods]
+impl MyClass {
+    #[pyo3(traceback)]
+    fn __repr__(&self) -> String {
+        String::new()
+    }
+}
+
+#[pymethods]
+impl MyClass {
+    #[staticmethod]
+    #[pyo3(abstractmethod)]
+    fn abstract_staticmethod() {}
+}
+
+#[pyclass]
+struct ConcreteClass {}
+
+#[pymethods]
+impl ConcreteClass {
+    #[pyo3(abstractmethod)]
+    fn abstract_method(&self) {}
+}
+
+#[pymethods]
+impl MyClass {
+    #[init]
+    fn init_without_receiver() {}
+}
+
+#[pymethods]
+impl MyClass {
+    #[init]
+    #[pyo3(name = "initialize")]
+    fn init_with_name(&self) {}
+}
+
+#[pymethods]
+impl MyClass {
+    #[init]
+    #[pyo3(text_signature = "()")]
+    fn init_with_text_signature(&self) {}
+}
+
+#[pymethods]
+impl MyClass {
+    #[init]
+    async fn async_init(&self) {}
+}
+
+#[pymethods]
+impl MyClass {
+    #[init]
+    fn init_returns_value(&self) -> i32 {
+        1
+    }
+}
+
 fn main() {}
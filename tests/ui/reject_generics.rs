@@ -10,4 +10,51 @@ struct ClassWithLifetimes<'a> {
     a: &'a str,
 }
 
+#[pyclass(generic(IntClass = OtherClass<i64>))]
+struct ClassWithWrongInstance<A> {
+    a: A,
+}
+
+#[pyclass(generic(IntClass = ClassWithMissingArgument<i64>))]
+struct ClassWithMissingArgument<A, B> {
+    a: A,
+    b: B,
+}
+
+#[pyclass(extends = pyo3::types::PyDict, generic(IntClass = ClassWithExtends<i64>))]
+struct ClassWithExtends<A> {
+    a: A,
+}
+
+#[pyclass]
+struct NotGeneric {
+    a: i64,
+}
+
+#[pymethods]
+impl<T> NotGeneric {
+    fn get(&self, _value: T) {}
+}
+
+struct NotAPyClass<A> {
+    a: A,
+}
+
+#[pymethods]
+impl<A> NotAPyClass<A> {
+    fn get(&self) {}
+}
+
+#[pyclass(generic(IntBounded = Bounded<i64>, StrBounded = Bounded<String>))]
+struct Bounded<T> {
+    value: T,
+}
+
+#[pymethods]
+impl<T: Copy> Bounded<T> {
+    fn get(&self) -> T {
+        self.value.clone()
+    }
+}
+
 fn main() {}